@@ -0,0 +1,31 @@
+#![feature(test)]
+
+extern crate rlox;
+extern crate test;
+
+use test::Bencher;
+
+fn run(b: &mut Bencher, code: &str, expected: &str) {
+    b.iter(|| {
+        let result = rlox::run_string(code.to_string());
+        assert_eq!(result.to_string(), expected);
+    });
+}
+
+// Function calls, and reading parameters and globals
+#[bench]
+fn fib(b: &mut Bencher) {
+    run(b, include_str!("fib.lox"), "6765\n");
+}
+
+// Reading and assigning locals of enclosing scopes
+#[bench]
+fn loops(b: &mut Bencher) {
+    run(b, include_str!("loops.lox"), "50868\n");
+}
+
+// Method calls, `this` and fields
+#[bench]
+fn methods(b: &mut Bencher) {
+    run(b, include_str!("methods.lox"), "9000\n15000\n");
+}
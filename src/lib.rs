@@ -4,6 +4,15 @@ extern crate lazy_static;
 #[macro_use]
 extern crate cfg_if;
 
+#[macro_use]
+extern crate serde_derive;
+extern crate serde;
+#[macro_use]
+extern crate serde_json;
+
+#[cfg(not(target_arch = "wasm32"))]
+extern crate rustyline;
+
 mod rlox;
 pub use rlox::api::*;
 pub use rlox::wasm::*;
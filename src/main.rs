@@ -1,26 +1,277 @@
 extern crate rlox;
 
 use std::env;
+use std::fs;
 use std::io;
+use std::io::{IsTerminal, Read};
+use std::process;
+
+use rlox::{Capabilities, Error, RunOptions};
+
+const USAGE: &str = "Usage: rlox [command] [arguments]
+
+Commands:
+    [run] [options] <script> [args...]   Run a script, passing it the arguments that follow
+    [run] [options] -e <code> [args...]  Run the given code
+    repl                                 Start the REPL, also the default without arguments
+    tokens <script>                      Print the tokens of a script
+    ast [--json] <script>                Print the syntax tree of a script, as JSON with --json
+    check <script>                       Look for errors in a script without running it
+    fmt [--write] <script>               Print a script formatted, or overwrite it with --write
+
+Scripts are read from stdin when given as `-`.
+
+Options for running code:
+    --error-format=json  Write errors to stderr as JSON, one per line
+    --opt                Optimize the code before running it";
+
+// From sysexits.h, like the exit codes of scripts themselves
+const EX_USAGE: i32 = 64;
+const EX_NOINPUT: i32 = 66;
+const EX_IOERR: i32 = 74;
 
 fn main() {
-    let mut args = env::args();
-    args.next();
+    let code = run(env::args().skip(1).collect());
+    process::exit(code);
+}
+
+fn run(args: Vec<String>) -> i32 {
+    let mut args = args.into_iter();
+    let command = args.next();
+
+    match command.as_ref().map(|command| command.as_str()) {
+        None => repl(),
+        Some("repl") if args.len() == 0 => repl(),
+        Some("repl") => usage(),
+        Some("run") => run_with_options(args.collect()),
+        Some("tokens") => inspect(args.collect(), rlox::tokens),
+        Some("ast") => ast(args.collect()),
+        Some("check") => check(args.collect()),
+        Some("fmt") => format(args.collect()),
+        Some("help") | Some("-h") | Some("--help") => {
+            println!("{}", USAGE);
+            0
+        }
+        // Running code without `run`, like `rlox --opt script.lox` or `rlox -e 'print 1;'`
+        Some(first) if first == "-" || first == "-e" || !first.starts_with('-')
+            || first.starts_with("--") =>
+        {
+            run_with_options(command.into_iter().chain(args).collect())
+        }
+        Some(_) => usage(),
+    }
+}
+
+fn usage() -> i32 {
+    eprintln!("{}", USAGE);
+    EX_USAGE
+}
+
+fn repl() -> i32 {
+    let stdin = io::stdin();
+
+    if stdin.is_terminal() {
+        return rlox::run_interactive_repl();
+    }
+
+    rlox::run_repl(&mut stdin.lock(), &mut io::stdout(), &mut io::stderr())
+}
+
+// The options for running code, followed by `-e <code>` or the script, and its arguments
+fn run_with_options(args: Vec<String>) -> i32 {
+    let mut json = false;
+    let mut optimize = false;
+    let mut args = args.into_iter().peekable();
+
+    while let Some(flag) = args.next_if(|arg| arg.starts_with("--")) {
+        match flag.as_str() {
+            "--error-format=json" => json = true,
+            "--error-format=text" => json = false,
+            "--opt" => optimize = true,
+            _ => return usage(),
+        }
+    }
+
+    // The script the code comes from, none for code given with `-e`
+    let (code, script) = match args.next() {
+        Some(ref flag) if flag == "-e" => match args.next() {
+            Some(code) => (code, None),
+            None => return usage(),
+        },
+        Some(script) => match read_script(&script) {
+            Ok(code) => (code, Some(script)),
+            Err(exit_code) => return exit_code,
+        },
+        None => return usage(),
+    };
+
+    // Code run from the command line can do anything, like reading files
+    let options = RunOptions {
+        capabilities: Capabilities::all(),
+        args: args.collect(),
+        optimize,
+        ..RunOptions::default()
+    };
+    let stdin = io::stdin();
+
+    if json {
+        let file = script.as_ref().filter(|script| *script != "-");
+
+        return rlox::run_source_with_json_errors(
+            file.map(|file| file.as_str()),
+            code,
+            options,
+            &mut io::stdout(),
+            &mut io::stderr(),
+            &mut stdin.lock(),
+        );
+    }
+
+    let source = match script {
+        Some(ref script) => describe(script),
+        None => "code given with -e".to_string(),
+    };
+
+    rlox::run_source_with_streams(
+        &source,
+        code,
+        options,
+        &mut io::stdout(),
+        &mut io::stderr(),
+        &mut stdin.lock(),
+    )
+}
+
+// `tokens` and `ast`, which print something about a single script
+fn inspect<F>(args: Vec<String>, f: F) -> i32
+where
+    F: FnOnce(String) -> Result<String, Vec<Error>>,
+{
+    let script = match single_script(&args) {
+        Some(script) => script,
+        None => return usage(),
+    };
+
+    let code = match read_script(script) {
+        Ok(code) => code,
+        Err(exit_code) => return exit_code,
+    };
+
+    match f(code) {
+        Ok(output) => {
+            print!("{}", output);
+            0
+        }
+        Err(errors) => report(script, &errors),
+    }
+}
 
-    let args: Vec<String> = args.collect();
+fn ast(args: Vec<String>) -> i32 {
+    match args.split_first() {
+        Some((flag, rest)) if flag == "--json" => inspect(rest.to_vec(), |code| {
+            rlox::ast_json(code).map(|json| format!("{}\n", json))
+        }),
+        _ => inspect(args, rlox::ast),
+    }
+}
 
-    if args.len() > 1 {
-        println!("Usage: rlox [script]");
-    } else if let Some(filename) = args.first() {
-        if let Err(errors) = rlox::run_file(filename, &mut io::stdout()) {
-            println!("Error running file {}\n", filename);
+fn check(args: Vec<String>) -> i32 {
+    let script = match single_script(&args) {
+        Some(script) => script,
+        None => return usage(),
+    };
 
-            for err in errors {
-                println!("{}", err);
+    let code = match read_script(script) {
+        Ok(code) => code,
+        Err(exit_code) => return exit_code,
+    };
+
+    match rlox::check(code) {
+        Ok(warnings) => {
+            for warning in warnings {
+                eprintln!("{}", warning);
             }
+
+            0
+        }
+        Err(errors) => report(script, &errors),
+    }
+}
+
+fn format(args: Vec<String>) -> i32 {
+    let (write, args) = match args.split_first() {
+        Some((flag, rest)) if flag == "--write" => (true, rest.to_vec()),
+        _ => (false, args),
+    };
+
+    let script = match single_script(&args) {
+        Some(script) if !(write && script == "-") => script,
+        _ => return usage(),
+    };
+
+    let code = match read_script(script) {
+        Ok(code) => code,
+        Err(exit_code) => return exit_code,
+    };
+
+    let formatted = match rlox::format_code(code) {
+        Ok(formatted) => formatted,
+        Err(errors) => return report(script, &errors),
+    };
+
+    if !write {
+        print!("{}", formatted);
+        return 0;
+    }
+
+    match fs::write(script, formatted) {
+        Ok(_) => 0,
+        Err(err) => {
+            eprintln!("Could not write {}: {}", script, err);
+            EX_IOERR
         }
+    }
+}
+
+fn single_script(args: &[String]) -> Option<&str> {
+    match args.len() {
+        1 => Some(args[0].as_str()),
+        _ => None,
+    }
+}
+
+fn read_script(script: &str) -> Result<String, i32> {
+    let mut code = String::new();
+
+    let result = if script == "-" {
+        io::stdin().read_to_string(&mut code)
     } else {
-        let stdin = io::stdin();
-        rlox::run_repl(&mut stdin.lock(), &mut io::stdout());
+        fs::File::open(script).and_then(|mut file| file.read_to_string(&mut code))
+    };
+
+    match result {
+        Ok(_) => Ok(code),
+        Err(err) => {
+            eprintln!("Could not read {}: {}", describe(script), err);
+            Err(EX_NOINPUT)
+        }
     }
 }
+
+fn describe(script: &str) -> String {
+    if script == "-" {
+        "stdin".to_string()
+    } else {
+        format!("file {}", script)
+    }
+}
+
+fn report(script: &str, errors: &[Error]) -> i32 {
+    eprintln!("Error in {}\n", describe(script));
+
+    for err in errors {
+        eprintln!("{}", err);
+    }
+
+    errors[0].exit_code()
+}
@@ -5,135 +5,452 @@ use std::cell::RefCell;
 use std::io::Read;
 use std::fs::File;
 
+use std::fmt;
+
 use rlox::scanner::Scanner;
-use rlox::parser::Parser;
-use rlox::errors::Error;
+use rlox::parser::{self, Stmt};
+pub use rlox::errors::{Error, ErrorReport, StackFrame};
 use rlox::interpreter::Interpreter;
-use rlox::resolver::Resolver;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::resolver;
+use rlox::optimizer;
+use rlox::formatter;
+use rlox::repl::{PlainLines, Repl};
+#[cfg(not(target_arch = "wasm32"))]
+use rlox::repl::LineEditor;
+use rlox::gc;
+pub use rlox::gc::GcStats;
+pub use rlox::callables::native::clock::{Clock, ManualClock, SystemClock};
+pub use rlox::capabilities::{Capabilities, Capability};
 
 pub fn run_file(path: &str, writer: &mut io::Write) -> Result<(), Vec<Error>> {
-    let mut f = File::open(path).expect("file not found");
-    let mut contents = String::new();
-    f.read_to_string(&mut contents)
-        .expect("something went wrong reading the file");
+    let contents = read_file(path);
 
     let writer = Rc::new(RefCell::new(writer));
     let mut interpreter = Interpreter::new(writer);
-    run(&mut interpreter, contents)
+    interpreter.set_capabilities(Capabilities::all());
+    run(&mut interpreter, contents, false)
 }
 
-pub fn run_repl<R: io::BufRead>(reader: &mut R, writer: &mut io::Write) {
-    println!("Welcome to the rlox prompt");
-    println!("^D to exit\n");
-
-    let writer = Rc::new(RefCell::new(writer));
-    let user_input = ReplIterator::new(reader, writer.clone());
-    let mut interpreter = Interpreter::new(writer.clone());
-
-    for input in user_input {
-        if let Err(errors) = run(&mut interpreter, input) {
-            writer
-                .borrow_mut()
-                .write_all(b"\n")
-                .expect("Error writing to stdout/writer");
-
-            for err in errors {
-                writer
-                    .borrow_mut()
-                    .write_all(format!("{}", err).as_ref())
-                    .expect("Error writing to stdout/writer");
-            }
+/// How to run a program. `RunOptions::default()` follows the system time, allows no
+/// capabilities, passes no arguments, gives no input and doesn't optimize the program. Set the
+/// fields that should be different, like
+/// `RunOptions { optimize: true, ..RunOptions::default() }`.
+pub struct RunOptions {
+    // What `clock()` and timers follow
+    pub clock: Rc<Clock>,
+    // What the program is allowed to do, like reading files
+    pub capabilities: Capabilities,
+    // What the `args()` native returns
+    pub args: Vec<String>,
+    // What `readLine()` and `input()` read, unless the entry point takes a reader
+    pub input: String,
+    // Whether to optimize the program before running it. See `optimizer::optimize` for what
+    // changes, which is never what the program does.
+    pub optimize: bool,
+}
 
-            writer
-                .borrow_mut()
-                .write_all(b"\n")
-                .expect("Error writing to stdout/writer");
+impl Default for RunOptions {
+    fn default() -> RunOptions {
+        RunOptions {
+            clock: Rc::new(SystemClock::new()),
+            capabilities: Capabilities::none(),
+            args: Vec::new(),
+            input: String::new(),
+            optimize: false,
         }
     }
 }
 
-/// Receive Lox code as a string, and return the contents of
-/// stdout after executing it as another string:
-pub fn run_string(code: String) -> String {
-    let output: Vec<u8> = Vec::new();
-    let mut cursor = Cursor::new(output);
+/// Same as `run_file`, with warnings and `eprint()` going to `error_writer`, and `readLine()`
+/// reading from `reader`. Errors are written to `error_writer`, and the result is the exit
+/// code of the program: 0 when it finishes, the one given to `exit()`, 65 for scanner or
+/// parser errors and 70 for runtime errors.
+pub fn run_file_with_streams<'a>(
+    path: &str,
+    options: RunOptions,
+    writer: &'a mut io::Write,
+    error_writer: &'a mut io::Write,
+    reader: &'a mut io::BufRead,
+) -> i32 {
+    let contents = read_file(path);
+    let source = format!("file {}", path);
+    run_source_with_streams(&source, contents, options, writer, error_writer, reader)
+}
 
-    let result = {
-        let mut writer = Rc::new(RefCell::new(&mut cursor as &mut io::Write));
-        let mut interpreter = Interpreter::new(writer.clone());
-        run(&mut interpreter, code)
+/// Same as `run_file_with_streams`, for code that doesn't come from a file. `source`
+/// describes where it comes from in error messages.
+pub fn run_source_with_streams<'a>(
+    source: &str,
+    code: String,
+    options: RunOptions,
+    writer: &'a mut io::Write,
+    error_writer: &'a mut io::Write,
+    reader: &'a mut io::BufRead,
+) -> i32 {
+    execute_with_streams(
+        code,
+        options,
+        writer,
+        error_writer,
+        reader,
+        |error_writer, errors, _| {
+            writeln!(error_writer, "Error running {}\n", source)?;
+
+            for err in errors.iter() {
+                writeln!(error_writer, "{}", err)?;
+            }
+
+            Ok(())
+        },
+    )
+}
+
+/// Same as `run_source_with_streams`, writing each error to `error_writer` as a line of JSON,
+/// for editors and CI tools. See `ErrorReport` for what it has. `file` is the one the code
+/// comes from, if any.
+pub fn run_source_with_json_errors<'a>(
+    file: Option<&str>,
+    code: String,
+    options: RunOptions,
+    writer: &'a mut io::Write,
+    error_writer: &'a mut io::Write,
+    reader: &'a mut io::BufRead,
+) -> i32 {
+    execute_with_streams(
+        code,
+        options,
+        writer,
+        error_writer,
+        reader,
+        |error_writer, errors, stack| {
+            for report in reports(errors, file, stack) {
+                writeln!(error_writer, "{}", report.to_json())?;
+            }
+
+            Ok(())
+        },
+    )
+}
+
+fn execute_with_streams<'a, F>(
+    code: String,
+    options: RunOptions,
+    writer: &'a mut io::Write,
+    error_writer: &'a mut io::Write,
+    reader: &'a mut io::BufRead,
+    report: F,
+) -> i32
+where
+    F: FnOnce(&mut io::Write, &[Error], &[StackFrame]) -> io::Result<()>,
+{
+    let error_writer = Rc::new(RefCell::new(error_writer));
+
+    let (result, stack) = {
+        let optimize = options.optimize;
+        let mut interpreter = interpreter(Rc::new(RefCell::new(writer)), options);
+        interpreter.set_error_writer(error_writer.clone());
+        interpreter.set_reader(Rc::new(RefCell::new(reader)));
+        let result = run(&mut interpreter, code, optimize);
+        (result, interpreter.stack_trace().to_vec())
     };
 
     match result {
-        Ok(_) => {
-            let output = cursor.get_ref().clone();
-            String::from_utf8(output).unwrap()
+        Ok(_) => 0,
+        Err(errors) => {
+            if !exited(&errors) {
+                let mut error_writer = error_writer.borrow_mut();
+                report(*error_writer, &errors, &stack)
+                    .expect("Error writing to stderr/error writer");
+            }
+
+            errors[0].exit_code()
         }
-        Err(errors) => errors
-            .iter()
-            .map(|error| error.to_string())
-            .collect::<Vec<String>>()
-            .join("\n"),
     }
 }
 
-fn run(interpreter: &mut Interpreter, code: String) -> Result<(), Vec<Error>> {
-    let scanner = Scanner::new(code);
-    let (tokens, scanner_errors) = scanner.scan_tokens();
-    let parser = Parser::new(tokens);
-    let ast = parser.ast();
+/// Runs a REPL reading lines from `reader`. Prompts and program output are written to
+/// `writer`, and errors to `error_writer`. Returns the exit code given to `exit()`, or 0 once
+/// there is no more input.
+pub fn run_repl<'a>(
+    reader: &'a mut io::BufRead,
+    writer: &'a mut io::Write,
+    error_writer: &'a mut io::Write,
+) -> i32 {
+    let reader = Rc::new(RefCell::new(reader));
+    let writer = Rc::new(RefCell::new(writer));
+    let mut lines = PlainLines::new(reader.clone(), writer.clone());
+    let mut repl = Repl::new(writer, Rc::new(RefCell::new(error_writer)), Some(reader));
+    repl.run(&mut lines)
+}
+
+/// Same as `run_repl`, for a user typing on a terminal, with line editing and a history that
+/// is kept between sessions. Falls back to reading stdin line by line if the terminal doesn't
+/// support line editing.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run_interactive_repl() -> i32 {
+    let mut lines = match LineEditor::new() {
+        Ok(lines) => lines,
+        Err(_) => {
+            let stdin = io::stdin();
+            return run_repl(&mut stdin.lock(), &mut io::stdout(), &mut io::stderr());
+        }
+    };
+
+    let mut stdout = io::stdout();
+    let mut stderr = io::stderr();
+    let mut repl = Repl::new(
+        Rc::new(RefCell::new(&mut stdout)),
+        Rc::new(RefCell::new(&mut stderr)),
+        None,
+    );
+    repl.run(&mut lines)
+}
+
+/// What running a program with `run_string` gave. `exit_code` is the same one
+/// `run_file_with_streams` returns.
+#[derive(Debug)]
+pub struct RunResult {
+    pub output: String,
+    // Errors that stopped the program, empty if it finished or called `exit()`
+    pub errors: Vec<Error>,
+    // Calls that were running when a runtime error happened, innermost first
+    pub stack: Vec<StackFrame>,
+    pub exit_code: i32,
+}
+
+impl RunResult {
+    /// The errors in a form meant for tools, see `ErrorReport`.
+    pub fn error_reports(&self) -> Vec<ErrorReport> {
+        reports(&self.errors, None, &self.stack)
+    }
+
+    /// The output, errors and exit code as a JSON object.
+    pub fn to_json(&self) -> String {
+        let result = json!({
+            "output": self.output,
+            "errors": self.error_reports(),
+            "exit_code": self.exit_code,
+        });
 
-    if scanner_errors.len() > 0 {
-        return Err(scanner_errors
-            .into_iter()
-            .map(|err| Error::Scanner(err))
-            .collect());
+        result.to_string()
     }
+}
+
+/// The errors, one per line, if there are any, otherwise the output.
+impl fmt::Display for RunResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.errors.is_empty() {
+            return f.write_str(&self.output);
+        }
+
+        let errors: Vec<String> = self.errors.iter().map(|error| error.to_string()).collect();
+        f.write_str(&errors.join("\n"))
+    }
+}
+
+/// Receive Lox code as a string, and run it, returning what it wrote to stdout along with the
+/// errors that stopped it. Its `to_string()` is the output, or the errors if there are any. The
+/// code can't use any capability, such as reading files:
+pub fn run_string(code: String) -> RunResult {
+    run_string_with_options(code, RunOptions::default())
+}
+
+/// Same as `run_string`, running the code as `options` say.
+pub fn run_string_with_options(code: String, options: RunOptions) -> RunResult {
+    execute_string(parser::parse(code), options)
+}
+
+/// Same as `run_string_with_options`, for a syntax tree given as JSON, like the one `ast_json`
+/// returns. It's resolved before running it.
+pub fn run_ast_json(json: &str, options: RunOptions) -> RunResult {
+    execute_string(parser::from_json(json), options)
+}
 
-    match ast {
-        Ok(mut ast) => {
-            let mut resolver = Resolver::new();
-            resolver.resolve_ast(&mut ast);
+/// What a program run by `run_string_with_error_output` wrote to its output, and to its error
+/// output separately. Warnings and errors go to the latter. `exit_code` is the same one
+/// `run_file_with_streams` returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Output {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+/// Same as `run_string_with_options`, keeping the output and error output of the program
+/// apart.
+pub fn run_string_with_error_output(code: String, options: RunOptions) -> Output {
+    let mut output: Vec<u8> = Vec::new();
+    let mut errors: Vec<u8> = Vec::new();
+    let mut input = Cursor::new(options.input.clone().into_bytes());
 
-            match interpreter.interpret(ast) {
-                Some(err) => Err(vec![Error::Runtime(err)]),
-                None => Ok(()),
+    let result = {
+        let optimize = options.optimize;
+        let mut interpreter = interpreter(Rc::new(RefCell::new(&mut output)), options);
+        interpreter.set_error_writer(Rc::new(RefCell::new(&mut errors)));
+        interpreter.set_reader(Rc::new(RefCell::new(&mut input)));
+        run(&mut interpreter, code, optimize)
+    };
+
+    let exit_code = match result {
+        Ok(_) => 0,
+        Err(run_errors) => {
+            if !exited(&run_errors) {
+                for error in run_errors.iter() {
+                    errors.extend(format!("{}\n", error).into_bytes());
+                }
             }
+
+            run_errors[0].exit_code()
         }
-        Err(errors) => Err(errors.into_iter().map(|err| Error::Parser(err)).collect()),
+    };
+
+    Output {
+        stdout: String::from_utf8(output).unwrap(),
+        stderr: String::from_utf8(errors).unwrap(),
+        exit_code,
     }
 }
 
-struct ReplIterator<'a, R: io::BufRead> {
-    reader: R,
-    writer: Rc<RefCell<&'a mut io::Write>>,
+fn execute_string(ast: Result<Vec<Stmt>, Vec<Error>>, options: RunOptions) -> RunResult {
+    let output: Vec<u8> = Vec::new();
+    let mut cursor = Cursor::new(output);
+    let mut input = Cursor::new(options.input.clone().into_bytes());
+
+    let (result, stack) = {
+        let optimize = options.optimize;
+        let writer = Rc::new(RefCell::new(&mut cursor as &mut io::Write));
+        let mut interpreter = interpreter(writer, options);
+        interpreter.set_reader(Rc::new(RefCell::new(&mut input)));
+        let result = ast.and_then(|ast| run_ast(&mut interpreter, ast, optimize));
+        (result, interpreter.stack_trace().to_vec())
+    };
+
+    let errors = result.err().unwrap_or_default();
+    let exit_code = errors.first().map_or(0, Error::exit_code);
+
+    RunResult {
+        output: String::from_utf8(cursor.into_inner()).unwrap(),
+        errors: if exited(&errors) { Vec::new() } else { errors },
+        stack,
+        exit_code,
+    }
+}
+
+// An interpreter writing to `writer`, set up as `options` say
+fn interpreter<'a>(writer: Rc<RefCell<&'a mut io::Write>>, options: RunOptions) -> Interpreter<'a> {
+    let mut interpreter = Interpreter::with_clock(writer, options.clock);
+    interpreter.set_capabilities(options.capabilities);
+    interpreter.set_args(options.args);
+    interpreter
+}
+
+/// Statistics of the garbage collector for the current thread, which is where every
+/// interpreter value created by it lives.
+pub fn gc_stats() -> GcStats {
+    gc::stats()
 }
 
-impl<'a, R: io::BufRead> ReplIterator<'a, R> {
-    fn new(reader: R, writer: Rc<RefCell<&'a mut io::Write>>) -> ReplIterator<R> {
-        ReplIterator { reader, writer }
+/// The tokens of `code`, one per line with the line they are on, their type and their lexeme.
+pub fn tokens(code: String) -> Result<String, Vec<Error>> {
+    let (tokens, scanner_errors) = Scanner::new(code).scan_tokens();
+
+    if !scanner_errors.is_empty() {
+        return Err(scanner_errors.into_iter().map(Error::Scanner).collect());
     }
+
+    Ok(tokens
+        .iter()
+        .map(|token| format!("{} {:?} {}\n", token.line, token.token_type, token.lexeme))
+        .collect())
 }
 
-impl<'a, R: io::BufRead> Iterator for ReplIterator<'a, R> {
-    type Item = String;
+/// The syntax tree of `code`, with a line for each top level statement.
+pub fn ast(code: String) -> Result<String, Vec<Error>> {
+    Ok(parser::parse(code)?
+        .iter()
+        .map(|stmt| format!("{}\n", stmt))
+        .collect())
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let mut input = String::new();
+/// The syntax tree of `code` as JSON, see `parser::to_json` for its format.
+pub fn ast_json(code: String) -> Result<String, Vec<Error>> {
+    Ok(parser::to_json(&parser::parse(code)?))
+}
 
-        self.writer
-            .borrow_mut()
-            .write_all(b">> ")
-            .expect("Error writing to stdout/writer");
-        self.writer
-            .borrow_mut()
-            .flush()
-            .expect("Error flushing stdout/writer");
+/// Scans, parses and resolves `code` without running it, and returns the warnings found.
+pub fn check(code: String) -> Result<Vec<String>, Vec<Error>> {
+    let mut ast = parser::parse(code)?;
+    resolver::resolve(&mut ast)
+}
 
-        match self.reader.read_line(&mut input) {
-            Ok(0) => None,
-            Ok(_) => Some(String::from(input.trim())),
-            Err(_) => panic!("Error reading input line"),
-        }
+/// Formats `code`, which has to scan and parse without errors. Comments are kept, see
+/// `formatter::format` for the rules.
+pub fn format_code(code: String) -> Result<String, Vec<Error>> {
+    parser::parse(code.clone())?;
+    let (tokens, _) = Scanner::new(code).scan_tokens_with_comments();
+    Ok(formatter::format(&tokens))
+}
+
+fn run(interpreter: &mut Interpreter, code: String, optimize: bool) -> Result<(), Vec<Error>> {
+    run_ast(interpreter, parser::parse(code)?, optimize)
+}
+
+fn run_ast(
+    interpreter: &mut Interpreter,
+    mut ast: Vec<Stmt>,
+    optimize: bool,
+) -> Result<(), Vec<Error>> {
+    for warning in resolver::resolve(&mut ast)? {
+        interpreter.warn(&warning);
+    }
+
+    if optimize {
+        ast = optimizer::optimize(ast);
     }
+
+    // Callbacks of timers and promises run once the program is done
+    let result = interpreter
+        .interpret(ast)
+        .or_else(|| interpreter.run_event_loop());
+
+    match result {
+        Some(err) => Err(vec![Error::Runtime(err)]),
+        None => Ok(()),
+    }
+}
+
+// Runtime errors get the stack trace of the interpreter that ran into them
+fn reports(errors: &[Error], file: Option<&str>, stack: &[StackFrame]) -> Vec<ErrorReport> {
+    errors
+        .iter()
+        .map(|error| {
+            let mut report = error.report(file);
+
+            if let Error::Runtime(_) = *error {
+                // Errors raised by natives don't know their token, but they happen in the call
+                // on top of the stack
+                if report.line.is_none() {
+                    report.line = stack.first().map(|frame| frame.line);
+                }
+
+                report.stack = Some(stack.to_vec());
+            }
+
+            report
+        })
+        .collect()
+}
+
+// Whether the program stopped by calling `exit()`, which isn't reported as an error
+fn exited(errors: &[Error]) -> bool {
+    matches!(errors.first(), Some(&Error::Runtime(RuntimeError::Exit(_))))
+}
+
+fn read_file(path: &str) -> String {
+    let mut f = File::open(path).expect("file not found");
+    let mut contents = String::new();
+    f.read_to_string(&mut contents)
+        .expect("something went wrong reading the file");
+    contents
 }
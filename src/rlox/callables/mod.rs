@@ -7,7 +7,55 @@ use rlox::lox_value::LoxValue;
 use rlox::interpreter::errors::RuntimeError;
 
 pub trait Callable: std::fmt::Debug {
-    fn arity(&self) -> usize;
+    fn arity(&self) -> Arity;
     fn call(&self, &mut Interpreter, Vec<LoxValue>) -> Result<LoxValue, RuntimeError>;
     fn as_any(&self) -> &Any; // TODO: Read https://stackoverflow.com/a/33687996/275442
 }
+
+/// Range of argument counts a callable accepts. `max` is `None` when it takes any number of
+/// extra arguments through a rest parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arity {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    pub fn exactly(count: usize) -> Arity {
+        Arity {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    pub fn between(min: usize, max: usize) -> Arity {
+        Arity {
+            min,
+            max: Some(max),
+        }
+    }
+
+    pub fn at_least(min: usize) -> Arity {
+        Arity { min, max: None }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min && match self.max {
+            Some(max) => count <= max,
+            None => true,
+        }
+    }
+}
+
+/// The argument counts with the word `argument`, like `1 argument` or `1 to 3 arguments`.
+impl std::fmt::Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.max {
+            Some(1) if self.min == 1 => f.write_str("1 argument"),
+            Some(max) if max == self.min => write!(f, "{} arguments", max),
+            Some(max) => write!(f, "{} to {} arguments", self.min, max),
+            None if self.min == 1 => f.write_str("at least 1 argument"),
+            None => write!(f, "at least {} arguments", self.min),
+        }
+    }
+}
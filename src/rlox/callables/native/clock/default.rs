@@ -1,8 +1,42 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-pub fn get_current_time() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
+use super::Clock;
+
+/// The real time of the machine. Sleeping blocks the thread.
+#[derive(Debug)]
+pub struct SystemClock {
+    // Reference point for `nanos()`, which unlike the system time never goes backwards
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> SystemClock {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64() * 1000.0
+    }
+
+    fn nanos(&self) -> f64 {
+        self.start.elapsed().as_nanos() as f64
+    }
+
+    fn sleep(&self, millis: f64) {
+        thread::sleep(Duration::from_millis(millis.ceil() as u64));
+    }
 }
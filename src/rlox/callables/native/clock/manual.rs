@@ -0,0 +1,37 @@
+use std::cell::Cell;
+
+use super::Clock;
+
+/// Clock that only moves when told to, so programs using time run instantly and always the
+/// same way. It stays fixed at its starting time unless advanced, or until a timer makes it
+/// sleep, which advances it by the given time.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Cell<f64>,
+}
+
+impl ManualClock {
+    pub fn new(start: f64) -> ManualClock {
+        ManualClock {
+            now: Cell::new(start),
+        }
+    }
+
+    pub fn advance(&self, millis: f64) {
+        self.now.set(self.now.get() + millis);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> f64 {
+        self.now.get()
+    }
+
+    fn nanos(&self) -> f64 {
+        self.now.get() * 1_000_000.0
+    }
+
+    fn sleep(&self, millis: f64) {
+        self.advance(millis);
+    }
+}
@@ -1,20 +1,36 @@
 use std::any::Any;
 
-use rlox::callables::Callable;
+use rlox::callables::{Arity, Callable};
 use rlox::interpreter::Interpreter;
 use rlox::interpreter::errors::RuntimeError;
 use rlox::lox_value::LoxValue;
 
+mod manual;
+
+pub use self::manual::ManualClock;
+
 cfg_if! {
     if #[cfg(target_arch = "wasm32")] {
         mod wasm;
-        use self::wasm::get_current_time;
+        pub use self::wasm::SystemClock;
     } else {
         mod default;
-        use self::default::get_current_time;
+        pub use self::default::SystemClock;
     }
 }
 
+/// Source of time for `clock()` and the timers of the event loop. Each interpreter has its
+/// own, so a fake one can be used to run programs deterministically.
+pub trait Clock {
+    /// Milliseconds since the Unix epoch.
+    fn now(&self) -> f64;
+    /// Nanoseconds since an arbitrary point in time. Unlike `now()`, it never goes backwards.
+    fn nanos(&self) -> f64;
+    /// Waits until the given number of milliseconds went by.
+    fn sleep(&self, millis: f64);
+}
+
+/// Returns the seconds since the Unix epoch, with a fractional part.
 #[derive(Debug)]
 pub struct ClockFunc {}
 
@@ -29,15 +45,72 @@ impl Callable for ClockFunc {
         self
     }
 
-    fn arity(&self) -> usize {
-        0
+    fn arity(&self) -> Arity {
+        Arity::exactly(0)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        _arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        Ok(LoxValue::Number(interpreter.clock().now() / 1000.0))
+    }
+}
+
+/// Returns the whole milliseconds since the Unix epoch.
+#[derive(Debug)]
+pub struct MillisFunc {}
+
+impl MillisFunc {
+    pub fn new() -> MillisFunc {
+        MillisFunc {}
+    }
+}
+
+impl Callable for MillisFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(0)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        _arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        Ok(LoxValue::Number(interpreter.clock().now().floor()))
+    }
+}
+
+/// Returns nanoseconds from a monotonic clock, only meaningful to measure the time between two
+/// calls.
+#[derive(Debug)]
+pub struct NanosFunc {}
+
+impl NanosFunc {
+    pub fn new() -> NanosFunc {
+        NanosFunc {}
+    }
+}
+
+impl Callable for NanosFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(0)
     }
 
     fn call(
         &self,
-        _interpreter: &mut Interpreter,
+        interpreter: &mut Interpreter,
         _arguments: Vec<LoxValue>,
     ) -> Result<LoxValue, RuntimeError> {
-        Ok(LoxValue::Number(get_current_time() as f64))
+        Ok(LoxValue::Number(interpreter.clock().nanos().floor()))
     }
 }
@@ -1,7 +1,32 @@
+use super::Clock;
+
 extern "C" {
-    fn get_current_js_time() -> u32;
+    // `Date.now()`
+    fn get_current_js_time() -> f64;
+    // `performance.now()`
+    fn get_monotonic_js_time() -> f64;
+}
+
+/// Time as reported by JavaScript.
+#[derive(Debug, Default)]
+pub struct SystemClock {}
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock {}
+    }
 }
 
-pub fn get_current_time() -> u64 {
-    unsafe { get_current_js_time() as u64 }
+impl Clock for SystemClock {
+    fn now(&self) -> f64 {
+        unsafe { get_current_js_time() }
+    }
+
+    fn nanos(&self) -> f64 {
+        unsafe { get_monotonic_js_time() * 1_000_000.0 }
+    }
+
+    // The main thread of the browser can't be blocked, timers just run in order without
+    // waiting for them
+    fn sleep(&self, _millis: f64) {}
 }
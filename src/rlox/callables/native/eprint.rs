@@ -0,0 +1,41 @@
+use std::any::Any;
+
+use rlox::callables::{Arity, Callable};
+use rlox::callables::native::stringify;
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+/// Prints a value like `print` does, to the error output of the interpreter instead.
+#[derive(Debug)]
+pub struct EprintFunc {}
+
+impl EprintFunc {
+    pub fn new() -> EprintFunc {
+        EprintFunc {}
+    }
+}
+
+impl Callable for EprintFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let value = arguments
+            .first()
+            .expect("Mismatched argument and parameter sizes");
+
+        let string = stringify(interpreter, value, "eprint")?;
+        interpreter.write_error(&string);
+        Ok(LoxValue::Nil)
+    }
+}
@@ -0,0 +1,114 @@
+use std::any::Any;
+
+use rlox::callables::{Arity, Callable};
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+const MILLIS_PER_DAY: i64 = 86_400_000;
+// Same range as JavaScript dates, 100 million days around the epoch
+const MAX_MILLIS: f64 = 8.64e15;
+const DEFAULT_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Formats a time given in seconds since the Unix epoch, like the ones returned by `clock()`,
+/// as a UTC date. The format defaults to `%Y-%m-%d %H:%M:%S`, and supports `%Y`, `%m`, `%d`,
+/// `%H`, `%M`, `%S`, `%L` for milliseconds and `%%`.
+#[derive(Debug)]
+pub struct FormatTimeFunc {}
+
+impl FormatTimeFunc {
+    pub fn new() -> FormatTimeFunc {
+        FormatTimeFunc {}
+    }
+}
+
+impl Callable for FormatTimeFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(1, 2)
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let millis = match arguments.first() {
+            Some(&LoxValue::Number(seconds)) if (seconds * 1000.0).abs() <= MAX_MILLIS => {
+                (seconds * 1000.0).floor() as i64
+            }
+            _ => return Err(invalid_argument("expected a time in seconds")),
+        };
+
+        let format = match arguments.get(1) {
+            Some(LoxValue::String(format)) => format.as_str(),
+            None => DEFAULT_FORMAT,
+            _ => return Err(invalid_argument("expected a format string")),
+        };
+
+        format_time(millis, format).map(LoxValue::String)
+    }
+}
+
+fn format_time(millis: i64, format: &str) -> Result<String, RuntimeError> {
+    let (year, month, day) = civil_from_days(millis.div_euclid(MILLIS_PER_DAY));
+    let time = millis.rem_euclid(MILLIS_PER_DAY);
+
+    let mut result = String::new();
+    let mut chars = format.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+
+        let field = match chars.next() {
+            Some('Y') => format!("{:04}", year),
+            Some('m') => format!("{:02}", month),
+            Some('d') => format!("{:02}", day),
+            Some('H') => format!("{:02}", time / 3_600_000),
+            Some('M') => format!("{:02}", time / 60_000 % 60),
+            Some('S') => format!("{:02}", time / 1000 % 60),
+            Some('L') => format!("{:03}", time % 1000),
+            Some('%') => "%".to_string(),
+            Some(other) => {
+                return Err(invalid_argument(&format!("unknown format `%{}`", other)))
+            }
+            None => return Err(invalid_argument("format can't end with `%`")),
+        };
+
+        result.push_str(&field);
+    }
+
+    Ok(result)
+}
+
+// Year, month and day of a number of days since 1970-01-01 in the proleptic Gregorian
+// calendar, from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months start in March, so the leap day is the last day of the year
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+fn invalid_argument(message: &str) -> RuntimeError {
+    RuntimeError::InvalidArgument("formatTime".to_string(), message.to_string())
+}
@@ -0,0 +1,135 @@
+use std::any::Any;
+use std::fs;
+
+use rlox::callables::{Arity, Callable};
+use rlox::capabilities::Capability;
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+/// Returns the contents of a file as a string.
+#[derive(Debug)]
+pub struct ReadFileFunc {}
+
+impl ReadFileFunc {
+    pub fn new() -> ReadFileFunc {
+        ReadFileFunc {}
+    }
+}
+
+impl Callable for ReadFileFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        interpreter.require("readFile", Capability::ReadFiles)?;
+        let path = string_argument("readFile", &arguments, 0, "a path")?;
+
+        fs::read_to_string(path)
+            .map(LoxValue::String)
+            .map_err(|err| RuntimeError::IoError("readFile".to_string(), err.to_string()))
+    }
+}
+
+/// Replaces the contents of a file with a string, creating it if needed.
+#[derive(Debug)]
+pub struct WriteFileFunc {}
+
+impl WriteFileFunc {
+    pub fn new() -> WriteFileFunc {
+        WriteFileFunc {}
+    }
+}
+
+impl Callable for WriteFileFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(2)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        interpreter.require("writeFile", Capability::WriteFiles)?;
+        let path = string_argument("writeFile", &arguments, 0, "a path")?;
+        let contents = string_argument("writeFile", &arguments, 1, "a string to write")?;
+
+        fs::write(path, contents)
+            .map(|_| LoxValue::Nil)
+            .map_err(|err| RuntimeError::IoError("writeFile".to_string(), err.to_string()))
+    }
+}
+
+/// Returns the sorted names of the entries in a directory.
+#[derive(Debug)]
+pub struct ListDirFunc {}
+
+impl ListDirFunc {
+    pub fn new() -> ListDirFunc {
+        ListDirFunc {}
+    }
+}
+
+impl Callable for ListDirFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        interpreter.require("listDir", Capability::ReadFiles)?;
+        let path = string_argument("listDir", &arguments, 0, "a path")?;
+        let io_error = |err: ::std::io::Error| {
+            RuntimeError::IoError("listDir".to_string(), err.to_string())
+        };
+
+        let mut names = Vec::new();
+
+        for entry in fs::read_dir(path).map_err(io_error)? {
+            let entry = entry.map_err(io_error)?;
+            names.push(entry.file_name().to_string_lossy().into_owned());
+        }
+
+        names.sort();
+
+        Ok(LoxValue::list(
+            names.into_iter().map(LoxValue::String).collect(),
+        ))
+    }
+}
+
+fn string_argument<'a>(
+    function: &str,
+    arguments: &'a [LoxValue],
+    index: usize,
+    expected: &str,
+) -> Result<&'a str, RuntimeError> {
+    match arguments.get(index) {
+        Some(LoxValue::String(string)) => Ok(string),
+        _ => Err(RuntimeError::InvalidArgument(
+            function.to_string(),
+            format!("expected {}", expected),
+        )),
+    }
+}
@@ -0,0 +1,35 @@
+use std::any::Any;
+
+use rlox::callables::{Arity, Callable};
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+use rlox::gc;
+
+/// Forces a garbage collection, returning the number of reclaimed objects.
+#[derive(Debug)]
+pub struct GcFunc {}
+
+impl GcFunc {
+    pub fn new() -> GcFunc {
+        GcFunc {}
+    }
+}
+
+impl Callable for GcFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(0)
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        _arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        Ok(LoxValue::Number(gc::collect() as f64))
+    }
+}
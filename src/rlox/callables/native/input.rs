@@ -0,0 +1,77 @@
+use std::any::Any;
+
+use rlox::callables::{Arity, Callable};
+use rlox::callables::native::stringify;
+use rlox::capabilities::Capability;
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+/// `readLine()` and `input(prompt)`, which writes the prompt first. They return the next line
+/// from the input of the interpreter, stdin by default, without its line ending, or `nil` at
+/// the end of the input.
+#[derive(Debug)]
+pub struct InputFunc {
+    kind: InputKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum InputKind {
+    ReadLine,
+    Input,
+}
+
+impl InputFunc {
+    pub fn read_line() -> InputFunc {
+        InputFunc { kind: InputKind::ReadLine }
+    }
+
+    pub fn input() -> InputFunc {
+        InputFunc { kind: InputKind::Input }
+    }
+
+    fn name(&self) -> &'static str {
+        match self.kind {
+            InputKind::ReadLine => "readLine",
+            InputKind::Input => "input",
+        }
+    }
+}
+
+impl Callable for InputFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        match self.kind {
+            InputKind::ReadLine => Arity::exactly(0),
+            InputKind::Input => Arity::between(0, 1),
+        }
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        interpreter.require(self.name(), Capability::Input)?;
+
+        if let Some(prompt) = arguments.first() {
+            let prompt = stringify(interpreter, prompt, self.name())?;
+            interpreter.write(&prompt);
+        }
+
+        let mut line = String::new();
+
+        match interpreter.read_line(&mut line) {
+            Ok(0) => Ok(LoxValue::Nil),
+            Ok(_) => {
+                let length = line.trim_end_matches(['\n', '\r']).len();
+                line.truncate(length);
+                Ok(LoxValue::String(line))
+            }
+            Err(err) => Err(RuntimeError::IoError(self.name().to_string(), err.to_string())),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::any::Any;
+
+use rlox::callables::{Arity, Callable};
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+/// Returns the number of characters in a string or of elements in a list.
+#[derive(Debug)]
+pub struct LenFunc {}
+
+impl LenFunc {
+    pub fn new() -> LenFunc {
+        LenFunc {}
+    }
+}
+
+impl Callable for LenFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let value = arguments
+            .first()
+            .expect("Mismatched argument and parameter sizes");
+
+        match *value {
+            LoxValue::String(ref string) => Ok(LoxValue::Number(string.chars().count() as f64)),
+            LoxValue::List(ref list) => Ok(LoxValue::Number(list.borrow().len() as f64)),
+            _ => Err(RuntimeError::InvalidArgument(
+                "len".to_string(),
+                "expected a string or a list".to_string(),
+            )),
+        }
+    }
+}
@@ -1,3 +1,43 @@
-mod clock;
+pub mod clock;
+mod eprint;
+mod format_time;
+mod fs;
+mod gc;
+mod input;
+mod len;
+mod process;
+mod string;
+mod timers;
 
-pub use self::clock::ClockFunc;
+pub use self::clock::{ClockFunc, MillisFunc, NanosFunc};
+pub use self::eprint::EprintFunc;
+pub use self::format_time::FormatTimeFunc;
+pub use self::fs::{ListDirFunc, ReadFileFunc, WriteFileFunc};
+pub use self::gc::GcFunc;
+pub use self::input::InputFunc;
+pub use self::len::LenFunc;
+pub use self::process::{ArgsFunc, ExitFunc, GetenvFunc};
+pub use self::string::StrFunc;
+pub use self::timers::{ClearTimerFunc, SetTimerFunc, SleepFunc};
+
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+// Converts an argument of the native `function` to a string, like `print` does. Natives have
+// no token to report an unusable `toString()` on, so it's an invalid argument of theirs.
+fn stringify(
+    interpreter: &mut Interpreter,
+    value: &LoxValue,
+    function: &str,
+) -> Result<String, RuntimeError> {
+    interpreter.stringify_or(value, &|class_name| {
+        Err(RuntimeError::InvalidArgument(
+            function.to_string(),
+            format!(
+                "`toString` of class `{}` must take no arguments and return a string",
+                class_name
+            ),
+        ))
+    })
+}
@@ -0,0 +1,124 @@
+use std::any::Any;
+use std::env;
+
+use rlox::callables::{Arity, Callable};
+use rlox::capabilities::Capability;
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+/// Returns the value of an environment variable, or `nil` if it isn't set.
+#[derive(Debug)]
+pub struct GetenvFunc {}
+
+impl GetenvFunc {
+    pub fn new() -> GetenvFunc {
+        GetenvFunc {}
+    }
+}
+
+impl Callable for GetenvFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        interpreter.require("getenv", Capability::Environment)?;
+
+        match arguments.first() {
+            Some(LoxValue::String(name)) => Ok(match env::var(name) {
+                Ok(value) => LoxValue::String(value),
+                Err(_) => LoxValue::Nil,
+            }),
+            _ => Err(RuntimeError::InvalidArgument(
+                "getenv".to_string(),
+                "expected the name of a variable".to_string(),
+            )),
+        }
+    }
+}
+
+/// Returns the command line arguments given to the program, as a list of strings.
+#[derive(Debug)]
+pub struct ArgsFunc {}
+
+impl ArgsFunc {
+    pub fn new() -> ArgsFunc {
+        ArgsFunc {}
+    }
+}
+
+impl Callable for ArgsFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(0)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        _arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        interpreter.require("args", Capability::Args)?;
+
+        let args = interpreter
+            .args()
+            .iter()
+            .map(|arg| LoxValue::String(arg.clone()))
+            .collect();
+
+        Ok(LoxValue::list(args))
+    }
+}
+
+/// Stops the program, with the given exit code or 0.
+#[derive(Debug)]
+pub struct ExitFunc {}
+
+impl ExitFunc {
+    pub fn new() -> ExitFunc {
+        ExitFunc {}
+    }
+}
+
+impl Callable for ExitFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(0, 1)
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let code = match arguments.first() {
+            Some(code) => match code.as_integer() {
+                Some(code) if (0..=255).contains(&code) => code as i32,
+                _ => {
+                    return Err(RuntimeError::InvalidArgument(
+                        "exit".to_string(),
+                        "expected an exit code between 0 and 255".to_string(),
+                    ))
+                }
+            },
+            None => 0,
+        };
+
+        Err(RuntimeError::Exit(code))
+    }
+}
@@ -0,0 +1,39 @@
+use std::any::Any;
+
+use rlox::callables::{Arity, Callable};
+use rlox::callables::native::stringify;
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+/// Converts any value to a string, the same way `print` does.
+#[derive(Debug)]
+pub struct StrFunc {}
+
+impl StrFunc {
+    pub fn new() -> StrFunc {
+        StrFunc {}
+    }
+}
+
+impl Callable for StrFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let value = arguments
+            .first()
+            .expect("Mismatched argument and parameter sizes");
+
+        Ok(LoxValue::String(stringify(interpreter, value, "str")?))
+    }
+}
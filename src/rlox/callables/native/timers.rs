@@ -0,0 +1,168 @@
+use std::any::Any;
+use std::rc::Rc;
+
+use rlox::callables::{Arity, Callable};
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::{LoxPromise, LoxValue, ResolveCallback};
+
+/// `setTimeout(callback, ms)` and `setInterval(callback, ms)`. They return the id of the timer,
+/// which can be cancelled with `clearTimeout` or `clearInterval`.
+#[derive(Debug)]
+pub struct SetTimerFunc {
+    name: &'static str,
+    repeat: bool,
+}
+
+impl SetTimerFunc {
+    pub fn timeout() -> SetTimerFunc {
+        SetTimerFunc {
+            name: "setTimeout",
+            repeat: false,
+        }
+    }
+
+    pub fn interval() -> SetTimerFunc {
+        SetTimerFunc {
+            name: "setInterval",
+            repeat: true,
+        }
+    }
+}
+
+impl Callable for SetTimerFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        // The delay of a timeout defaults to 0, running the callback on the next turn of the
+        // event loop
+        if self.repeat {
+            Arity::exactly(2)
+        } else {
+            Arity::between(1, 2)
+        }
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let callback = match arguments.first().and_then(|value| value.get_callable()) {
+            Some(callback) if callback.arity().accepts(0) => callback,
+            _ => {
+                return Err(RuntimeError::InvalidArgument(
+                    self.name.to_string(),
+                    "expected a function without parameters".to_string(),
+                ))
+            }
+        };
+
+        let delay = match arguments.get(1) {
+            Some(delay) => milliseconds(self.name, delay)?,
+            None => 0.0,
+        };
+
+        let id = interpreter
+            .event_loop()
+            .set_timer(callback, delay, self.repeat);
+        Ok(LoxValue::Number(id as f64))
+    }
+}
+
+/// `clearTimeout(id)` and `clearInterval(id)`, which are interchangeable.
+#[derive(Debug)]
+pub struct ClearTimerFunc {
+    name: &'static str,
+}
+
+impl ClearTimerFunc {
+    pub fn new(name: &'static str) -> ClearTimerFunc {
+        ClearTimerFunc { name }
+    }
+}
+
+impl Callable for ClearTimerFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let id = arguments
+            .first()
+            .expect("Mismatched argument and parameter sizes");
+
+        match id.as_integer() {
+            Some(id) if id > 0 => interpreter.event_loop().clear_timer(id as usize),
+            _ => {
+                return Err(RuntimeError::InvalidArgument(
+                    self.name.to_string(),
+                    "expected a timer id".to_string(),
+                ))
+            }
+        }
+
+        Ok(LoxValue::Nil)
+    }
+}
+
+/// Returns a promise that resolves with `nil` after the given milliseconds.
+#[derive(Debug)]
+pub struct SleepFunc {}
+
+impl SleepFunc {
+    pub fn new() -> SleepFunc {
+        SleepFunc {}
+    }
+}
+
+impl Callable for SleepFunc {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let delay = milliseconds(
+            "sleep",
+            arguments
+                .first()
+                .expect("Mismatched argument and parameter sizes"),
+        )?;
+
+        let promise = LoxPromise::new();
+        let resolve = Rc::new(ResolveCallback {
+            promise: promise.clone(),
+        });
+        interpreter.event_loop().set_timer(resolve, delay, false);
+
+        Ok(LoxValue::Promise(promise))
+    }
+}
+
+fn milliseconds(function: &str, value: &LoxValue) -> Result<f64, RuntimeError> {
+    match *value {
+        LoxValue::Number(millis) if millis.is_finite() && millis >= 0.0 => Ok(millis),
+        _ => Err(RuntimeError::InvalidArgument(
+            function.to_string(),
+            "expected a delay of zero or more milliseconds".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,66 @@
+use std;
+
+/// Access to the world outside of the interpreter that a group of natives needs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capability {
+    // `readLine()` and `input()`
+    Input,
+    // `readFile()` and `listDir()`
+    ReadFiles,
+    // `writeFile()`
+    WriteFiles,
+    // `getenv()`
+    Environment,
+    // `args()`
+    Args,
+}
+
+impl std::fmt::Display for Capability {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Capability::Input => f.write_str("read input"),
+            Capability::ReadFiles => f.write_str("read files"),
+            Capability::WriteFiles => f.write_str("write files"),
+            Capability::Environment => f.write_str("read environment variables"),
+            Capability::Args => f.write_str("read command line arguments"),
+        }
+    }
+}
+
+/// Capabilities a program is allowed to use. Nothing is allowed by default.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Capabilities {
+    allowed: Vec<Capability>,
+}
+
+impl Capabilities {
+    pub fn none() -> Capabilities {
+        Capabilities {
+            allowed: Vec::new(),
+        }
+    }
+
+    pub fn all() -> Capabilities {
+        Capabilities {
+            allowed: vec![
+                Capability::Input,
+                Capability::ReadFiles,
+                Capability::WriteFiles,
+                Capability::Environment,
+                Capability::Args,
+            ],
+        }
+    }
+
+    pub fn allow(mut self, capability: Capability) -> Capabilities {
+        if !self.allows(capability) {
+            self.allowed.push(capability);
+        }
+
+        self
+    }
+
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.allowed.contains(&capability)
+    }
+}
@@ -3,6 +3,8 @@ use std;
 #[derive(Debug)]
 pub enum EnvironmentError {
     UndefinedVariable(String),
+    // A local that isn't defined yet, by its depth and slot
+    UndefinedSlot(usize, usize),
 }
 
 impl std::fmt::Display for EnvironmentError {
@@ -11,6 +13,9 @@ impl std::fmt::Display for EnvironmentError {
             EnvironmentError::UndefinedVariable(ref name) => {
                 write!(f, "Undefined variable {}", name)
             }
+            EnvironmentError::UndefinedSlot(depth, slot) => {
+                write!(f, "Undefined variable in slot {} at depth {}", slot, depth)
+            }
         }
     }
 }
@@ -19,6 +24,7 @@ impl std::error::Error for EnvironmentError {
     fn description(&self) -> &str {
         match *self {
             EnvironmentError::UndefinedVariable(_) => "UndefinedVariable",
+            EnvironmentError::UndefinedSlot(_, _) => "UndefinedSlot",
         }
     }
 }
\ No newline at end of file
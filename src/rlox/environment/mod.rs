@@ -1,111 +1,202 @@
-use std::collections::hash_map::HashMap;
 mod errors;
+mod symbol;
 
 use std::rc::Rc;
 use std::cell::RefCell;
 
 use self::errors::EnvironmentError;
+pub use self::symbol::Symbol;
 use rlox::lox_value::LoxValue;
 use rlox::callables::native;
+use rlox::gc::{GcRef, Trace};
+
+/// Where a variable is found at runtime, as worked out by the resolver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Binding {
+    // A local, by how many environments up from the current one it is and its slot in there
+    Local(usize, usize),
+    // A global, by its interned name
+    Global(Symbol),
+}
 
+/// Variables in a scope. Locals are kept in the order they are defined, which is the order the
+/// resolver gave them their slots in. Globals are only in the global environment, indexed by
+/// their interned names, because they can be defined anywhere, like in a different REPL entry
+/// than the one using them.
 #[derive(Debug)]
 pub struct Environment {
-    values: HashMap<String, LoxValue>,
+    values: Vec<LoxValue>,
+    globals: Option<Vec<Option<LoxValue>>>,
     pub enclosing: Option<Rc<RefCell<Environment>>>,
 }
 
 impl Environment {
-    pub fn new() -> Environment {
-        Environment {
-            values: HashMap::new(),
-            enclosing: None,
-        }
-    }
-
     pub fn global() -> Environment {
-        let mut env = Environment::new();
+        let mut env = Environment {
+            values: Vec::new(),
+            globals: Some(Vec::new()),
+            enclosing: None,
+        };
 
+        env.define("clock", LoxValue::Func(Rc::new(native::ClockFunc::new())));
+        env.define("millis", LoxValue::Func(Rc::new(native::MillisFunc::new())));
+        env.define("nanos", LoxValue::Func(Rc::new(native::NanosFunc::new())));
+        env.define("formatTime", LoxValue::Func(Rc::new(native::FormatTimeFunc::new())));
+        env.define("gc", LoxValue::Func(Rc::new(native::GcFunc::new())));
+        env.define("len", LoxValue::Func(Rc::new(native::LenFunc::new())));
+        env.define("str", LoxValue::Func(Rc::new(native::StrFunc::new())));
+        env.define("setTimeout", LoxValue::Func(Rc::new(native::SetTimerFunc::timeout())));
+        env.define("setInterval", LoxValue::Func(Rc::new(native::SetTimerFunc::interval())));
         env.define(
-            "clock".to_string(),
-            LoxValue::Func(Rc::new(native::ClockFunc::new())),
+            "clearTimeout",
+            LoxValue::Func(Rc::new(native::ClearTimerFunc::new("clearTimeout"))),
         );
+        env.define(
+            "clearInterval",
+            LoxValue::Func(Rc::new(native::ClearTimerFunc::new("clearInterval"))),
+        );
+        env.define("sleep", LoxValue::Func(Rc::new(native::SleepFunc::new())));
+        env.define("eprint", LoxValue::Func(Rc::new(native::EprintFunc::new())));
+        env.define("readLine", LoxValue::Func(Rc::new(native::InputFunc::read_line())));
+        env.define("input", LoxValue::Func(Rc::new(native::InputFunc::input())));
+        env.define("readFile", LoxValue::Func(Rc::new(native::ReadFileFunc::new())));
+        env.define("writeFile", LoxValue::Func(Rc::new(native::WriteFileFunc::new())));
+        env.define("listDir", LoxValue::Func(Rc::new(native::ListDirFunc::new())));
+        env.define("getenv", LoxValue::Func(Rc::new(native::GetenvFunc::new())));
+        env.define("args", LoxValue::Func(Rc::new(native::ArgsFunc::new())));
+        env.define("exit", LoxValue::Func(Rc::new(native::ExitFunc::new())));
 
         env
     }
 
     pub fn from_parent(parent: Rc<RefCell<Environment>>) -> Environment {
         Environment {
-            values: HashMap::new(),
+            values: Vec::new(),
+            globals: None,
             enclosing: Some(parent),
         }
     }
 
-    pub fn define(&mut self, key: String, val: LoxValue) {
-        self.values.insert(key, val);
+    /// Defines a global by name in the global environment, or the next local in any other.
+    pub fn define(&mut self, name: &str, val: LoxValue) {
+        match self.globals {
+            Some(ref mut globals) => {
+                let Symbol(index) = Symbol::intern(name);
+
+                if globals.len() <= index {
+                    globals.resize(index + 1, None);
+                }
+
+                globals[index] = Some(val);
+            }
+            None => self.values.push(val),
+        }
     }
 
-    pub fn assign(&mut self, key: &String, val: LoxValue) -> Result<(), EnvironmentError> {
-        if self.values.contains_key(key) {
-            self.values.insert(key.clone(), val);
-            Ok(())
-        } else {
-            Err(EnvironmentError::UndefinedVariable(key.clone()))
+    pub fn get_at(&self, depth: usize, slot: usize) -> Result<LoxValue, EnvironmentError> {
+        if depth > 0 {
+            return match self.enclosing {
+                Some(ref parent) => parent.borrow().get_at(depth - 1, slot),
+                None => Err(EnvironmentError::UndefinedSlot(depth, slot)),
+            };
+        }
+
+        match self.values.get(slot) {
+            Some(value) => Ok(value.clone()),
+            None => Err(EnvironmentError::UndefinedSlot(depth, slot)),
         }
     }
 
     pub fn assign_at(
         &mut self,
-        key: &String,
+        depth: usize,
+        slot: usize,
         val: LoxValue,
-        distance: usize,
     ) -> Result<(), EnvironmentError> {
-        if distance == 0 {
-            return self.assign(key, val);
+        if depth > 0 {
+            return match self.enclosing {
+                Some(ref parent) => parent.borrow_mut().assign_at(depth - 1, slot, val),
+                None => Err(EnvironmentError::UndefinedSlot(depth, slot)),
+            };
         }
 
-        let mut parent_env = self.ancestor(distance);
-
-        match parent_env {
-            Some(ref mut parent) => parent.borrow_mut().assign(key, val),
-            None => Err(EnvironmentError::UndefinedVariable(key.clone())),
+        match self.values.get_mut(slot) {
+            Some(value) => {
+                *value = val;
+                Ok(())
+            }
+            None => Err(EnvironmentError::UndefinedSlot(depth, slot)),
         }
     }
 
-    pub fn get(&self, key: &String) -> Result<LoxValue, EnvironmentError> {
-        match self.values.get(key) {
-            Some(value) => Ok(value.clone()),
-            None => Err(EnvironmentError::UndefinedVariable(key.clone())),
-        }
-    }
+    pub fn get_global(&self, symbol: Symbol) -> Result<LoxValue, EnvironmentError> {
+        let Symbol(index) = symbol;
 
-    pub fn get_at(&self, key: &String, distance: usize) -> Result<LoxValue, EnvironmentError> {
-        if distance == 0 {
-            return self.get(key);
+        match self.globals.as_ref().and_then(|globals| globals.get(index)) {
+            Some(&Some(ref value)) => Ok(value.clone()),
+            _ => Err(EnvironmentError::UndefinedVariable(symbol.name())),
         }
+    }
 
-        let parent_env = self.ancestor(distance);
+    pub fn assign_global(&mut self, symbol: Symbol, val: LoxValue) -> Result<(), EnvironmentError> {
+        let Symbol(index) = symbol;
 
-        match parent_env {
-            Some(parent_env) => parent_env.borrow().get(key),
-            None => Err(EnvironmentError::UndefinedVariable(key.clone())),
+        match self.globals.as_mut().and_then(|globals| globals.get_mut(index)) {
+            Some(&mut Some(ref mut value)) => {
+                *value = val;
+                Ok(())
+            }
+            _ => Err(EnvironmentError::UndefinedVariable(symbol.name())),
         }
     }
 
-    fn ancestor(&self, distance: usize) -> Option<Rc<RefCell<Environment>>> {
-        let mut ret_env = match self.enclosing {
-            Some(ref parent_env) => parent_env.clone(),
-            None => return None,
+    /// Every global defined in this environment with its value, sorted by name.
+    pub fn entries(&self) -> Vec<(String, LoxValue)> {
+        let globals = match self.globals {
+            Some(ref globals) => globals,
+            None => return Vec::new(),
         };
 
-        for _ in 1..distance {
-            let new_env = match ret_env.borrow().enclosing {
-                Some(ref parent_env) => parent_env.clone(),
-                None => return None,
-            };
+        let mut entries: Vec<(String, LoxValue)> = globals
+            .iter()
+            .enumerate()
+            .filter_map(|(index, value)| {
+                value
+                    .as_ref()
+                    .map(|value| (Symbol(index).name(), value.clone()))
+            })
+            .collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+
+    /// Drops every value and the reference to the enclosing environment, used by the garbage
+    /// collector to break reference cycles.
+    pub fn clear(&mut self) {
+        self.values.clear();
 
-            ret_env = new_env;
+        if let Some(ref mut globals) = self.globals {
+            globals.clear();
         }
 
-        Some(ret_env)
+        self.enclosing = None;
+    }
+}
+
+impl Trace for Environment {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        for value in self.values.iter() {
+            value.trace(children);
+        }
+
+        if let Some(ref globals) = self.globals {
+            for value in globals.iter().flat_map(|value| value.iter()) {
+                value.trace(children);
+            }
+        }
+
+        if let Some(ref enclosing) = self.enclosing {
+            children.push(GcRef::Environment(enclosing.clone()));
+        }
     }
 }
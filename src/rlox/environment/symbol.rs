@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+thread_local! {
+    // Names are interned once per thread, like values are collected per thread, so every
+    // interpreter on it agrees on the symbol of each name
+    static INTERNER: RefCell<Interner> = RefCell::new(Interner::default());
+}
+
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<Rc<str>, usize>,
+    names: Vec<Rc<str>>,
+}
+
+/// A name, interned so that looking up a global indexes a `Vec` instead of hashing a string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(pub usize);
+
+impl Symbol {
+    pub fn intern(name: &str) -> Symbol {
+        INTERNER.with(|interner| {
+            let mut interner = interner.borrow_mut();
+
+            if let Some(&index) = interner.symbols.get(name) {
+                return Symbol(index);
+            }
+
+            let name: Rc<str> = Rc::from(name);
+            let index = interner.names.len();
+            interner.names.push(name.clone());
+            interner.symbols.insert(name, index);
+            Symbol(index)
+        })
+    }
+
+    /// The symbol of a name that was already interned, without interning it otherwise.
+    pub fn lookup(name: &str) -> Option<Symbol> {
+        INTERNER.with(|interner| interner.borrow().symbols.get(name).map(|&index| Symbol(index)))
+    }
+
+    pub fn name(&self) -> String {
+        INTERNER.with(|interner| interner.borrow().names[self.0].to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_does_not_intern() {
+        assert_eq!(Symbol::lookup("never_interned"), None);
+        assert_eq!(Symbol::lookup("never_interned"), None);
+
+        let symbol = Symbol::intern("interned");
+        assert_eq!(Symbol::lookup("interned"), Some(symbol));
+        assert_eq!(symbol.name(), "interned");
+    }
+}
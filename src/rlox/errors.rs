@@ -1,21 +1,136 @@
 use std;
+use std::error::Error as StdError;
+
+use serde_json;
 
 use rlox::scanner::errors::ScannerError;
 use rlox::parser::errors::ParsingError;
+use rlox::resolver::errors::ResolverError;
 use rlox::interpreter::errors::RuntimeError;
+use rlox::token::Token;
 
 #[derive(Debug)]
 pub enum Error {
     Scanner(ScannerError),
     Parser(ParsingError),
+    Resolver(ResolverError),
     Runtime(RuntimeError),
 }
 
+impl Error {
+    /// Exit code of a program that stopped because of this error. Following clox and jlox, it's
+    /// 65 for errors in the code and 70 for runtime errors.
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            Error::Scanner(_) | Error::Parser(_) | Error::Resolver(_) => 65,
+            Error::Runtime(RuntimeError::Exit(code)) => code,
+            Error::Runtime(_) => 70,
+        }
+    }
+
+    /// The step of running a program that found the error: `scanner`, `parser`, `resolver` or
+    /// `runtime`.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            Error::Scanner(_) => "scanner",
+            Error::Parser(_) => "parser",
+            Error::Resolver(_) => "resolver",
+            Error::Runtime(_) => "runtime",
+        }
+    }
+
+    /// Name of the variant of the error, like `UndefinedVariable`.
+    #[allow(deprecated)]
+    pub fn variant(&self) -> &str {
+        match *self {
+            Error::Scanner(ref err) => err.description(),
+            Error::Parser(ref err) => err.description(),
+            Error::Resolver(ref err) => err.description(),
+            Error::Runtime(ref err) => err.description(),
+        }
+    }
+
+    /// The error without the line it happened on.
+    pub fn message(&self) -> String {
+        match *self {
+            Error::Scanner(ref err) => err.message().to_string(),
+            Error::Parser(ref err) => err.message(),
+            Error::Resolver(ref err) => err.message(),
+            Error::Runtime(ref err) => err.message(),
+        }
+    }
+
+    pub fn line(&self) -> Option<usize> {
+        match *self {
+            Error::Scanner(ref err) => Some(err.line()),
+            _ => self.token().map(|token| token.line),
+        }
+    }
+
+    pub fn column(&self) -> Option<usize> {
+        match *self {
+            Error::Scanner(ref err) => Some(err.column()),
+            _ => self.token().map(|token| token.column),
+        }
+    }
+
+    /// The error in a form meant for tools, `file` being the one the code comes from.
+    pub fn report(&self, file: Option<&str>) -> ErrorReport {
+        ErrorReport {
+            kind: self.kind().to_string(),
+            variant: self.variant().to_string(),
+            message: self.message(),
+            file: file.map(|file| file.to_string()),
+            line: self.line(),
+            column: self.column(),
+            stack: None,
+        }
+    }
+
+    fn token(&self) -> Option<&Token> {
+        match *self {
+            Error::Scanner(_) => None,
+            Error::Parser(ref err) => err.token(),
+            Error::Resolver(ref err) => Some(err.token()),
+            Error::Runtime(ref err) => err.token(),
+        }
+    }
+}
+
+/// An error with where it happened, which serializes to JSON for editors and CI tools.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ErrorReport {
+    pub kind: String,
+    pub variant: String,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    // Only for runtime errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<StackFrame>>,
+}
+
+impl ErrorReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Error serializing an error report")
+    }
+}
+
+/// A call that was running when a runtime error happened: the function called, and the line
+/// of the call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StackFrame {
+    pub function: String,
+    pub line: usize,
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
             Error::Scanner(ref err) => write!(f, "{}", err),
             Error::Parser(ref err) => write!(f, "{}", err),
+            Error::Resolver(ref err) => write!(f, "{}", err),
             Error::Runtime(ref err) => write!(f, "{}", err),
         }
     }
@@ -26,6 +141,7 @@ impl std::error::Error for Error {
         match *self {
             Error::Scanner(_) => "Error::Scanner",
             Error::Parser(_) => "Error::Parser",
+            Error::Resolver(_) => "Error::Resolver",
             Error::Runtime(_) => "Error::Runtime",
         }
     }
@@ -0,0 +1,122 @@
+use std;
+use std::rc::Rc;
+use std::collections::VecDeque;
+
+use rlox::callables::Callable;
+use rlox::callables::native::clock::Clock;
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+
+/// A callback waiting to be run by the event loop.
+#[derive(Debug)]
+pub struct Task {
+    callback: Rc<Callable>,
+    arguments: Vec<LoxValue>,
+}
+
+impl Task {
+    pub fn run(self, interpreter: &mut Interpreter) -> Result<LoxValue, RuntimeError> {
+        self.callback.call(interpreter, self.arguments)
+    }
+}
+
+#[derive(Debug)]
+struct Timer {
+    id: usize,
+    // In milliseconds, by the clock of the event loop
+    due: f64,
+    // `Some` for timers created with `setInterval`
+    interval: Option<f64>,
+    callback: Rc<Callable>,
+}
+
+/// Single threaded loop that runs the callbacks of timers and promises once the program is
+/// done. Tasks that are ready always go first, in the order they were scheduled. Otherwise
+/// the loop sleeps until the earliest timer is due, with ties broken by creation order.
+pub struct EventLoop {
+    clock: Rc<Clock>,
+    tasks: VecDeque<Task>,
+    timers: Vec<Timer>,
+    next_id: usize,
+}
+
+impl EventLoop {
+    pub fn new(clock: Rc<Clock>) -> EventLoop {
+        EventLoop {
+            clock,
+            tasks: VecDeque::new(),
+            timers: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn clock(&self) -> &Clock {
+        &*self.clock
+    }
+
+    /// Queues a callback to run as soon as possible.
+    pub fn schedule(&mut self, callback: Rc<Callable>, arguments: Vec<LoxValue>) {
+        self.tasks.push_back(Task {
+            callback,
+            arguments,
+        });
+    }
+
+    /// Calls `callback` without arguments after `delay` milliseconds, and every `delay`
+    /// milliseconds after that if `repeat` is set. Returns an id for `clear_timer`.
+    pub fn set_timer(&mut self, callback: Rc<Callable>, delay: f64, repeat: bool) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.timers.push(Timer {
+            id,
+            due: self.clock.now() + delay,
+            interval: if repeat { Some(delay) } else { None },
+            callback,
+        });
+
+        id
+    }
+
+    /// Cancels a timer. Unknown ids, such as the ones of timers that already ran, are ignored.
+    pub fn clear_timer(&mut self, id: usize) {
+        self.timers.retain(|timer| timer.id != id);
+    }
+
+    /// Returns the next task to run, waiting for a timer if needed, or `None` once there is
+    /// nothing left to do.
+    pub fn next(&mut self) -> Option<Task> {
+        if let Some(task) = self.tasks.pop_front() {
+            return Some(task);
+        }
+
+        let index = (0..self.timers.len()).min_by(|&a, &b| {
+            let (a, b) = (&self.timers[a], &self.timers[b]);
+            a.due
+                .partial_cmp(&b.due)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.id.cmp(&b.id))
+        })?;
+
+        let wait = self.timers[index].due - self.clock.now();
+
+        if wait > 0.0 {
+            self.clock.sleep(wait);
+        }
+
+        let callback = match self.timers[index].interval {
+            Some(interval) => {
+                let timer = &mut self.timers[index];
+                timer.due += interval;
+                timer.callback.clone()
+            }
+            None => self.timers.remove(index).callback,
+        };
+
+        Some(Task {
+            callback,
+            arguments: Vec::new(),
+        })
+    }
+}
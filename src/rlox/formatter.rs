@@ -0,0 +1,208 @@
+use rlox::token::{Token, TokenType};
+
+const INDENT: &str = "  ";
+
+/// Formats the tokens of a program, comments included. Line breaks are kept where they are,
+/// collapsing consecutive blank lines into one, while indentation and the spacing between
+/// tokens are normalized. Each open bracket indents the lines that follow it by one level,
+/// and so does each line that continues the statement of the previous one. The `}` of a block
+/// that spans several lines always goes on a line of its own.
+pub fn format(tokens: &[Token]) -> String {
+    let mut output = String::new();
+    // Indentation of the lines inside each open bracket, and the line where it was opened
+    let mut brackets: Vec<(usize, usize)> = Vec::new();
+    let mut line = 0;
+    let mut previous: Option<&Token> = None;
+    // Whether the previous token is a prefix operator, like the `-` in `-1`
+    let mut after_prefix = false;
+    // Last token that isn't a comment, which tells if a line continues a statement
+    let mut last_code: Option<&Token> = None;
+
+    for token in tokens.iter().filter(|token| token.token_type != TokenType::Eof) {
+        let starts_line = match previous {
+            Some(previous) => {
+                token.line > last_line(previous)
+                    || token.token_type == TokenType::RightBrace
+                        && brackets.last().is_some_and(|&(_, opened)| opened < line)
+            }
+            None => true,
+        };
+
+        if starts_line {
+            if let Some(previous) = previous {
+                output.push('\n');
+                line += 1;
+
+                if token.line > last_line(previous) + 1 {
+                    output.push('\n');
+                }
+            }
+
+            let indent = if is_closing(token) {
+                brackets.last().map_or(0, |&(indent, _)| indent - 1)
+            } else {
+                let base = brackets.last().map_or(0, |&(indent, _)| indent);
+                if continues_statement(last_code, token) {
+                    base + 1
+                } else {
+                    base
+                }
+            };
+
+            output.push_str(&INDENT.repeat(indent));
+        } else if let Some(previous) = previous {
+            if (!after_prefix || would_join(previous, token)) && needs_space(previous, token) {
+                output.push(' ');
+            }
+        }
+
+        after_prefix = is_prefix(previous, token);
+
+        match token.token_type {
+            TokenType::LeftParen | TokenType::LeftBracket | TokenType::LeftBrace => {
+                brackets.push((bracket_indent(&brackets, line), line))
+            }
+            TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace => {
+                brackets.pop();
+            }
+            // Parts of a string with interpolations open and close the interpolated expressions
+            TokenType::Interpolation => {
+                if is_string_continuation(token) {
+                    brackets.pop();
+                }
+                brackets.push((bracket_indent(&brackets, line), line));
+            }
+            TokenType::String if is_string_continuation(token) => {
+                brackets.pop();
+            }
+            _ => {}
+        }
+
+        output.push_str(token.lexeme.trim_end());
+        line += token.lexeme.matches('\n').count();
+        previous = Some(token);
+
+        if token.token_type != TokenType::Comment {
+            last_code = Some(token);
+        }
+    }
+
+    if !output.is_empty() {
+        output.push('\n');
+    }
+
+    output
+}
+
+// Tokens know the line they start on, strings can span several of them
+fn last_line(token: &Token) -> usize {
+    token.line + token.lexeme.matches('\n').count()
+}
+
+// Indentation of the lines inside a bracket opened on `line`. It's one level more than the
+// lines of the bracket around it, or than the top level, whatever the line it's opened on is
+// indented by, so a bracket on a line that continues a statement doesn't indent twice. Brackets
+// opened on the same line share a level, so `foo([` only indents its contents once.
+fn bracket_indent(brackets: &[(usize, usize)], line: usize) -> usize {
+    match brackets.last() {
+        Some(&(indent, opened)) if opened == line => indent,
+        Some(&(indent, _)) => indent + 1,
+        None => 1,
+    }
+}
+
+fn is_closing(token: &Token) -> bool {
+    match token.token_type {
+        TokenType::RightParen | TokenType::RightBracket | TokenType::RightBrace => true,
+        TokenType::String | TokenType::Interpolation => is_string_continuation(token),
+        _ => false,
+    }
+}
+
+// The part of a string that follows an interpolated expression starts with its closing `}`
+fn is_string_continuation(token: &Token) -> bool {
+    token.lexeme.starts_with('}')
+}
+
+fn continues_statement(last_code: Option<&Token>, token: &Token) -> bool {
+    let last_code = match last_code {
+        Some(last_code) => last_code,
+        None => return false,
+    };
+
+    if token.token_type == TokenType::Comment || token.token_type == TokenType::LeftBrace {
+        return false;
+    }
+
+    !matches!(
+        last_code.token_type,
+        TokenType::Semicolon
+            | TokenType::LeftBrace
+            | TokenType::RightBrace
+            | TokenType::LeftParen
+            | TokenType::LeftBracket
+            | TokenType::Comma
+    )
+}
+
+// Whether the token ends an operand, so that a `-` or `++` after it is a binary operator or a
+// postfix one, and a `(` or `[` after it is a call or an index
+fn ends_operand(token: &Token) -> bool {
+    match token.token_type {
+        // `in` and `match` are only keywords in some places, but they are spaced like keywords
+        // everywhere, like the `[` in `for (x in [1, 2])` and the `(` in `match (x) {`
+        TokenType::Identifier => token.lexeme != "in" && token.lexeme != "match",
+        TokenType::Number
+        | TokenType::True
+        | TokenType::False
+        | TokenType::Nil
+        | TokenType::This
+        | TokenType::Super
+        | TokenType::RightParen
+        | TokenType::RightBracket
+        | TokenType::PlusPlus
+        | TokenType::MinusMinus
+        | TokenType::String => true,
+        _ => false,
+    }
+}
+
+fn is_prefix(previous: Option<&Token>, token: &Token) -> bool {
+    match token.token_type {
+        TokenType::Bang | TokenType::Tilde | TokenType::Ellipsis => true,
+        TokenType::Minus | TokenType::PlusPlus | TokenType::MinusMinus => {
+            !previous.is_some_and(ends_operand)
+        }
+        _ => false,
+    }
+}
+
+// Whether the tokens would scan as a different one without a space, like `-` and `-1` as `--1`
+fn would_join(previous: &Token, token: &Token) -> bool {
+    match previous.token_type {
+        TokenType::Minus | TokenType::MinusMinus => token.lexeme.starts_with('-'),
+        TokenType::Plus | TokenType::PlusPlus => token.lexeme.starts_with('+'),
+        _ => false,
+    }
+}
+
+fn needs_space(previous: &Token, token: &Token) -> bool {
+    match previous.token_type {
+        TokenType::LeftParen | TokenType::LeftBracket | TokenType::Dot => return false,
+        TokenType::Interpolation => return false,
+        _ => {}
+    }
+
+    match token.token_type {
+        TokenType::RightParen
+        | TokenType::RightBracket
+        | TokenType::Comma
+        | TokenType::Semicolon
+        | TokenType::Dot => false,
+        TokenType::RightBrace => previous.token_type != TokenType::LeftBrace,
+        TokenType::LeftParen | TokenType::LeftBracket => !ends_operand(previous),
+        TokenType::PlusPlus | TokenType::MinusMinus => !ends_operand(previous),
+        TokenType::String | TokenType::Interpolation => !is_string_continuation(token),
+        _ => true,
+    }
+}
@@ -0,0 +1,325 @@
+use std;
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use std::collections::hash_map::{Entry, HashMap};
+
+use rlox::callables::Callable;
+use rlox::environment::Environment;
+use rlox::lox_value::{LoxClass, LoxClassInternal, LoxFunc, LoxGenerator, LoxInstance, LoxPromise,
+                      LoxValue};
+
+// Number of tracked objects the heap can hold before an automatic collection kicks in.
+const MIN_THRESHOLD: usize = 1024;
+
+thread_local! {
+    // Values are `Rc`s, so they can't leave the thread they were created in. A heap per thread
+    // is therefore enough to know about every object a given interpreter allocated.
+    static HEAP: RefCell<Heap> = RefCell::new(Heap::new());
+}
+
+/// Strong reference to one of the heap objects that can be part of a reference cycle.
+#[derive(Clone)]
+pub enum GcRef {
+    Environment(Rc<RefCell<Environment>>),
+    Instance(Rc<RefCell<LoxInstance>>),
+    Func(Rc<Callable>),
+    Class(Rc<LoxClass>),
+    ClassInternal(Rc<LoxClassInternal>),
+    List(Rc<RefCell<Vec<LoxValue>>>),
+    Generator(Rc<RefCell<LoxGenerator>>),
+    Promise(Rc<RefCell<LoxPromise>>),
+}
+
+enum WeakRef {
+    Environment(Weak<RefCell<Environment>>),
+    Instance(Weak<RefCell<LoxInstance>>),
+    Func(Weak<Callable>),
+    Class(Weak<LoxClass>),
+    ClassInternal(Weak<LoxClassInternal>),
+    List(Weak<RefCell<Vec<LoxValue>>>),
+    Generator(Weak<RefCell<LoxGenerator>>),
+    Promise(Weak<RefCell<LoxPromise>>),
+}
+
+/// Implemented by everything that holds references to other heap objects, so the collector
+/// can find out which references are internal to the heap.
+pub trait Trace {
+    fn trace(&self, children: &mut Vec<GcRef>);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GcStats {
+    pub collections: usize,
+    pub reclaimed: usize,
+    pub last_reclaimed: usize,
+    pub tracked: usize,
+}
+
+impl std::fmt::Display for GcStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "collections: {}, reclaimed: {}, last reclaimed: {}, tracked: {}",
+            self.collections, self.reclaimed, self.last_reclaimed, self.tracked
+        )
+    }
+}
+
+impl GcRef {
+    fn id(&self) -> usize {
+        match *self {
+            GcRef::Environment(ref env) => Rc::as_ptr(env) as *const u8 as usize,
+            GcRef::Instance(ref instance) => Rc::as_ptr(instance) as *const u8 as usize,
+            GcRef::Func(ref func) => Rc::as_ptr(func) as *const u8 as usize,
+            GcRef::Class(ref class) => Rc::as_ptr(class) as *const u8 as usize,
+            GcRef::ClassInternal(ref class) => Rc::as_ptr(class) as *const u8 as usize,
+            GcRef::List(ref list) => Rc::as_ptr(list) as *const u8 as usize,
+            GcRef::Generator(ref generator) => Rc::as_ptr(generator) as *const u8 as usize,
+            GcRef::Promise(ref promise) => Rc::as_ptr(promise) as *const u8 as usize,
+        }
+    }
+
+    fn strong_count(&self) -> usize {
+        match *self {
+            GcRef::Environment(ref env) => Rc::strong_count(env),
+            GcRef::Instance(ref instance) => Rc::strong_count(instance),
+            GcRef::Func(ref func) => Rc::strong_count(func),
+            GcRef::Class(ref class) => Rc::strong_count(class),
+            GcRef::ClassInternal(ref class) => Rc::strong_count(class),
+            GcRef::List(ref list) => Rc::strong_count(list),
+            GcRef::Generator(ref generator) => Rc::strong_count(generator),
+            GcRef::Promise(ref promise) => Rc::strong_count(promise),
+        }
+    }
+
+    fn downgrade(&self) -> WeakRef {
+        match *self {
+            GcRef::Environment(ref env) => WeakRef::Environment(Rc::downgrade(env)),
+            GcRef::Instance(ref instance) => WeakRef::Instance(Rc::downgrade(instance)),
+            GcRef::Func(ref func) => WeakRef::Func(Rc::downgrade(func)),
+            GcRef::Class(ref class) => WeakRef::Class(Rc::downgrade(class)),
+            GcRef::ClassInternal(ref class) => WeakRef::ClassInternal(Rc::downgrade(class)),
+            GcRef::List(ref list) => WeakRef::List(Rc::downgrade(list)),
+            GcRef::Generator(ref generator) => WeakRef::Generator(Rc::downgrade(generator)),
+            GcRef::Promise(ref promise) => WeakRef::Promise(Rc::downgrade(promise)),
+        }
+    }
+
+    /// Pushes the direct children of this object. Returns `false` if the object is currently
+    /// borrowed and couldn't be inspected, in which case it has to be considered alive.
+    fn trace(&self, children: &mut Vec<GcRef>) -> bool {
+        match *self {
+            GcRef::Environment(ref env) => match env.try_borrow() {
+                Ok(env) => env.trace(children),
+                Err(_) => return false,
+            },
+            GcRef::Instance(ref instance) => match instance.try_borrow() {
+                Ok(instance) => instance.trace(children),
+                Err(_) => return false,
+            },
+            GcRef::Func(ref func) => {
+                if let Some(func) = func.as_any().downcast_ref::<LoxFunc>() {
+                    func.trace(children);
+                }
+            }
+            GcRef::Class(ref class) => class.trace(children),
+            GcRef::ClassInternal(ref class) => class.trace(children),
+            GcRef::List(ref list) => match list.try_borrow() {
+                Ok(list) => for item in list.iter() {
+                    item.trace(children);
+                },
+                Err(_) => return false,
+            },
+            GcRef::Generator(ref generator) => match generator.try_borrow() {
+                Ok(generator) => generator.trace(children),
+                Err(_) => return false,
+            },
+            GcRef::Promise(ref promise) => match promise.try_borrow() {
+                Ok(promise) => promise.trace(children),
+                Err(_) => return false,
+            },
+        }
+
+        true
+    }
+
+    /// Drops every reference held by this object, breaking any cycle it is part of.
+    fn clear(&self) {
+        match *self {
+            GcRef::Environment(ref env) => env.borrow_mut().clear(),
+            GcRef::Instance(ref instance) => instance.borrow_mut().clear(),
+            GcRef::List(ref list) => list.borrow_mut().clear(),
+            GcRef::Generator(ref generator) => generator.borrow_mut().clear(),
+            GcRef::Promise(ref promise) => promise.borrow_mut().clear(),
+            // Functions and classes are immutable, a cycle through them always goes through
+            // an environment, an instance, a list, a generator or a promise as well.
+            GcRef::Func(_) | GcRef::Class(_) | GcRef::ClassInternal(_) => {}
+        }
+    }
+}
+
+impl WeakRef {
+    fn upgrade(&self) -> Option<GcRef> {
+        match *self {
+            WeakRef::Environment(ref env) => env.upgrade().map(GcRef::Environment),
+            WeakRef::Instance(ref instance) => instance.upgrade().map(GcRef::Instance),
+            WeakRef::Func(ref func) => func.upgrade().map(GcRef::Func),
+            WeakRef::Class(ref class) => class.upgrade().map(GcRef::Class),
+            WeakRef::ClassInternal(ref class) => class.upgrade().map(GcRef::ClassInternal),
+            WeakRef::List(ref list) => list.upgrade().map(GcRef::List),
+            WeakRef::Generator(ref generator) => generator.upgrade().map(GcRef::Generator),
+            WeakRef::Promise(ref promise) => promise.upgrade().map(GcRef::Promise),
+        }
+    }
+}
+
+/// Cycle collector for the reference counted values of the interpreter.
+///
+/// Objects are still freed by `Rc` as soon as their last reference goes away. The heap only
+/// keeps weak references to them, and on every collection it looks for groups of objects that
+/// are only kept alive by references among themselves (the same trial deletion approach used
+/// by CPython). Any reference from outside the heap - the interpreter's current environment, a
+/// value on the Rust stack - keeps an object and everything reachable from it alive.
+pub struct Heap {
+    tracked: Vec<WeakRef>,
+    threshold: usize,
+    stats: GcStats,
+}
+
+impl Heap {
+    pub fn new() -> Heap {
+        Heap {
+            tracked: Vec::new(),
+            threshold: MIN_THRESHOLD,
+            stats: GcStats {
+                collections: 0,
+                reclaimed: 0,
+                last_reclaimed: 0,
+                tracked: 0,
+            },
+        }
+    }
+
+    pub fn track(&mut self, object: GcRef) {
+        self.tracked.push(object.downgrade());
+    }
+
+    pub fn should_collect(&self) -> bool {
+        self.tracked.len() >= self.threshold
+    }
+
+    pub fn stats(&self) -> GcStats {
+        GcStats {
+            tracked: self.tracked.len(),
+            ..self.stats
+        }
+    }
+
+    pub fn collect(&mut self) -> usize {
+        let mut objects: Vec<GcRef> = Vec::new();
+        let mut index: HashMap<usize, usize> = HashMap::new();
+
+        for weak in self.tracked.iter() {
+            if let Some(object) = weak.upgrade() {
+                if let Entry::Vacant(entry) = index.entry(object.id()) {
+                    entry.insert(objects.len());
+                    objects.push(object);
+                }
+            }
+        }
+
+        // `objects` holds one strong reference of its own to each of them
+        let mut external_refs: Vec<usize> = objects
+            .iter()
+            .map(|object| object.strong_count() - 1)
+            .collect();
+        let mut traceable: Vec<bool> = Vec::with_capacity(objects.len());
+
+        for object in objects.iter() {
+            let mut children = Vec::new();
+            traceable.push(object.trace(&mut children));
+
+            for child in children {
+                if let Some(&i) = index.get(&child.id()) {
+                    external_refs[i] = external_refs[i].saturating_sub(1);
+                }
+            }
+        }
+
+        let mut reachable = vec![false; objects.len()];
+        let mut pending: Vec<usize> = (0..objects.len())
+            .filter(|&i| external_refs[i] > 0 || !traceable[i])
+            .collect();
+
+        while let Some(i) = pending.pop() {
+            if reachable[i] {
+                continue;
+            }
+
+            reachable[i] = true;
+
+            let mut children = Vec::new();
+            objects[i].trace(&mut children);
+
+            for child in children {
+                if let Some(&child_index) = index.get(&child.id()) {
+                    if !reachable[child_index] {
+                        pending.push(child_index);
+                    }
+                }
+            }
+        }
+
+        let garbage: Vec<GcRef> = objects
+            .iter()
+            .zip(reachable.iter())
+            .filter(|&(_, &reachable)| !reachable)
+            .map(|(object, _)| object.clone())
+            .collect();
+
+        for object in garbage.iter() {
+            object.clear();
+        }
+
+        let reclaimed = garbage.len();
+        drop(garbage);
+
+        self.tracked = objects
+            .iter()
+            .zip(reachable.iter())
+            .filter(|&(_, &reachable)| reachable)
+            .map(|(object, _)| object.downgrade())
+            .collect();
+        self.threshold = std::cmp::max(MIN_THRESHOLD, self.tracked.len() * 2);
+
+        self.stats.collections += 1;
+        self.stats.reclaimed += reclaimed;
+        self.stats.last_reclaimed = reclaimed;
+
+        reclaimed
+    }
+}
+
+pub fn track(object: GcRef) {
+    HEAP.with(|heap| heap.borrow_mut().track(object));
+}
+
+/// Runs a collection and returns the number of objects that were reclaimed.
+pub fn collect() -> usize {
+    HEAP.with(|heap| heap.borrow_mut().collect())
+}
+
+/// Runs a collection only if enough objects were allocated since the last one.
+pub fn maybe_collect() {
+    HEAP.with(|heap| {
+        let mut heap = heap.borrow_mut();
+
+        if heap.should_collect() {
+            heap.collect();
+        }
+    });
+}
+
+pub fn stats() -> GcStats {
+    HEAP.with(|heap| heap.borrow().stats())
+}
@@ -1,5 +1,7 @@
 use std;
 use rlox::token::Token;
+use rlox::callables::Arity;
+use rlox::capabilities::Capability;
 
 #[derive(Debug)]
 pub enum RuntimeError {
@@ -16,94 +18,197 @@ pub enum RuntimeError {
     DivideByZeroError(Token),
     UndefinedVariable(Token),
     CallOnNonCallable(Token),
-    WrongArity(Token, usize, usize),
+    WrongArity(Token, usize, Arity),
     InvalidGetTarget(Token),
     UndefinedProperty(Token),
     InvalidSuperclass(Token),
+    InvalidToString(Token, String),
+    MissingOperatorMethod(Token, String, String),
+    ReadOnlyProperty(Token),
+    WriteOnlyProperty(Token),
+    InvalidArgument(String, String),
+    InvalidSpread(Token),
+    InvalidIndexTarget(Token),
+    InvalidIndex(Token),
+    IndexOutOfBounds(Token, usize),
+    ModuloNonNumbers(Token),
+    IntegerDivideNonNumbers(Token),
+    PowerNonNumbers(Token),
+    NotANumber(Token),
+    NonIntegerOperands(Token),
+    InvalidShift(Token),
+    InvalidPatternClass(Token),
+    NotIterable(Token),
+    GeneratorAlreadyRunning(String),
+    PermissionDenied(String, Capability),
+    IoError(String, String),
+    // Not an actual error, `exit()` unwinds the interpreter with it
+    Exit(i32),
 }
 
-impl std::fmt::Display for RuntimeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl RuntimeError {
+    /// The token where the error happened, if it's known.
+    pub fn token(&self) -> Option<&Token> {
+        match *self {
+            RuntimeError::NegateNonNumberError(ref token)
+            | RuntimeError::SubtractNonNumbers(ref token)
+            | RuntimeError::DivideNonNumbers(ref token)
+            | RuntimeError::MultiplyNonNumbers(ref token)
+            | RuntimeError::PlusTypeError(ref token)
+            | RuntimeError::GreaterNonNumbers(ref token)
+            | RuntimeError::GreaterEqualNonNumbers(ref token)
+            | RuntimeError::LessNonNumbers(ref token)
+            | RuntimeError::LessEqualNonNumbers(ref token)
+            | RuntimeError::DivideByZeroError(ref token)
+            | RuntimeError::UndefinedVariable(ref token)
+            | RuntimeError::CallOnNonCallable(ref token)
+            | RuntimeError::InvalidGetTarget(ref token)
+            | RuntimeError::UndefinedProperty(ref token)
+            | RuntimeError::InvalidSuperclass(ref token)
+            | RuntimeError::ReadOnlyProperty(ref token)
+            | RuntimeError::WriteOnlyProperty(ref token)
+            | RuntimeError::InvalidSpread(ref token)
+            | RuntimeError::InvalidIndexTarget(ref token)
+            | RuntimeError::InvalidIndex(ref token)
+            | RuntimeError::ModuloNonNumbers(ref token)
+            | RuntimeError::IntegerDivideNonNumbers(ref token)
+            | RuntimeError::PowerNonNumbers(ref token)
+            | RuntimeError::NotANumber(ref token)
+            | RuntimeError::NonIntegerOperands(ref token)
+            | RuntimeError::InvalidShift(ref token)
+            | RuntimeError::InvalidPatternClass(ref token)
+            | RuntimeError::NotIterable(ref token)
+            | RuntimeError::WrongArity(ref token, _, _)
+            | RuntimeError::MissingOperatorMethod(ref token, _, _)
+            | RuntimeError::InvalidToString(ref token, _)
+            | RuntimeError::IndexOutOfBounds(ref token, _) => Some(token),
+            _ => None,
+        }
+    }
+
+    /// The error without the line it happened on.
+    pub fn message(&self) -> String {
         match *self {
             RuntimeError::InternalError(ref message) => {
-                write!(f, "Internal interpreter error: {}", message)
+                format!("Internal interpreter error: {}", message)
             }
-            RuntimeError::NegateNonNumberError(ref token) => write!(
-                f,
-                "[line {}] Cannot negate a non-numerical value",
-                token.line
-            ),
-            RuntimeError::SubtractNonNumbers(ref token) => write!(
-                f,
-                "[line {}] Both sides of a subtraction must be numbers",
-                token.line
-            ),
-            RuntimeError::DivideNonNumbers(ref token) => write!(
-                f,
-                "[line {}] Both sides of a division must be numbers",
-                token.line
-            ),
-            RuntimeError::MultiplyNonNumbers(ref token) => write!(
-                f,
-                "[line {}] Both sides of a multiplication must be numbers",
-                token.line
-            ),
-            RuntimeError::PlusTypeError(ref token) => write!(
-                f,
-                "[line {}] Both sides of an addition must be either strings or numbers",
-                token.line
-            ),
-            RuntimeError::GreaterNonNumbers(ref token) => write!(
-                f,
-                "[line {}] Both sides of a greater than comparison must be numbers",
-                token.line
-            ),
-            RuntimeError::GreaterEqualNonNumbers(ref token) => write!(
-                f,
-                "[line {}] Both sides of a greater or equal comparison must be numbers",
-                token.line
-            ),
-            RuntimeError::LessNonNumbers(ref token) => write!(
-                f,
-                "[line {}] Both sides of a less than comparison must be numbers",
-                token.line
+            RuntimeError::NegateNonNumberError(_) => {
+                "Cannot negate a non-numerical value".to_string()
+            }
+            RuntimeError::SubtractNonNumbers(_) => {
+                "Both sides of a subtraction must be numbers".to_string()
+            }
+            RuntimeError::DivideNonNumbers(_) => {
+                "Both sides of a division must be numbers".to_string()
+            }
+            RuntimeError::MultiplyNonNumbers(_) => {
+                "Both sides of a multiplication must be numbers".to_string()
+            }
+            RuntimeError::PlusTypeError(_) => {
+                "Both sides of an addition must be either strings or numbers".to_string()
+            }
+            RuntimeError::GreaterNonNumbers(_) => {
+                "Both sides of a greater than comparison must be numbers".to_string()
+            }
+            RuntimeError::GreaterEqualNonNumbers(_) => {
+                "Both sides of a greater or equal comparison must be numbers".to_string()
+            }
+            RuntimeError::LessNonNumbers(_) => {
+                "Both sides of a less than comparison must be numbers".to_string()
+            }
+            RuntimeError::LessEqualNonNumbers(_) => {
+                "Both sides of a less or equal comparison must be numbers".to_string()
+            }
+            RuntimeError::DivideByZeroError(_) => "Cannot divide by zero".to_string(),
+            RuntimeError::UndefinedVariable(ref token) => {
+                format!("Undefined variable `{}`", token.lexeme)
+            }
+            RuntimeError::CallOnNonCallable(_) => "Attempted to call on non-callable".to_string(),
+            RuntimeError::WrongArity(_, actual, expected) => format!(
+                "Function arity error, expected {} but got {}",
+                expected, actual
             ),
-            RuntimeError::LessEqualNonNumbers(ref token) => write!(
-                f,
-                "[line {}] Both sides of a less or equal comparison must be numbers",
-                token.line
+            RuntimeError::InvalidGetTarget(ref token) => format!(
+                "Only instances have properties, tried to access `{}` in non-instance",
+                token.lexeme
             ),
-            RuntimeError::DivideByZeroError(ref token) => {
-                write!(f, "[line {}] Cannot divide by zero", token.line)
+            RuntimeError::UndefinedProperty(ref token) => {
+                format!("Undefined property `{}`.", token.lexeme)
             }
-            RuntimeError::UndefinedVariable(ref token) => write!(
-                f,
-                "[line {}] Undefined variable `{}`",
-                token.line, token.lexeme
-            ),
-            RuntimeError::CallOnNonCallable(ref token) => {
-                write!(f, "[line {}] Attempted to call on non-callable", token.line)
+            RuntimeError::InvalidSuperclass(ref token) => {
+                format!("Invalid parent class for `{}`.", token.lexeme)
             }
-            RuntimeError::WrongArity(ref token, actual, expected) => write!(
-                f,
-                "[line {}] Function arity error, expected {} arguments but got {}",
-                token.line, expected, actual
-            ),
-            RuntimeError::InvalidGetTarget(ref token) => write!(
-                f,
-                "[line {}] Only instances have properties, tried to access `{}` in non-instance",
-                token.line, token.lexeme
+            RuntimeError::InvalidToString(_, ref class_name) => format!(
+                "`toString` of class `{}` must take no arguments and return a string.",
+                class_name
             ),
-            RuntimeError::UndefinedProperty(ref token) => write!(
-                f,
-                "[line {}] Undefined property `{}`.",
-                token.line, token.lexeme
+            RuntimeError::MissingOperatorMethod(ref token, ref class_name, ref method_name) => {
+                format!(
+                    "Class `{}` has no `{}` method to support `{}`.",
+                    class_name, method_name, token.lexeme
+                )
+            }
+            RuntimeError::ReadOnlyProperty(ref token) => {
+                format!("Property `{}` has a getter but no setter.", token.lexeme)
+            }
+            RuntimeError::WriteOnlyProperty(ref token) => {
+                format!("Property `{}` has a setter but no getter.", token.lexeme)
+            }
+            RuntimeError::InvalidArgument(ref function, ref message) => {
+                format!("Invalid argument for `{}`, {}.", function, message)
+            }
+            RuntimeError::InvalidSpread(_) => "Only lists can be spread.".to_string(),
+            RuntimeError::InvalidIndexTarget(_) => {
+                "Only lists and strings can be indexed.".to_string()
+            }
+            RuntimeError::InvalidIndex(_) => "Index must be a non-negative integer.".to_string(),
+            RuntimeError::IndexOutOfBounds(_, length) => {
+                format!("Index out of bounds for length {}.", length)
+            }
+            RuntimeError::ModuloNonNumbers(_) => {
+                "Both sides of a modulo must be numbers".to_string()
+            }
+            RuntimeError::IntegerDivideNonNumbers(_) => {
+                "Both sides of an integer division must be numbers".to_string()
+            }
+            RuntimeError::PowerNonNumbers(_) => {
+                "Both sides of an exponentiation must be numbers".to_string()
+            }
+            RuntimeError::NonIntegerOperands(ref token) => {
+                format!("Operands of `{}` must be integers", token.lexeme)
+            }
+            RuntimeError::InvalidShift(_) => "Shift amount must be between 0 and 63".to_string(),
+            RuntimeError::NotANumber(ref token) => format!(
+                "Operation `{}` doesn't produce a valid number",
+                token.lexeme
             ),
-            RuntimeError::InvalidSuperclass(ref token) => write!(
-                f,
-                "[line {}] Invalid parent class for `{}`.",
-                token.line, token.lexeme
+            RuntimeError::NotIterable(_) => {
+                "Can only iterate over lists, strings and instances with `iterator()`, or `hasNext()` and `next()` methods"
+                    .to_string()
+            }
+            RuntimeError::GeneratorAlreadyRunning(ref name) => {
+                format!("Generator `{}` is already running", name)
+            }
+            RuntimeError::InvalidPatternClass(ref token) => {
+                format!("`{}` in a match pattern must be a class", token.lexeme)
+            }
+            RuntimeError::PermissionDenied(ref function, capability) => format!(
+                "Permission denied for `{}`, the program is not allowed to {}.",
+                function, capability
             ),
+            RuntimeError::IoError(ref function, ref message) => {
+                format!("`{}` failed: {}", function, message)
+            }
+            RuntimeError::Exit(code) => format!("Exited with code {}", code),
+        }
+    }
+}
+
+impl std::fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.token() {
+            Some(token) => write!(f, "[line {}] {}", token.line, self.message()),
+            None => f.write_str(&self.message()),
         }
     }
 }
@@ -128,6 +233,27 @@ impl std::error::Error for RuntimeError {
             RuntimeError::InvalidGetTarget(_) => "InvalidGetTarget",
             RuntimeError::UndefinedProperty(_) => "UndefinedProperty",
             RuntimeError::InvalidSuperclass(_) => "InvalidSuperclass",
+            RuntimeError::InvalidToString(_, _) => "InvalidToString",
+            RuntimeError::MissingOperatorMethod(_, _, _) => "MissingOperatorMethod",
+            RuntimeError::ReadOnlyProperty(_) => "ReadOnlyProperty",
+            RuntimeError::WriteOnlyProperty(_) => "WriteOnlyProperty",
+            RuntimeError::InvalidArgument(_, _) => "InvalidArgument",
+            RuntimeError::InvalidSpread(_) => "InvalidSpread",
+            RuntimeError::InvalidIndexTarget(_) => "InvalidIndexTarget",
+            RuntimeError::InvalidIndex(_) => "InvalidIndex",
+            RuntimeError::IndexOutOfBounds(_, _) => "IndexOutOfBounds",
+            RuntimeError::ModuloNonNumbers(_) => "ModuloNonNumbers",
+            RuntimeError::IntegerDivideNonNumbers(_) => "IntegerDivideNonNumbers",
+            RuntimeError::PowerNonNumbers(_) => "PowerNonNumbers",
+            RuntimeError::NotANumber(_) => "NotANumber",
+            RuntimeError::NonIntegerOperands(_) => "NonIntegerOperands",
+            RuntimeError::InvalidShift(_) => "InvalidShift",
+            RuntimeError::InvalidPatternClass(_) => "InvalidPatternClass",
+            RuntimeError::NotIterable(_) => "NotIterable",
+            RuntimeError::GeneratorAlreadyRunning(_) => "GeneratorAlreadyRunning",
+            RuntimeError::PermissionDenied(_, _) => "PermissionDenied",
+            RuntimeError::IoError(_, _) => "IoError",
+            RuntimeError::Exit(_) => "Exit",
         }
     }
 }
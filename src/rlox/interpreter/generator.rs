@@ -0,0 +1,385 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::interpreter::iteration::Iteration;
+use rlox::parser::{Expr, Pattern, Stmt};
+use rlox::token::{Token, TokenType};
+use rlox::environment::{Binding, Environment};
+use rlox::lox_value::LoxValue;
+use rlox::gc::{self, GcRef, Trace};
+
+/// Generator and async functions can't run on the Rust stack like the rest of the interpreter,
+/// since they stop at every `yield` or `await` and continue later on. Their body is compiled to a
+/// list of instructions instead, with jumps for the control flow, so the whole state of a
+/// suspended function is kept in a `Frame`.
+///
+/// Statements without any `yield` in them are run as a whole by the interpreter.
+#[derive(Debug)]
+pub enum Instruction {
+    Run(Stmt),
+    // Suspends the function with the value of the expression, and says where the value it's
+    // resumed with goes when it continues
+    Yield(Expr, Option<ResumeTarget>),
+    Jump(usize),
+    JumpUnless(Expr, usize),
+    BeginScope,
+    EndScope,
+    // Starts a for-in loop over the value of the expression
+    Iterate(Token, Expr),
+    // Defines the loop variable with the next item in a new scope, or jumps to the end of the
+    // loop when there are no more items
+    NextItem(Token, usize),
+    // Keeps the value being matched until `EndMatch`
+    Match(Expr),
+    // Starts the scope of the arm if its pattern and guard match, otherwise jumps to the next one
+    MatchArm(Pattern, Option<Expr>, usize),
+    EndMatch,
+}
+
+#[derive(Debug, Clone)]
+pub enum ResumeTarget {
+    // `var name = yield value;`
+    Define(Token),
+    // `name = yield value;`
+    Assign(Token, Option<Binding>),
+}
+
+/// Outcome of running a suspended function until it stops again.
+#[derive(Debug)]
+pub enum Step {
+    Yielded(LoxValue),
+    Returned(LoxValue),
+}
+
+/// Where a generator is at, and the state of the loops and `match` statements it is in.
+#[derive(Debug)]
+pub struct Frame {
+    code: Rc<Vec<Instruction>>,
+    // Index of the next instruction to run
+    position: usize,
+    env: Rc<RefCell<Environment>>,
+    iterations: Vec<Iteration>,
+    subjects: Vec<LoxValue>,
+    resume_target: Option<ResumeTarget>,
+}
+
+impl Frame {
+    pub fn new(code: Rc<Vec<Instruction>>, env: Rc<RefCell<Environment>>) -> Frame {
+        Frame {
+            code,
+            position: 0,
+            env,
+            iterations: Vec::new(),
+            subjects: Vec::new(),
+            resume_target: None,
+        }
+    }
+}
+
+impl Trace for Frame {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        children.push(GcRef::Environment(self.env.clone()));
+
+        for iteration in self.iterations.iter() {
+            iteration.trace(children);
+        }
+
+        for subject in self.subjects.iter() {
+            subject.trace(children);
+        }
+    }
+}
+
+/// Compiles the body of a function that uses `yield` or `await`. Returns `None` for any other
+/// function.
+pub fn compile_generator(body: &[Stmt]) -> Option<Rc<Vec<Instruction>>> {
+    if !body.iter().any(yields) {
+        return None;
+    }
+
+    let mut code = Vec::new();
+
+    for stmt in body {
+        compile(stmt, &mut code);
+    }
+
+    Some(Rc::new(code))
+}
+
+/// Whether the function uses `await`, making it async instead of a generator. The resolver
+/// makes sure `yield` and `await` aren't used in the same function.
+pub fn is_async(body: &[Stmt]) -> bool {
+    body.iter()
+        .filter_map(suspension)
+        .next()
+        .map(|keyword| keyword.token_type == TokenType::Await)
+        .unwrap_or(false)
+}
+
+/// Whether the statement has a `yield` or `await` of the function it's in.
+pub fn yields(stmt: &Stmt) -> bool {
+    suspension(stmt).is_some()
+}
+
+// Finds the first `yield` or `await` of a statement. The resolver only allows them in these
+// positions, and nested functions are generators of their own.
+fn suspension(stmt: &Stmt) -> Option<&Token> {
+    match *stmt {
+        Stmt::Expr(Expr::Yield(ref keyword, _)) | Stmt::Var(_, Expr::Yield(ref keyword, _)) => {
+            Some(keyword)
+        }
+        Stmt::Expr(Expr::Assign(_, ref value, _)) => match **value {
+            Expr::Yield(ref keyword, _) => Some(keyword),
+            _ => None,
+        },
+        Stmt::Block(ref stmts) => stmts.iter().filter_map(suspension).next(),
+        Stmt::If(_, ref then_branch, ref else_branch) => {
+            suspension(then_branch).or_else(|| match **else_branch {
+                Some(ref else_branch) => suspension(else_branch),
+                None => None,
+            })
+        }
+        Stmt::While(_, ref body) | Stmt::ForIn(_, _, ref body) => suspension(body),
+        Stmt::Match(_, _, ref arms) => arms.iter()
+            .filter_map(|&(_, _, ref body)| suspension(body))
+            .next(),
+        _ => None,
+    }
+}
+
+fn compile(stmt: &Stmt, code: &mut Vec<Instruction>) {
+    if !yields(stmt) {
+        code.push(Instruction::Run(stmt.clone()));
+        return;
+    }
+
+    match *stmt {
+        Stmt::Expr(Expr::Yield(_, ref value)) => {
+            code.push(Instruction::Yield((**value).clone(), None));
+        }
+        Stmt::Var(ref name, Expr::Yield(_, ref value)) => code.push(Instruction::Yield(
+            (**value).clone(),
+            Some(ResumeTarget::Define(name.clone())),
+        )),
+        Stmt::Expr(Expr::Assign(ref name, ref value, binding)) => {
+            if let Expr::Yield(_, ref value) = **value {
+                code.push(Instruction::Yield(
+                    (**value).clone(),
+                    Some(ResumeTarget::Assign(name.clone(), binding)),
+                ));
+            }
+        }
+        Stmt::Block(ref stmts) => {
+            code.push(Instruction::BeginScope);
+
+            for stmt in stmts {
+                compile(stmt, code);
+            }
+
+            code.push(Instruction::EndScope);
+        }
+        Stmt::If(ref condition, ref then_branch, ref else_branch) => {
+            let jump_to_else = code.len();
+            code.push(Instruction::JumpUnless(condition.clone(), 0));
+            compile(then_branch, code);
+
+            if let Some(ref else_branch) = **else_branch {
+                let jump_to_end = code.len();
+                code.push(Instruction::Jump(0));
+                patch(code, jump_to_else);
+                compile(else_branch, code);
+                patch(code, jump_to_end);
+            } else {
+                patch(code, jump_to_else);
+            }
+        }
+        Stmt::While(ref condition, ref body) => {
+            let start = code.len();
+            code.push(Instruction::JumpUnless(condition.clone(), 0));
+            compile(body, code);
+            code.push(Instruction::Jump(start));
+            patch(code, start);
+        }
+        Stmt::ForIn(ref name, ref iterable, ref body) => {
+            code.push(Instruction::Iterate(name.clone(), iterable.clone()));
+            let start = code.len();
+            code.push(Instruction::NextItem(name.clone(), 0));
+            compile(body, code);
+            code.push(Instruction::EndScope);
+            code.push(Instruction::Jump(start));
+            patch(code, start);
+        }
+        Stmt::Match(_, ref subject, ref arms) => {
+            code.push(Instruction::Match(subject.clone()));
+            let mut jumps_to_end = Vec::new();
+
+            for &(ref pattern, ref guard, ref body) in arms {
+                let arm = code.len();
+                code.push(Instruction::MatchArm(pattern.clone(), guard.clone(), 0));
+                compile(body, code);
+                code.push(Instruction::EndScope);
+                jumps_to_end.push(code.len());
+                code.push(Instruction::Jump(0));
+                patch(code, arm);
+            }
+
+            for jump in jumps_to_end {
+                patch(code, jump);
+            }
+
+            code.push(Instruction::EndMatch);
+        }
+        _ => code.push(Instruction::Run(stmt.clone())),
+    }
+}
+
+// Points the jump at `index` to the next instruction that will be added
+fn patch(code: &mut [Instruction], index: usize) {
+    let target = code.len();
+
+    match code[index] {
+        Instruction::Jump(ref mut position)
+        | Instruction::JumpUnless(_, ref mut position)
+        | Instruction::NextItem(_, ref mut position)
+        | Instruction::MatchArm(_, _, ref mut position) => *position = target,
+        _ => panic!("Only jump instructions can be patched"),
+    }
+}
+
+impl<'a> Interpreter<'a> {
+    /// Runs a suspended function until its next `yield` or `await`, or until it returns. `sent`
+    /// is the value of the `yield` or `await` it was suspended at.
+    pub fn resume_generator(
+        &mut self,
+        frame: &mut Frame,
+        sent: LoxValue,
+    ) -> Result<Step, RuntimeError> {
+        let parent_env = self.env.clone();
+        self.env = frame.env.clone();
+
+        let result = self.run_frame(frame, sent);
+
+        frame.env = self.env.clone();
+        self.env = parent_env;
+        result
+    }
+
+    fn run_frame(&mut self, frame: &mut Frame, sent: LoxValue) -> Result<Step, RuntimeError> {
+        match frame.resume_target.take() {
+            Some(ResumeTarget::Define(ref name)) => {
+                self.env.borrow_mut().define(&name.lexeme, sent)
+            }
+            Some(ResumeTarget::Assign(ref name, binding)) => {
+                self.assign_variable(name, binding, sent)?
+            }
+            None => {}
+        }
+
+        let code = frame.code.clone();
+
+        while let Some(instruction) = code.get(frame.position) {
+            frame.position += 1;
+
+            match *instruction {
+                Instruction::Run(ref stmt) => {
+                    if let Some(value) = self.interpret_stmt(stmt)? {
+                        return Ok(Step::Returned(value));
+                    }
+                }
+                Instruction::Yield(ref value, ref target) => {
+                    let value = self.interpret_expr(value)?;
+                    frame.resume_target = target.clone();
+                    return Ok(Step::Yielded(value));
+                }
+                Instruction::Jump(position) => frame.position = position,
+                Instruction::JumpUnless(ref condition, position) => {
+                    if !self.interpret_expr(condition)?.is_truthy() {
+                        frame.position = position;
+                    }
+                }
+                Instruction::BeginScope => self.begin_scope(),
+                Instruction::EndScope => self.end_scope(),
+                Instruction::Iterate(ref name, ref iterable) => {
+                    let iterable = self.interpret_expr(iterable)?;
+                    let iteration = Iteration::new(self, name, iterable)?;
+                    frame.iterations.push(iteration);
+                }
+                Instruction::NextItem(ref name, end) => {
+                    let item = match frame.iterations.last_mut() {
+                        Some(iteration) => iteration.next(self, name)?,
+                        None => {
+                            return Err(RuntimeError::InternalError(
+                                "Missing iteration in generator".to_string(),
+                            ))
+                        }
+                    };
+
+                    match item {
+                        Some(item) => {
+                            self.begin_scope();
+                            self.env.borrow_mut().define(&name.lexeme, item);
+                        }
+                        None => {
+                            frame.iterations.pop();
+                            frame.position = end;
+                        }
+                    }
+                }
+                Instruction::Match(ref subject) => {
+                    let subject = self.interpret_expr(subject)?;
+                    frame.subjects.push(subject);
+                }
+                Instruction::MatchArm(ref pattern, ref guard, next_arm) => {
+                    let subject = match frame.subjects.last() {
+                        Some(subject) => subject.clone(),
+                        None => {
+                            return Err(RuntimeError::InternalError(
+                                "Missing match subject in generator".to_string(),
+                            ))
+                        }
+                    };
+
+                    if !self.matches(pattern, &subject)? {
+                        frame.position = next_arm;
+                        continue;
+                    }
+
+                    self.begin_scope();
+
+                    if let Some(name) = pattern.binding() {
+                        self.env.borrow_mut().define(&name.lexeme, subject);
+                    }
+
+                    if let Some(ref guard) = *guard {
+                        if !self.interpret_expr(guard)?.is_truthy() {
+                            self.end_scope();
+                            frame.position = next_arm;
+                        }
+                    }
+                }
+                Instruction::EndMatch => {
+                    frame.subjects.pop();
+                }
+            }
+        }
+
+        Ok(Step::Returned(LoxValue::Nil))
+    }
+
+    fn begin_scope(&mut self) {
+        let env = Rc::new(RefCell::new(Environment::from_parent(self.env.clone())));
+        gc::track(GcRef::Environment(env.clone()));
+        self.env = env;
+    }
+
+    fn end_scope(&mut self) {
+        let enclosing = self.env
+            .borrow()
+            .enclosing
+            .clone()
+            .expect("A generator scope should always have an enclosing environment");
+        self.env = enclosing;
+    }
+}
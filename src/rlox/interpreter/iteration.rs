@@ -0,0 +1,108 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::{LoxFunc, LoxGenerator, LoxInstance, LoxValue};
+use rlox::token::Token;
+use rlox::gc::{GcRef, Trace};
+
+/// State of a for-in loop over a value.
+#[derive(Debug)]
+pub enum Iteration {
+    // The length is looked up on every step, since the body of the loop can change the list
+    List(Rc<RefCell<Vec<LoxValue>>>, usize),
+    String(Vec<char>, usize),
+    // The `hasNext()` and `next()` methods of an iterator
+    Iterator(LoxFunc, LoxFunc),
+    Generator(Rc<RefCell<LoxGenerator>>),
+}
+
+impl Iteration {
+    pub fn new(
+        interpreter: &mut Interpreter,
+        token: &Token,
+        iterable: LoxValue,
+    ) -> Result<Iteration, RuntimeError> {
+        match iterable {
+            LoxValue::List(list) => Ok(Iteration::List(list, 0)),
+            LoxValue::String(string) => Ok(Iteration::String(string.chars().collect(), 0)),
+            LoxValue::Generator(generator) => Ok(Iteration::Generator(generator)),
+            LoxValue::Instance(ref instance) => {
+                // Instances with an `iterator()` method return the object to iterate with,
+                // otherwise they have to be iterators themselves.
+                let iterator = match LoxInstance::find_method(instance, "iterator") {
+                    Some(method) => match interpreter.call_iterator_method(token, &method)? {
+                        LoxValue::Instance(iterator) => iterator,
+                        LoxValue::Generator(generator) => {
+                            return Ok(Iteration::Generator(generator))
+                        }
+                        _ => return Err(RuntimeError::NotIterable(token.clone())),
+                    },
+                    None => instance.clone(),
+                };
+
+                match (
+                    LoxInstance::find_method(&iterator, "hasNext"),
+                    LoxInstance::find_method(&iterator, "next"),
+                ) {
+                    (Some(has_next), Some(next)) => Ok(Iteration::Iterator(has_next, next)),
+                    _ => Err(RuntimeError::NotIterable(token.clone())),
+                }
+            }
+            _ => Err(RuntimeError::NotIterable(token.clone())),
+        }
+    }
+
+    /// Returns the next item, or `None` once the iteration is over.
+    pub fn next(
+        &mut self,
+        interpreter: &mut Interpreter,
+        token: &Token,
+    ) -> Result<Option<LoxValue>, RuntimeError> {
+        match *self {
+            Iteration::List(ref list, ref mut index) => {
+                let item = list.borrow().get(*index).cloned();
+                *index += 1;
+                Ok(item)
+            }
+            Iteration::String(ref characters, ref mut index) => {
+                let item = characters
+                    .get(*index)
+                    .map(|character| LoxValue::String(character.to_string()));
+                *index += 1;
+                Ok(item)
+            }
+            Iteration::Iterator(ref has_next, ref next) => {
+                if interpreter.call_iterator_method(token, has_next)?.is_truthy() {
+                    interpreter.call_iterator_method(token, next).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+            Iteration::Generator(ref generator) => {
+                if LoxGenerator::has_next(generator, interpreter)? {
+                    LoxGenerator::next(generator, interpreter).map(Some)
+                } else {
+                    Ok(None)
+                }
+            }
+        }
+    }
+}
+
+impl Trace for Iteration {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        match *self {
+            Iteration::List(ref list, _) => children.push(GcRef::List(list.clone())),
+            Iteration::String(_, _) => {}
+            Iteration::Iterator(ref has_next, ref next) => {
+                has_next.trace(children);
+                next.trace(children);
+            }
+            Iteration::Generator(ref generator) => {
+                children.push(GcRef::Generator(generator.clone()))
+            }
+        }
+    }
+}
@@ -1,61 +1,227 @@
 pub mod errors;
+pub mod generator;
+mod iteration;
 
+use std;
 use std::io;
+use std::io::BufRead;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::collections::hash_map::HashMap;
 
 use self::errors::RuntimeError;
-use rlox::lox_value::{LoxClass, LoxFunc, LoxValue, ValueError};
-use rlox::parser::{Expr, Stmt};
-use rlox::token::TokenType;
-use rlox::environment::Environment;
+use self::iteration::Iteration;
+pub use self::generator::{compile_generator, is_async, Frame, Instruction, Step};
+use rlox::lox_value::{format_list, GeneratorMethod, LoxClass, LoxFunc, LoxInstance, LoxValue,
+                      PromiseMethod, ValueError};
+use rlox::parser::{Expr, MethodKind, Pattern, Stmt};
+use rlox::token::{Token, TokenType};
+use rlox::environment::{Binding, Environment, Symbol};
+use rlox::callables::Callable;
+use rlox::callables::native::clock::{Clock, SystemClock};
+use rlox::event_loop::EventLoop;
+use rlox::capabilities::{Capabilities, Capability};
+use rlox::gc::{self, GcRef};
+use rlox::errors::StackFrame;
 
 pub struct Interpreter<'a> {
     env: Rc<RefCell<Environment>>,
     globals: Rc<RefCell<Environment>>,
     writer: Rc<RefCell<&'a mut io::Write>>,
+    // Warnings and `eprint()` go here, it's the same as `writer` unless set otherwise
+    error_writer: Rc<RefCell<&'a mut io::Write>>,
+    // Read by `readLine()` and `input()`, stdin when `None`
+    reader: Option<Rc<RefCell<&'a mut io::BufRead>>>,
+    event_loop: EventLoop,
+    capabilities: Capabilities,
+    // Returned by `args()`
+    args: Vec<String>,
+    // Calls the last runtime error went through, innermost first
+    stack_trace: Vec<StackFrame>,
 }
 
 impl<'a> Interpreter<'a> {
     pub fn new(writer: Rc<RefCell<&'a mut io::Write>>) -> Interpreter<'a> {
+        Interpreter::with_clock(writer, Rc::new(SystemClock::new()))
+    }
+
+    /// Builds an interpreter whose `clock()` and timers follow the given clock.
+    pub fn with_clock(
+        writer: Rc<RefCell<&'a mut io::Write>>,
+        clock: Rc<Clock>,
+    ) -> Interpreter<'a> {
         let globals = Rc::new(RefCell::new(Environment::global()));
+        gc::track(GcRef::Environment(globals.clone()));
 
         Interpreter {
             env: globals.clone(),
             globals: globals.clone(),
+            error_writer: writer.clone(),
             writer,
+            reader: None,
+            event_loop: EventLoop::new(clock),
+            capabilities: Capabilities::none(),
+            args: Vec::new(),
+            stack_trace: Vec::new(),
         }
     }
 
     pub fn interpret(&mut self, stmts: Vec<Stmt>) -> Option<RuntimeError> {
+        self.stack_trace.clear();
+
         for stmt in stmts.iter() {
             if let Err(err) = self.interpret_stmt(stmt) {
                 return Some(err);
             }
+
+            gc::maybe_collect();
         }
 
         None
     }
 
+    /// Same as `interpret`, returning the value of the last statement if it's an expression
+    /// statement. Used by the REPL to show the value of what was typed.
+    pub fn interpret_with_value(
+        &mut self,
+        stmts: Vec<Stmt>,
+    ) -> Result<Option<LoxValue>, RuntimeError> {
+        let last = stmts.len().saturating_sub(1);
+        self.stack_trace.clear();
+
+        for (i, stmt) in stmts.iter().enumerate() {
+            match *stmt {
+                Stmt::Expr(ref expr) if i == last => return self.interpret_expr(expr).map(Some),
+                ref stmt => self.interpret_stmt(stmt)?,
+            };
+
+            gc::maybe_collect();
+        }
+
+        Ok(None)
+    }
+
+    /// Runs the callbacks of timers and promises until there are none left.
+    pub fn run_event_loop(&mut self) -> Option<RuntimeError> {
+        while let Some(task) = self.event_loop.next() {
+            if let Err(err) = task.run(self) {
+                return Some(err);
+            }
+
+            gc::maybe_collect();
+        }
+
+        None
+    }
+
+    /// The calls that were running when the last runtime error happened, innermost first.
+    pub fn stack_trace(&self) -> &[StackFrame] {
+        &self.stack_trace
+    }
+
+    pub fn event_loop(&mut self) -> &mut EventLoop {
+        &mut self.event_loop
+    }
+
+    /// Source of time of the interpreter, shared with the timers of the event loop.
+    pub fn clock(&self) -> &Clock {
+        self.event_loop.clock()
+    }
+
+    pub fn set_capabilities(&mut self, capabilities: Capabilities) {
+        self.capabilities = capabilities;
+    }
+
+    /// Checks that the program is allowed to use a capability before `function` uses it.
+    pub fn require(&self, function: &str, capability: Capability) -> Result<(), RuntimeError> {
+        if self.capabilities.allows(capability) {
+            Ok(())
+        } else {
+            Err(RuntimeError::PermissionDenied(
+                function.to_string(),
+                capability,
+            ))
+        }
+    }
+
+    /// Every global with its value, sorted by name.
+    pub fn globals(&self) -> Vec<(String, LoxValue)> {
+        self.globals.borrow().entries()
+    }
+
+    pub fn set_args(&mut self, args: Vec<String>) {
+        self.args = args;
+    }
+
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    pub fn set_error_writer(&mut self, error_writer: Rc<RefCell<&'a mut io::Write>>) {
+        self.error_writer = error_writer;
+    }
+
+    pub fn set_reader(&mut self, reader: Rc<RefCell<&'a mut io::BufRead>>) {
+        self.reader = Some(reader);
+    }
+
+    /// Writes text to the same output as `print`, without a trailing newline.
+    pub fn write(&mut self, text: &str) {
+        let mut writer = self.writer.borrow_mut();
+        writer
+            .write_all(text.as_ref())
+            .expect("Error writing to stdout/writer");
+        writer.flush().expect("Error flushing stdout/writer");
+    }
+
+    /// Writes a line to the error output.
+    pub fn write_error(&mut self, line: &str) {
+        self.error_writer
+            .borrow_mut()
+            .write_all(format!("{}\n", line).as_ref())
+            .expect("Error writing to stderr/error writer");
+    }
+
+    /// Reports a problem that doesn't stop the program, on the error output.
+    pub fn warn(&mut self, message: &str) {
+        self.write_error(message);
+    }
+
+    /// Reads a line from the input, including its line ending. Returns `0` at the end of the
+    /// input.
+    pub fn read_line(&mut self, line: &mut String) -> io::Result<usize> {
+        match self.reader {
+            Some(ref reader) => reader.borrow_mut().read_line(line),
+            None => {
+                let stdin = io::stdin();
+                let mut stdin = stdin.lock();
+                stdin.read_line(line)
+            }
+        }
+    }
+
     fn interpret_stmt(&mut self, stmt: &Stmt) -> Result<Option<LoxValue>, RuntimeError> {
         match *stmt {
-            Stmt::Print(ref expr) => self.interpret_expr(expr).map(|val| {
+            Stmt::Print(ref keyword, ref expr) => {
+                let value = self.interpret_expr(expr)?;
+                let string = self.stringify(&value, keyword)?;
+
                 self.writer
                     .borrow_mut()
-                    .write_all(format!("{}\n", val).as_ref())
+                    .write_all(format!("{}\n", string).as_ref())
                     .expect("Error writing to stdout/writer");
-                None
-            }),
+                Ok(None)
+            }
             Stmt::Expr(ref expr) => self.interpret_expr(expr).map(|_| None),
             Stmt::Var(ref token, ref expr) => self.interpret_expr(expr).map(|value| {
-                self.env.borrow_mut().define(token.lexeme.clone(), value);
+                self.env.borrow_mut().define(&token.lexeme, value);
                 None
             }),
             Stmt::Block(ref statements) => {
-                let env = Environment::from_parent(self.env.clone());
+                let env = Rc::new(RefCell::new(Environment::from_parent(self.env.clone())));
+                gc::track(GcRef::Environment(env.clone()));
 
-                self.interpret_block(statements, RefCell::new(env))
+                self.interpret_block(statements, env)
             }
             Stmt::If(ref condition, ref then_branch, ref else_branch) => {
                 self.interpret_expr(condition).and_then(|condition_result| {
@@ -75,15 +241,21 @@ impl<'a> Interpreter<'a> {
 
                 Ok(None)
             }
+            Stmt::ForIn(ref name, ref iterable, ref body) => {
+                let iterable = self.interpret_expr(iterable)?;
+                self.interpret_for_in(name, iterable, body)
+            }
             Stmt::Func(ref name, _, _) => {
-                let func =
-                    LoxValue::Func(Rc::new(LoxFunc::new(stmt.clone(), self.env.clone(), false)));
-                self.env.borrow_mut().define(name.lexeme.clone(), func);
+                let func = LoxFunc::new(stmt.clone(), self.env.clone(), false).into_value();
+                self.env.borrow_mut().define(&name.lexeme, func);
                 Ok(None)
             }
             Stmt::Return(_, ref expr) => Ok(Some(self.interpret_expr(expr)?)),
             Stmt::Class(ref token, ref superclass, ref method_statements) => {
                 let mut methods = HashMap::new();
+                let mut getters = HashMap::new();
+                let mut setters = HashMap::new();
+                let mut static_methods = HashMap::new();
                 let mut parent_env = None;
 
                 let resolved_superclass = if let &Some(ref superclass) = superclass {
@@ -94,23 +266,33 @@ impl<'a> Interpreter<'a> {
 
                     parent_env = Some(self.env.clone());
                     let mut env = Environment::from_parent(self.env.clone());
-                    env.define("super".to_string(), LoxValue::Class(superclass.clone()));
+                    env.define("super", LoxValue::Class(superclass.clone()));
                     self.env = Rc::new(RefCell::new(env));
+                    gc::track(GcRef::Environment(self.env.clone()));
 
                     Some(superclass)
                 } else {
                     None
                 };
 
-                for method_statement in method_statements {
+                for &(kind, ref method_statement) in method_statements {
                     match method_statement {
                         &Stmt::Func(ref name, _, _) => {
-                            let method = LoxValue::Func(Rc::new(LoxFunc::new(
+                            let is_initializer =
+                                kind == MethodKind::Method && name.lexeme == "init";
+                            let method = LoxFunc::new(
                                 method_statement.clone(),
                                 self.env.clone(),
-                                name.lexeme == "init",
-                            )));
-                            methods.insert(name.lexeme.clone(), method);
+                                is_initializer,
+                            ).into_value();
+
+                            let members = match kind {
+                                MethodKind::Method => &mut methods,
+                                MethodKind::Getter => &mut getters,
+                                MethodKind::Setter => &mut setters,
+                                MethodKind::Static => &mut static_methods,
+                            };
+                            members.insert(name.lexeme.clone(), method);
                         }
                         _ => {
                             return Err(RuntimeError::InternalError(
@@ -120,31 +302,138 @@ impl<'a> Interpreter<'a> {
                     };
                 }
 
-                let class = LoxValue::Class(Rc::new(LoxClass::new(
+                let class = LoxClass::new(
                     token.lexeme.clone(),
                     resolved_superclass,
                     methods,
-                )));
+                    getters,
+                    setters,
+                    static_methods,
+                ).into_value();
 
                 if superclass.is_some() {
                     self.env = parent_env.expect("When interpreting a subclass, a parent environment should always be present");
                 }
 
-                self.env.borrow_mut().define(token.lexeme.clone(), class);
+                self.env.borrow_mut().define(&token.lexeme, class);
+
+                Ok(None)
+            }
+            Stmt::Match(_, ref subject, ref arms) => {
+                let value = self.interpret_expr(subject)?;
+
+                for &(ref pattern, ref guard, ref body) in arms {
+                    if !self.matches(pattern, &value)? {
+                        continue;
+                    }
+
+                    let env = Rc::new(RefCell::new(Environment::from_parent(self.env.clone())));
+                    gc::track(GcRef::Environment(env.clone()));
+
+                    if let Some(name) = pattern.binding() {
+                        env.borrow_mut().define(&name.lexeme, value.clone());
+                    }
 
+                    if let Some(ref guard) = *guard {
+                        if !self.interpret_expr_in(guard, env.clone())?.is_truthy() {
+                            continue;
+                        }
+                    }
+
+                    return self.interpret_block(std::slice::from_ref(body), env);
+                }
+
+                // Nothing happens when no arm matches
                 Ok(None)
             }
         }
     }
 
+    fn interpret_for_in(
+        &mut self,
+        name: &Token,
+        iterable: LoxValue,
+        body: &Stmt,
+    ) -> Result<Option<LoxValue>, RuntimeError> {
+        let mut iteration = Iteration::new(self, name, iterable)?;
+
+        while let Some(item) = iteration.next(self, name)? {
+            if let Some(value) = self.interpret_iteration(name, item, body)? {
+                return Ok(Some(value));
+            }
+        }
+
+        Ok(None)
+    }
+
+    // Runs the body of a for-in loop in a new environment, so closures created in it capture
+    // the value of this iteration
+    fn interpret_iteration(
+        &mut self,
+        name: &Token,
+        item: LoxValue,
+        body: &Stmt,
+    ) -> Result<Option<LoxValue>, RuntimeError> {
+        let env = Rc::new(RefCell::new(Environment::from_parent(self.env.clone())));
+        gc::track(GcRef::Environment(env.clone()));
+        env.borrow_mut().define(&name.lexeme, item);
+
+        self.interpret_block(std::slice::from_ref(body), env)
+    }
+
+    fn call_iterator_method(
+        &mut self,
+        name: &Token,
+        method: &LoxFunc,
+    ) -> Result<LoxValue, RuntimeError> {
+        if !method.arity().accepts(0) {
+            return Err(RuntimeError::WrongArity(name.clone(), 0, method.arity()));
+        }
+
+        method.call(self, Vec::new())
+    }
+
+    fn matches(&mut self, pattern: &Pattern, value: &LoxValue) -> Result<bool, RuntimeError> {
+        match *pattern {
+            Pattern::Wildcard(_) | Pattern::Binding(_) => Ok(true),
+            Pattern::Literal(_, ref literal) => match literal.value() {
+                Some(literal) => Ok(literal
+                    .is_equal(value)
+                    .map(|is_equal| is_equal.is_truthy())
+                    .unwrap_or(false)),
+                None => Err(RuntimeError::InternalError(
+                    "Invalid literal - no value".to_string(),
+                )),
+            },
+            Pattern::Class(ref class, _) => {
+                let class = match (self.interpret_expr(class)?, class) {
+                    (LoxValue::Class(class), _) => class,
+                    (_, &Expr::Var(ref token, _)) => {
+                        return Err(RuntimeError::InvalidPatternClass(token.clone()))
+                    }
+                    _ => {
+                        return Err(RuntimeError::InternalError(
+                            "Found a class pattern without a name".to_string(),
+                        ))
+                    }
+                };
+
+                Ok(match *value {
+                    LoxValue::Instance(ref instance) => class.has_instance(&instance.borrow()),
+                    _ => false,
+                })
+            }
+        }
+    }
+
     pub fn interpret_block(
         &mut self,
-        statements: &Vec<Stmt>,
-        environment: RefCell<Environment>,
+        statements: &[Stmt],
+        environment: Rc<RefCell<Environment>>,
     ) -> Result<Option<LoxValue>, RuntimeError> {
         let mut return_value = None;
         let parent_env = self.env.clone();
-        self.env = Rc::new(environment);
+        self.env = environment;
 
         for ref stmt in statements {
             return_value = self.interpret_stmt(stmt)?;
@@ -152,12 +441,269 @@ impl<'a> Interpreter<'a> {
             if return_value.is_some() {
                 break;
             }
+
+            gc::maybe_collect();
         }
 
         self.env = parent_env;
         Ok(return_value)
     }
 
+    /// Evaluates an expression in the given environment instead of the current one.
+    pub fn interpret_expr_in(
+        &mut self,
+        expr: &Expr,
+        environment: Rc<RefCell<Environment>>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let parent_env = self.env.clone();
+        self.env = environment;
+        let result = self.interpret_expr(expr);
+        self.env = parent_env;
+
+        result
+    }
+
+    /// Converts a value to a string, calling its `toString()` method if it's an instance of a
+    /// class that defines one. `token` is the one of the code converting it, like a `print`.
+    pub fn stringify(&mut self, value: &LoxValue, token: &Token) -> Result<String, RuntimeError> {
+        self.stringify_or(value, &|class_name| {
+            Err(RuntimeError::InvalidToString(token.clone(), class_name))
+        })
+    }
+
+    /// Same as `stringify`, for conversions that aren't part of the code, like the ones of
+    /// natives. `invalid` gives the result for an instance whose `toString()` takes arguments
+    /// or doesn't return a string, from the name of its class.
+    pub fn stringify_or(
+        &mut self,
+        value: &LoxValue,
+        invalid: &Fn(String) -> Result<String, RuntimeError>,
+    ) -> Result<String, RuntimeError> {
+        if let LoxValue::List(ref list) = *value {
+            return format_list(list, &mut |item| self.stringify_or(item, invalid));
+        }
+
+        if let LoxValue::Instance(ref instance) = *value {
+            if let Some(method) = LoxInstance::find_method(instance, "toString") {
+                let class_name = instance.borrow().get_class_name().to_string();
+
+                if !method.arity().accepts(0) {
+                    return invalid(class_name);
+                }
+
+                return match method.call(self, Vec::new())? {
+                    LoxValue::String(string) => Ok(string),
+                    _ => invalid(class_name),
+                };
+            }
+        }
+
+        Ok(value.to_string())
+    }
+
+    /// Binary operators with an instance on the left side are resolved by calling one of its
+    /// methods, e.g. `a + b` calls `a.__add__(b)`. Returns `None` when the operator should keep
+    /// its default behavior, which for instances is only the case for `==`, `!=` (comparing by
+    /// identity) and `+` with a string (converting the instance to a string).
+    fn call_operator_method(
+        &mut self,
+        instance: &Rc<RefCell<LoxInstance>>,
+        operator: &Token,
+        argument: &LoxValue,
+    ) -> Result<Option<LoxValue>, RuntimeError> {
+        let method_name = match operator_method_name(operator.token_type) {
+            Some(method_name) => method_name,
+            None => return Ok(None),
+        };
+
+        let method = match LoxInstance::find_method(instance, method_name) {
+            Some(method) => method,
+            None => {
+                return match (operator.token_type, argument) {
+                    (TokenType::EqualEqual, _)
+                    | (TokenType::BangEqual, _)
+                    | (TokenType::Plus, &LoxValue::String(_)) => Ok(None),
+                    _ => Err(RuntimeError::MissingOperatorMethod(
+                        operator.clone(),
+                        instance.borrow().get_class_name().to_string(),
+                        method_name.to_string(),
+                    )),
+                }
+            }
+        };
+
+        if !method.arity().accepts(1) {
+            return Err(RuntimeError::WrongArity(operator.clone(), 1, method.arity()));
+        }
+
+        let result = method.call(self, vec![argument.clone()])?;
+
+        Ok(Some(match operator.token_type {
+            TokenType::EqualEqual => LoxValue::Bool(result.is_truthy()),
+            TokenType::BangEqual => LoxValue::Bool(!result.is_truthy()),
+            _ => result,
+        }))
+    }
+
+    /// Evaluates call arguments or list elements, expanding the ones spread with `...`.
+    fn interpret_elements(&mut self, elements: &[Expr]) -> Result<Vec<LoxValue>, RuntimeError> {
+        let mut values = Vec::with_capacity(elements.len());
+
+        for element in elements {
+            match *element {
+                Expr::Spread(ref token, ref expr) => match self.interpret_expr(expr)? {
+                    LoxValue::List(ref list) => values.extend(list.borrow().iter().cloned()),
+                    _ => return Err(RuntimeError::InvalidSpread(token.clone())),
+                },
+                _ => values.push(self.interpret_expr(element)?),
+            }
+        }
+
+        Ok(values)
+    }
+
+    fn binary_operation(
+        &mut self,
+        left_value: LoxValue,
+        operator: &Token,
+        right_value: LoxValue,
+    ) -> Result<LoxValue, RuntimeError> {
+        if let LoxValue::Instance(ref instance) = left_value {
+            if let Some(result) = self.call_operator_method(instance, operator, &right_value)? {
+                return Ok(result);
+            }
+        }
+
+        if operator.token_type == TokenType::Plus {
+            if let (&LoxValue::String(_), _) | (_, &LoxValue::String(_)) =
+                (&left_value, &right_value)
+            {
+                let left_string = self.stringify(&left_value, operator)?;
+                let right_string = self.stringify(&right_value, operator)?;
+                return Ok(LoxValue::String(left_string + &right_string));
+            }
+        }
+
+        apply_binary_operator(left_value, operator, right_value)
+    }
+
+    fn look_up_variable(
+        &self,
+        token: &Token,
+        binding: Option<Binding>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let value = match binding.or_else(|| unresolved_binding(token)) {
+            Some(Binding::Local(depth, slot)) => self.env.borrow().get_at(depth, slot),
+            Some(Binding::Global(symbol)) => self.globals.borrow().get_global(symbol),
+            None => return Err(RuntimeError::UndefinedVariable(token.clone())),
+        };
+
+        value.map_err(|_| RuntimeError::UndefinedVariable(token.clone()))
+    }
+
+    fn assign_variable(
+        &mut self,
+        token: &Token,
+        binding: Option<Binding>,
+        value: LoxValue,
+    ) -> Result<(), RuntimeError> {
+        let result = match binding.or_else(|| unresolved_binding(token)) {
+            Some(Binding::Local(depth, slot)) => {
+                self.env.borrow_mut().assign_at(depth, slot, value)
+            }
+            Some(Binding::Global(symbol)) => {
+                self.globals.borrow_mut().assign_global(symbol, value)
+            }
+            None => return Err(RuntimeError::UndefinedVariable(token.clone())),
+        };
+
+        result.map_err(|_| RuntimeError::UndefinedVariable(token.clone()))
+    }
+
+    fn get_property(
+        &mut self,
+        target: &LoxValue,
+        token: &Token,
+    ) -> Result<LoxValue, RuntimeError> {
+        match *target {
+            LoxValue::Instance(ref instance) => {
+                match LoxInstance::find_getter(instance, &token.lexeme) {
+                    Some(getter) => getter.call(self, Vec::new()),
+                    None => LoxInstance::get(instance, token),
+                }
+            }
+            LoxValue::Class(ref class) => class
+                .find_static_method(&token.lexeme)
+                .ok_or_else(|| RuntimeError::UndefinedProperty(token.clone())),
+            LoxValue::Generator(ref generator) => GeneratorMethod::find(generator, &token.lexeme)
+                .ok_or_else(|| RuntimeError::UndefinedProperty(token.clone())),
+            LoxValue::Promise(ref promise) => PromiseMethod::find(promise, &token.lexeme)
+                .ok_or_else(|| RuntimeError::UndefinedProperty(token.clone())),
+            _ => Err(RuntimeError::InvalidGetTarget(token.clone())),
+        }
+    }
+
+    fn set_property(
+        &mut self,
+        target: &LoxValue,
+        token: &Token,
+        value: LoxValue,
+    ) -> Result<(), RuntimeError> {
+        let instance = match *target {
+            LoxValue::Instance(ref instance) => instance,
+            _ => return Err(RuntimeError::InvalidGetTarget(token.clone())),
+        };
+
+        if let Some(setter) = LoxInstance::find_setter(instance, &token.lexeme) {
+            setter.call(self, vec![value])?;
+        } else if instance.borrow().has_getter(&token.lexeme) {
+            return Err(RuntimeError::ReadOnlyProperty(token.clone()));
+        } else {
+            instance.borrow_mut().set(&token.lexeme, value);
+        }
+
+        Ok(())
+    }
+
+    /// Reads an assignable expression, computes its new value and writes it back, evaluating the
+    /// parts of the target (the object of a property, the list and index of an element) only
+    /// once. Returns both the old and the new values.
+    fn update(
+        &mut self,
+        target: &Expr,
+        compute: &mut FnMut(&mut Interpreter<'a>, LoxValue) -> Result<LoxValue, RuntimeError>,
+    ) -> Result<(LoxValue, LoxValue), RuntimeError> {
+        match *target {
+            Expr::Var(ref token, distance) => {
+                let old_value = self.look_up_variable(token, distance)?;
+                let new_value = compute(self, old_value.clone())?;
+                self.assign_variable(token, distance, new_value.clone())?;
+
+                Ok((old_value, new_value))
+            }
+            Expr::Get(ref object, ref token) => {
+                let resolved_object = self.interpret_expr(object)?;
+                let old_value = self.get_property(&resolved_object, token)?;
+                let new_value = compute(self, old_value.clone())?;
+                self.set_property(&resolved_object, token, new_value.clone())?;
+
+                Ok((old_value, new_value))
+            }
+            Expr::Index(ref list, ref bracket, ref index) => {
+                let resolved_list = self.interpret_expr(list)?;
+                let resolved_index = self.interpret_expr(index)?;
+                let old_value = get_index(&resolved_list, bracket, &resolved_index)?;
+                let new_value = compute(self, old_value.clone())?;
+                set_index(&resolved_list, bracket, &resolved_index, new_value.clone())?;
+
+                Ok((old_value, new_value))
+            }
+            _ => Err(RuntimeError::InternalError(
+                "Invalid target for an update".to_string(),
+            )),
+        }
+    }
+
     fn interpret_expr(&mut self, expr: &Expr) -> Result<LoxValue, RuntimeError> {
         match *expr {
             Expr::Literal(ref literal) => {
@@ -180,6 +726,9 @@ impl<'a> Interpreter<'a> {
                     TokenType::Bang => value
                         .negate()
                         .map_err(|_| RuntimeError::InternalError("Can't negate value".to_string())),
+                    TokenType::Tilde => value
+                        .bitwise_not()
+                        .map_err(|_| RuntimeError::NonIntegerOperands(token.clone())),
                     _ => Err(RuntimeError::InternalError(format!(
                         "Invalid unary operator: {:?}",
                         token
@@ -190,77 +739,45 @@ impl<'a> Interpreter<'a> {
                 let left_value = self.interpret_expr(left)?;
                 let right_value = self.interpret_expr(right)?;
 
-                match operator.token_type {
-                    TokenType::Minus => left_value
-                        .subtract(right_value)
-                        .map_err(|_| RuntimeError::SubtractNonNumbers(operator.clone())),
-                    TokenType::Slash => left_value.divide(right_value).map_err(|err| match err {
-                        ValueError::DivideByZero => {
-                            RuntimeError::DivideByZeroError(operator.clone())
-                        }
-                        _ => RuntimeError::DivideNonNumbers(operator.clone()),
-                    }),
-                    TokenType::Star => left_value
-                        .multiply(right_value)
-                        .map_err(|_| RuntimeError::MultiplyNonNumbers(operator.clone())),
-                    TokenType::Plus => left_value
-                        .plus(right_value)
-                        .map_err(|_| RuntimeError::PlusTypeError(operator.clone())),
-                    TokenType::Greater => left_value
-                        .is_greater(right_value)
-                        .map_err(|_| RuntimeError::GreaterNonNumbers(operator.clone())),
-                    TokenType::GreaterEqual => left_value
-                        .is_greater_equal(right_value)
-                        .map_err(|_| RuntimeError::GreaterEqualNonNumbers(operator.clone())),
-                    TokenType::Less => left_value
-                        .is_less(right_value)
-                        .map_err(|_| RuntimeError::LessNonNumbers(operator.clone())),
-                    TokenType::LessEqual => left_value
-                        .is_less_equal(right_value)
-                        .map_err(|_| RuntimeError::LessEqualNonNumbers(operator.clone())),
-                    TokenType::BangEqual => left_value.is_not_equal(&right_value).map_err(|_| {
-                        RuntimeError::InternalError("Can't check non-equality".to_string())
-                    }),
-                    TokenType::EqualEqual => left_value.is_equal(&right_value).map_err(|_| {
-                        RuntimeError::InternalError("Can't check equality".to_string())
-                    }),
-                    _ => Err(RuntimeError::InternalError(format!(
-                        "Invalid binary operator: {:?}",
-                        operator
-                    ))),
-                }
+                self.binary_operation(left_value, operator, right_value)
             }
-            Expr::Var(ref token, ref distance) => match distance {
-                &Some(distance) => match self.env.borrow().get_at(&token.lexeme, distance) {
-                    Ok(value) => Ok(value.clone()),
-                    Err(_) => Err(RuntimeError::UndefinedVariable(token.clone())),
-                },
-                &None => match self.globals.borrow().get(&token.lexeme) {
-                    Ok(value) => Ok(value.clone()),
-                    Err(_) => Err(RuntimeError::UndefinedVariable(token.clone())),
-                },
-            },
-            Expr::Assign(ref token, ref expr, ref distance) => {
+            Expr::Var(ref token, distance) => self.look_up_variable(token, distance),
+            Expr::Assign(ref token, ref expr, distance) => {
                 let value = self.interpret_expr(expr)?;
-
-                match distance {
-                    &Some(distance) => match self.env.borrow_mut().assign_at(
-                        &token.lexeme,
-                        value.clone(),
-                        distance,
-                    ) {
-                        Ok(()) => Ok(value.clone()),
-                        Err(_) => Err(RuntimeError::UndefinedVariable(token.clone())),
-                    },
-                    &None => match self.globals
-                        .borrow_mut()
-                        .assign(&token.lexeme, value.clone())
-                    {
-                        Ok(()) => Ok(value.clone()),
-                        Err(_) => Err(RuntimeError::UndefinedVariable(token.clone())),
-                    },
+                self.assign_variable(token, distance, value.clone())?;
+                Ok(value)
+            }
+            Expr::Conditional(ref condition, ref then_branch, ref else_branch) => {
+                if self.interpret_expr(condition)?.is_truthy() {
+                    self.interpret_expr(then_branch)
+                } else {
+                    self.interpret_expr(else_branch)
                 }
             }
+            Expr::CompoundAssign(ref target, ref operator, ref expr) => {
+                let (_, value) = self.update(target, &mut |interpreter, old_value| {
+                    let right_value = interpreter.interpret_expr(expr)?;
+                    interpreter.binary_operation(old_value, operator, right_value)
+                })?;
+
+                Ok(value)
+            }
+            Expr::Postfix(ref target, ref operator) => {
+                let (old_value, _) = self.update(target, &mut |interpreter, old_value| {
+                    interpreter.binary_operation(old_value, operator, LoxValue::Number(1.0))
+                })?;
+
+                Ok(old_value)
+            }
+            Expr::Lambda(_, ref parameters, ref body) => Ok(LoxFunc::anonymous(
+                parameters.clone(),
+                (**body).clone(),
+                self.env.clone(),
+            ).into_value()),
+            Expr::Stringify(ref token, ref expr) => {
+                let value = self.interpret_expr(expr)?;
+                Ok(LoxValue::String(self.stringify(&value, token)?))
+            }
             Expr::Logical(ref left, ref operator, ref right) => {
                 let left_value = self.interpret_expr(left)?;
 
@@ -281,68 +798,83 @@ impl<'a> Interpreter<'a> {
                     .get_callable()
                     .ok_or_else(|| RuntimeError::CallOnNonCallable(token.clone()))?;
 
-                let mut evaluated_args: Vec<LoxValue> = Vec::new();
+                let evaluated_args = self.interpret_elements(arguments)?;
 
-                for arg in arguments {
-                    evaluated_args.push(self.interpret_expr(arg)?);
-                }
-
-                if arguments.len() != callable.arity() {
+                if !callable.arity().accepts(evaluated_args.len()) {
                     return Err(RuntimeError::WrongArity(
                         token.clone(),
-                        arguments.len(),
+                        evaluated_args.len(),
                         callable.arity(),
                     ));
                 }
 
-                callable.call(self, evaluated_args)
-            }
-            Expr::Get(ref target, ref token) => {
-                let resolved_target = self.interpret_expr(target)?;
+                let result = callable.call(self, evaluated_args);
 
-                match resolved_target {
-                    // TODO: Don't clone!
-                    LoxValue::Instance(ref instance) => Ok(instance.borrow().get(&token)?.clone()),
-                    _ => Err(RuntimeError::InvalidGetTarget(token.clone())),
+                // Each call adds itself to the stack trace as an error unwinds through it
+                if let Err(ref err) = result {
+                    if !matches!(*err, RuntimeError::Exit(_)) {
+                        self.stack_trace.push(StackFrame {
+                            function: function_name(&callable, callee),
+                            line: token.line,
+                        });
+                    }
                 }
+
+                result
             }
-            Expr::Set(ref target, ref token, ref expr) => {
+            Expr::List(_, ref items) => Ok(LoxValue::list(self.interpret_elements(items)?)),
+            Expr::Index(ref target, ref bracket, ref index) => {
                 let resolved_target = self.interpret_expr(target)?;
+                let resolved_index = self.interpret_expr(index)?;
 
-                let value = match resolved_target {
-                    LoxValue::Instance(instance) => {
-                        let resolved_value = self.interpret_expr(expr)?;
-
-                        // TODO: Don't clone!
-                        instance
-                            .borrow_mut()
-                            .set(&token.lexeme, resolved_value.clone());
-                        resolved_value.clone()
-                    }
-                    _ => return Err(RuntimeError::InvalidGetTarget(token.clone())),
-                };
+                get_index(&resolved_target, bracket, &resolved_index)
+            }
+            Expr::SetIndex(ref target, ref bracket, ref index, ref expr) => {
+                let resolved_target = self.interpret_expr(target)?;
+                let resolved_index = self.interpret_expr(index)?;
+                let value = self.interpret_expr(expr)?;
 
+                set_index(&resolved_target, bracket, &resolved_index, value.clone())?;
                 Ok(value)
             }
+            Expr::Spread(_, _) => Err(RuntimeError::InternalError(
+                "Found a spread outside of a call or a list".to_string(),
+            )),
+            Expr::Yield(_, _) => Err(RuntimeError::InternalError(
+                "Found a yield outside of a generator statement".to_string(),
+            )),
+            Expr::Get(ref target, ref token) => {
+                let resolved_target = self.interpret_expr(target)?;
+                self.get_property(&resolved_target, token)
+            }
+            Expr::Set(ref target, ref token, ref expr) => {
+                let resolved_target = self.interpret_expr(target)?;
+
+                if let LoxValue::Instance(_) = resolved_target {
+                    let value = self.interpret_expr(expr)?;
+                    self.set_property(&resolved_target, token, value.clone())?;
+                    Ok(value)
+                } else {
+                    Err(RuntimeError::InvalidGetTarget(token.clone()))
+                }
+            }
+            // `this` and `super` are the only variable in the environments that bind them
             Expr::This(ref token, ref distance) => match distance {
-                &Some(distance) => match self.env.borrow().get_at(&token.lexeme, distance) {
-                    Ok(value) => Ok(value.clone()),
-                    Err(_) => Err(RuntimeError::UndefinedVariable(token.clone())),
-                },
-                &None => match self.globals.borrow().get(&token.lexeme) {
+                &Some(distance) => match self.env.borrow().get_at(distance, 0) {
                     Ok(value) => Ok(value.clone()),
                     Err(_) => Err(RuntimeError::UndefinedVariable(token.clone())),
                 },
+                &None => Err(RuntimeError::UndefinedVariable(token.clone())),
             },
             Expr::Super(_, ref method, ref distance) => match distance {
                 &Some(distance) => {
                     let superclass = self.env
                         .borrow()
-                        .get_at(&"super".to_string(), distance)
+                        .get_at(distance, 0)
                         .expect("Couldn't find `super` when interpreting");
                     let instance = self.env
                         .borrow()
-                        .get_at(&"this".to_string(), distance - 1)
+                        .get_at(distance - 1, 0)
                         .expect("Couldn't find `this` when interpreting `super` call");
 
                     let superclass = match superclass {
@@ -366,7 +898,7 @@ impl<'a> Interpreter<'a> {
                     let resolved_method = superclass.find_method(&method.lexeme, instance.clone());
 
                     match resolved_method {
-                        Some(method) => Ok(LoxValue::Func(Rc::new(method))),
+                        Some(method) => Ok(method.into_value()),
                         None => Err(RuntimeError::UndefinedProperty(method.clone())),
                     }
                 }
@@ -377,3 +909,189 @@ impl<'a> Interpreter<'a> {
         }
     }
 }
+
+// Name of a called function for stack traces. Native functions don't know their name, so the
+// one they were called by is used
+fn function_name(callable: &Rc<Callable>, callee: &Expr) -> String {
+    if let Some(func) = callable.as_any().downcast_ref::<LoxFunc>() {
+        return func.name().to_string();
+    }
+
+    if let Some(class) = callable.as_any().downcast_ref::<LoxClass>() {
+        return class.get_name().to_string();
+    }
+
+    match *callee {
+        Expr::Var(ref name, _) | Expr::Get(_, ref name) => name.lexeme.clone(),
+        _ => "<native fn>".to_string(),
+    }
+}
+
+fn operator_method_name(operator: TokenType) -> Option<&'static str> {
+    match operator {
+        TokenType::Plus => Some("__add__"),
+        TokenType::Minus => Some("__sub__"),
+        TokenType::Star => Some("__mul__"),
+        TokenType::Slash => Some("__div__"),
+        TokenType::Percent => Some("__mod__"),
+        TokenType::TildeSlash => Some("__floordiv__"),
+        TokenType::StarStar => Some("__pow__"),
+        TokenType::Ampersand => Some("__and__"),
+        TokenType::Pipe => Some("__or__"),
+        TokenType::Caret => Some("__xor__"),
+        TokenType::LessLess => Some("__lshift__"),
+        TokenType::GreaterGreater => Some("__rshift__"),
+        TokenType::EqualEqual | TokenType::BangEqual => Some("__eq__"),
+        TokenType::Less => Some("__lt__"),
+        TokenType::LessEqual => Some("__le__"),
+        TokenType::Greater => Some("__gt__"),
+        TokenType::GreaterEqual => Some("__ge__"),
+        _ => None,
+    }
+}
+
+// Variables the resolver didn't bind are looked up as globals. A name that was never interned
+// can't have been defined, and it isn't interned here so that looking up typos doesn't grow the
+// interner for as long as the thread lives.
+fn unresolved_binding(token: &Token) -> Option<Binding> {
+    Symbol::lookup(&token.lexeme).map(Binding::Global)
+}
+
+/// Applies a binary operator to values that aren't instances, also used by the optimizer to
+/// fold operations between literals. Adding a string to something else is left to the
+/// interpreter, which may have to call `toString()` on it.
+pub fn apply_binary_operator(
+    left_value: LoxValue,
+    operator: &Token,
+    right_value: LoxValue,
+) -> Result<LoxValue, RuntimeError> {
+    let result = match operator.token_type {
+        TokenType::Minus => left_value
+            .subtract(right_value)
+            .map_err(|_| RuntimeError::SubtractNonNumbers(operator.clone())),
+        TokenType::Slash => left_value.divide(right_value).map_err(|err| match err {
+            ValueError::DivideByZero => RuntimeError::DivideByZeroError(operator.clone()),
+            _ => RuntimeError::DivideNonNumbers(operator.clone()),
+        }),
+        TokenType::Star => left_value
+            .multiply(right_value)
+            .map_err(|_| RuntimeError::MultiplyNonNumbers(operator.clone())),
+        TokenType::Percent => left_value.modulo(right_value).map_err(|err| match err {
+            ValueError::DivideByZero => RuntimeError::DivideByZeroError(operator.clone()),
+            _ => RuntimeError::ModuloNonNumbers(operator.clone()),
+        }),
+        TokenType::TildeSlash => left_value.integer_divide(right_value).map_err(|err| {
+            match err {
+                ValueError::DivideByZero => RuntimeError::DivideByZeroError(operator.clone()),
+                _ => RuntimeError::IntegerDivideNonNumbers(operator.clone()),
+            }
+        }),
+        TokenType::StarStar => left_value
+            .power(right_value)
+            .map_err(|_| RuntimeError::PowerNonNumbers(operator.clone())),
+        TokenType::Ampersand => left_value
+            .bitwise_and(right_value)
+            .map_err(|_| RuntimeError::NonIntegerOperands(operator.clone())),
+        TokenType::Pipe => left_value
+            .bitwise_or(right_value)
+            .map_err(|_| RuntimeError::NonIntegerOperands(operator.clone())),
+        TokenType::Caret => left_value
+            .bitwise_xor(right_value)
+            .map_err(|_| RuntimeError::NonIntegerOperands(operator.clone())),
+        TokenType::LessLess => left_value.shift_left(right_value).map_err(|err| match err {
+            ValueError::InvalidShift => RuntimeError::InvalidShift(operator.clone()),
+            _ => RuntimeError::NonIntegerOperands(operator.clone()),
+        }),
+        TokenType::GreaterGreater => left_value.shift_right(right_value).map_err(|err| {
+            match err {
+                ValueError::InvalidShift => RuntimeError::InvalidShift(operator.clone()),
+                _ => RuntimeError::NonIntegerOperands(operator.clone()),
+            }
+        }),
+        TokenType::Plus => left_value
+            .plus(right_value)
+            .map_err(|_| RuntimeError::PlusTypeError(operator.clone())),
+        TokenType::Greater => left_value
+            .is_greater(right_value)
+            .map_err(|_| RuntimeError::GreaterNonNumbers(operator.clone())),
+        TokenType::GreaterEqual => left_value
+            .is_greater_equal(right_value)
+            .map_err(|_| RuntimeError::GreaterEqualNonNumbers(operator.clone())),
+        TokenType::Less => left_value
+            .is_less(right_value)
+            .map_err(|_| RuntimeError::LessNonNumbers(operator.clone())),
+        TokenType::LessEqual => left_value
+            .is_less_equal(right_value)
+            .map_err(|_| RuntimeError::LessEqualNonNumbers(operator.clone())),
+        TokenType::BangEqual => left_value.is_not_equal(&right_value).map_err(|_| {
+            RuntimeError::InternalError("Can't check non-equality".to_string())
+        }),
+        TokenType::EqualEqual => left_value.is_equal(&right_value).map_err(|_| {
+            RuntimeError::InternalError("Can't check equality".to_string())
+        }),
+        _ => Err(RuntimeError::InternalError(format!(
+            "Invalid binary operator: {:?}",
+            operator
+        ))),
+    };
+
+    // Numbers are strict, an operation that can't produce a meaningful result is an
+    // error instead of silently turning into NaN.
+    match result {
+        Ok(LoxValue::Number(number)) if number.is_nan() => {
+            Err(RuntimeError::NotANumber(operator.clone()))
+        }
+        _ => result,
+    }
+}
+
+fn list_index(bracket: &Token, index: &LoxValue, length: usize) -> Result<usize, RuntimeError> {
+    match *index {
+        LoxValue::Number(number) if number >= 0.0 && number.fract() == 0.0 => {
+            let i = number as usize;
+
+            if i < length {
+                Ok(i)
+            } else {
+                Err(RuntimeError::IndexOutOfBounds(bracket.clone(), length))
+            }
+        }
+        _ => Err(RuntimeError::InvalidIndex(bracket.clone())),
+    }
+}
+
+fn get_index(
+    target: &LoxValue,
+    bracket: &Token,
+    index: &LoxValue,
+) -> Result<LoxValue, RuntimeError> {
+    match *target {
+        LoxValue::List(ref list) => {
+            let list = list.borrow();
+            let i = list_index(bracket, index, list.len())?;
+            Ok(list[i].clone())
+        }
+        LoxValue::String(ref string) => {
+            let i = list_index(bracket, index, string.chars().count())?;
+            Ok(LoxValue::String(string.chars().nth(i).unwrap().to_string()))
+        }
+        _ => Err(RuntimeError::InvalidIndexTarget(bracket.clone())),
+    }
+}
+
+fn set_index(
+    target: &LoxValue,
+    bracket: &Token,
+    index: &LoxValue,
+    value: LoxValue,
+) -> Result<(), RuntimeError> {
+    match *target {
+        LoxValue::List(ref list) => {
+            let length = list.borrow().len();
+            let i = list_index(bracket, index, length)?;
+            list.borrow_mut()[i] = value;
+            Ok(())
+        }
+        _ => Err(RuntimeError::InvalidIndexTarget(bracket.clone())),
+    }
+}
@@ -4,6 +4,7 @@ use std;
 pub enum ValueError {
     TypeError,
     DivideByZero,
+    InvalidShift,
 }
 
 impl std::fmt::Display for ValueError {
@@ -11,6 +12,7 @@ impl std::fmt::Display for ValueError {
         match *self {
             ValueError::TypeError => f.write_str("TypeError"),
             ValueError::DivideByZero => f.write_str("DivideByZero"),
+            ValueError::InvalidShift => f.write_str("InvalidShift"),
         }
     }
 }
@@ -20,6 +22,7 @@ impl std::error::Error for ValueError {
         match *self {
             ValueError::TypeError => "TypeError",
             ValueError::DivideByZero => "DivideByZero",
+            ValueError::InvalidShift => "InvalidShift",
         }
     }
 }
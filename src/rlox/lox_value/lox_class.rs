@@ -1,16 +1,20 @@
+use std;
 use std::any::Any;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::collections::hash_map::HashMap;
 
-use rlox::callables::Callable;
+use rlox::callables::{Arity, Callable};
 use rlox::interpreter::Interpreter;
 use rlox::interpreter::errors::RuntimeError;
 use rlox::lox_value::{LoxFunc, LoxInstance, LoxValue};
+use rlox::parser::MethodKind;
+use rlox::gc::{self, GcRef, Trace};
 
 #[derive(Debug)]
 pub struct LoxClass {
     internal: Rc<LoxClassInternal>,
+    static_methods: HashMap<String, LoxValue>,
 }
 
 #[derive(Debug)]
@@ -18,11 +22,67 @@ pub struct LoxClassInternal {
     pub name: String,
     pub superclass: Option<Rc<LoxClass>>,
     pub methods: HashMap<String, LoxValue>,
+    pub getters: HashMap<String, LoxValue>,
+    pub setters: HashMap<String, LoxValue>,
 }
 
 impl LoxClassInternal {
     pub fn find_method(&self, name: &str, instance: Rc<RefCell<LoxInstance>>) -> Option<LoxFunc> {
-        self.methods
+        self.find_bound(name, instance, |class| &class.methods)
+    }
+
+    pub fn find_getter(&self, name: &str, instance: Rc<RefCell<LoxInstance>>) -> Option<LoxFunc> {
+        self.find_bound(name, instance, |class| &class.getters)
+    }
+
+    pub fn find_setter(&self, name: &str, instance: Rc<RefCell<LoxInstance>>) -> Option<LoxFunc> {
+        self.find_bound(name, instance, |class| &class.setters)
+    }
+
+    pub fn has_getter(&self, name: &str) -> bool {
+        self.has_member(name, |class| &class.getters)
+    }
+
+    pub fn has_setter(&self, name: &str) -> bool {
+        self.has_member(name, |class| &class.setters)
+    }
+
+    /// Whether this is the given class or one of its subclasses.
+    pub fn inherits_from(&self, class: &LoxClassInternal) -> bool {
+        if std::ptr::eq(self, class) {
+            return true;
+        }
+
+        match self.superclass {
+            Some(ref superclass) => superclass.internal.inherits_from(class),
+            None => false,
+        }
+    }
+
+    /// Names of the methods, getters and setters of the class and its superclasses.
+    pub fn member_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .methods
+            .keys()
+            .chain(self.getters.keys())
+            .chain(self.setters.keys())
+            .cloned()
+            .collect();
+
+        if let Some(ref superclass) = self.superclass {
+            names.extend(superclass.internal.member_names());
+        }
+
+        names
+    }
+
+    fn find_bound(
+        &self,
+        name: &str,
+        instance: Rc<RefCell<LoxInstance>>,
+        members: fn(&LoxClassInternal) -> &HashMap<String, LoxValue>,
+    ) -> Option<LoxFunc> {
+        members(self)
             .get(name)
             .map(|method| method.clone())
             .map(|method| match method {
@@ -35,12 +95,31 @@ impl LoxClassInternal {
             })
             .or_else(|| {
                 if let Some(superclass) = self.superclass.clone() {
-                    superclass.find_method(name, instance)
+                    superclass.internal.find_bound(name, instance, members)
                 } else {
                     None
                 }
             })
     }
+
+    fn has_member(
+        &self,
+        name: &str,
+        members: fn(&LoxClassInternal) -> &HashMap<String, LoxValue>,
+    ) -> bool {
+        self.find_member(name, members).is_some()
+    }
+
+    fn find_member(
+        &self,
+        name: &str,
+        members: fn(&LoxClassInternal) -> &HashMap<String, LoxValue>,
+    ) -> Option<&LoxValue> {
+        members(self).get(name).or_else(|| match self.superclass {
+            Some(ref superclass) => superclass.internal.find_member(name, members),
+            None => None,
+        })
+    }
 }
 
 impl LoxClass {
@@ -48,16 +127,32 @@ impl LoxClass {
         name: String,
         superclass: Option<Rc<LoxClass>>,
         methods: HashMap<String, LoxValue>,
+        getters: HashMap<String, LoxValue>,
+        setters: HashMap<String, LoxValue>,
+        static_methods: HashMap<String, LoxValue>,
     ) -> LoxClass {
+        let internal = Rc::new(LoxClassInternal {
+            name,
+            superclass,
+            methods,
+            getters,
+            setters,
+        });
+        gc::track(GcRef::ClassInternal(internal.clone()));
+
         LoxClass {
-            internal: Rc::new(LoxClassInternal {
-                name,
-                superclass,
-                methods,
-            }),
+            internal,
+            static_methods,
         }
     }
 
+    /// Wraps the class in a `LoxValue`, registering it with the garbage collector.
+    pub fn into_value(self) -> LoxValue {
+        let class = Rc::new(self);
+        gc::track(GcRef::Class(class.clone()));
+        LoxValue::Class(class)
+    }
+
     pub fn instantiate(&self) -> Result<LoxInstance, RuntimeError> {
         Ok(LoxInstance::new(self.internal.clone()))
     }
@@ -69,6 +164,58 @@ impl LoxClass {
     pub fn find_method(&self, name: &str, instance: Rc<RefCell<LoxInstance>>) -> Option<LoxFunc> {
         self.internal.find_method(name, instance)
     }
+
+    /// Whether the instance belongs to this class or to one of its subclasses.
+    pub fn has_instance(&self, instance: &LoxInstance) -> bool {
+        instance.class().inherits_from(&self.internal)
+    }
+
+    /// Static methods are called on the class itself, so they aren't bound to any instance.
+    pub fn find_static_method(&self, name: &str) -> Option<LoxValue> {
+        self.static_methods.get(name).cloned().or_else(|| {
+            match self.internal.superclass {
+                Some(ref superclass) => superclass.find_static_method(name),
+                None => None,
+            }
+        })
+    }
+
+    pub fn superclass(&self) -> Option<&Rc<LoxClass>> {
+        self.internal.superclass.as_ref()
+    }
+
+    /// Methods, getters, setters and static methods declared in this class, without the
+    /// inherited ones, sorted by name.
+    pub fn members(&self) -> Vec<(MethodKind, &str, &LoxValue)> {
+        let kinds = [
+            (MethodKind::Method, &self.internal.methods),
+            (MethodKind::Getter, &self.internal.getters),
+            (MethodKind::Setter, &self.internal.setters),
+            (MethodKind::Static, &self.static_methods),
+        ];
+
+        let mut members: Vec<(MethodKind, &str, &LoxValue)> = kinds
+            .iter()
+            .flat_map(|&(kind, members)| {
+                members
+                    .iter()
+                    .map(move |(name, value)| (kind, name.as_str(), value))
+            })
+            .collect();
+        members.sort_by(|a, b| a.1.cmp(b.1));
+        members
+    }
+
+    /// Names of the static methods of the class and its superclasses.
+    pub fn static_method_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.static_methods.keys().cloned().collect();
+
+        if let Some(ref superclass) = self.internal.superclass {
+            names.extend(superclass.static_method_names());
+        }
+
+        names
+    }
 }
 
 impl Callable for LoxClass {
@@ -76,15 +223,12 @@ impl Callable for LoxClass {
         self
     }
 
-    fn arity(&self) -> usize {
-        let initializer = self.internal.methods.get("init");
-        if let Some(init) = initializer {
-            match init {
-                &LoxValue::Func(ref callable) => callable.arity(),
-                _ => panic!("Can't get non-func as method from an instance"),
-            }
-        } else {
-            0
+    fn arity(&self) -> Arity {
+        // The initializer can be inherited from a superclass
+        match self.internal.find_member("init", |class| &class.methods) {
+            Some(&LoxValue::Func(ref callable)) => callable.arity(),
+            Some(_) => panic!("Can't get non-func as method from an instance"),
+            None => Arity::exactly(0),
         }
     }
 
@@ -94,6 +238,7 @@ impl Callable for LoxClass {
         arguments: Vec<LoxValue>,
     ) -> Result<LoxValue, RuntimeError> {
         let instance = Rc::new(RefCell::new(self.instantiate()?));
+        gc::track(GcRef::Instance(instance.clone()));
 
         if let Some(init) = self.internal.find_method("init", instance.clone()) {
             init.call(interpreter, arguments)?;
@@ -102,3 +247,30 @@ impl Callable for LoxClass {
         Ok(LoxValue::Instance(instance))
     }
 }
+
+impl Trace for LoxClass {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        children.push(GcRef::ClassInternal(self.internal.clone()));
+
+        for method in self.static_methods.values() {
+            method.trace(children);
+        }
+    }
+}
+
+impl Trace for LoxClassInternal {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        if let Some(ref superclass) = self.superclass {
+            children.push(GcRef::Class(superclass.clone()));
+        }
+
+        for method in self
+            .methods
+            .values()
+            .chain(self.getters.values())
+            .chain(self.setters.values())
+        {
+            method.trace(children);
+        }
+    }
+}
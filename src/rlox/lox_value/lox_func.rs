@@ -2,53 +2,127 @@ use std::any::Any;
 use std::rc::Rc;
 use std::cell::RefCell;
 
-use rlox::callables::Callable;
-use rlox::parser::Stmt;
-use rlox::interpreter::Interpreter;
+use rlox::callables::{Arity, Callable};
+use rlox::parser::{Parameter, Stmt};
+use rlox::interpreter::{compile_generator, is_async, Frame, Instruction, Interpreter};
 use rlox::environment::Environment;
 use rlox::interpreter::errors::RuntimeError;
-use rlox::lox_value::{LoxInstance, LoxValue};
+use rlox::lox_value::{AsyncCall, LoxGenerator, LoxInstance, LoxValue};
+use rlox::gc::{self, GcRef, Trace};
 
 #[derive(Debug)]
 pub struct LoxFunc {
-    declaration: Stmt,
+    // `None` for anonymous functions
+    name: Option<String>,
+    parameters: Rc<Vec<Parameter>>,
+    body: Rc<Vec<Stmt>>,
     closure: Rc<RefCell<Environment>>,
     is_initializer: bool,
+    // Compiled body of functions that use `yield` or `await`. Calling them returns a generator,
+    // or a promise for async functions
+    generator: Option<Rc<Vec<Instruction>>>,
+    is_async: bool,
 }
 
 impl LoxFunc {
     pub fn new(stmt: Stmt, closure: Rc<RefCell<Environment>>, is_initializer: bool) -> LoxFunc {
         // TODO: Would be great to have a compile-time check for this instead of panicking
         match stmt {
-            Stmt::Func(_, _, _) => LoxFunc {
-                declaration: stmt,
-                closure,
-                is_initializer,
-            },
+            Stmt::Func(name, parameters, body) => {
+                LoxFunc::build(Some(name.lexeme), parameters, *body, closure, is_initializer)
+            }
             _ => panic!("Cannot build a LoxFunc with a Stmt other than Stmt::Func"),
         }
     }
 
+    pub fn anonymous(
+        parameters: Vec<Parameter>,
+        body: Stmt,
+        closure: Rc<RefCell<Environment>>,
+    ) -> LoxFunc {
+        LoxFunc::build(None, parameters, body, closure, false)
+    }
+
+    fn build(
+        name: Option<String>,
+        parameters: Vec<Parameter>,
+        body: Stmt,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> LoxFunc {
+        let body = match body {
+            Stmt::Block(statements) => statements,
+            _ => panic!("Cannot build a LoxFunc with a body Stmt other than Stmt::Block"),
+        };
+
+        LoxFunc {
+            name,
+            parameters: Rc::new(parameters),
+            generator: compile_generator(&body),
+            is_async: is_async(&body),
+            body: Rc::new(body),
+            closure,
+            is_initializer,
+        }
+    }
+
     pub fn bind(&self, instance: Rc<RefCell<LoxInstance>>) -> LoxFunc {
         let mut env = Environment::from_parent(self.closure.clone());
-        env.define("this".to_string(), LoxValue::Instance(instance.clone()));
+        env.define("this", LoxValue::Instance(instance.clone()));
+
+        let closure = Rc::new(RefCell::new(env));
+        gc::track(GcRef::Environment(closure.clone()));
 
         LoxFunc {
-            declaration: self.declaration.clone(),
-            closure: Rc::new(RefCell::new(env)),
+            name: self.name.clone(),
+            parameters: self.parameters.clone(),
+            body: self.body.clone(),
+            closure,
             is_initializer: self.is_initializer,
+            generator: self.generator.clone(),
+            is_async: self.is_async,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self.name {
+            Some(ref name) => name,
+            None => "<anonymous>",
         }
     }
+
+    pub fn parameters(&self) -> &[Parameter] {
+        &self.parameters
+    }
+
+    /// Wraps the function in a `LoxValue`, registering it with the garbage collector.
+    pub fn into_value(self) -> LoxValue {
+        let func: Rc<Callable> = Rc::new(self);
+        gc::track(GcRef::Func(func.clone()));
+        LoxValue::Func(func)
+    }
+}
+
+impl Trace for LoxFunc {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        children.push(GcRef::Environment(self.closure.clone()));
+    }
 }
 
 impl Callable for LoxFunc {
     fn as_any(&self) -> &Any {
         self
     }
-    fn arity(&self) -> usize {
-        match self.declaration {
-            Stmt::Func(_, ref parameters, _) => parameters.len(),
-            _ => panic!("Cannot build a LoxFunc with a Stmt other than Stmt::Func"),
+
+    fn arity(&self) -> Arity {
+        let required = self.parameters
+            .iter()
+            .filter(|parameter| matches!(**parameter, Parameter::Required(_)))
+            .count();
+
+        match self.parameters.last() {
+            Some(&Parameter::Rest(_)) => Arity::at_least(required),
+            _ => Arity::between(required, self.parameters.len()),
         }
     }
 
@@ -57,29 +131,38 @@ impl Callable for LoxFunc {
         interpreter: &mut Interpreter,
         arguments: Vec<LoxValue>,
     ) -> Result<LoxValue, RuntimeError> {
-        let mut env = Environment::from_parent(self.closure.clone());
+        let env = Rc::new(RefCell::new(Environment::from_parent(self.closure.clone())));
+        gc::track(GcRef::Environment(env.clone()));
 
-        let (parameters, body) = match self.declaration {
-            Stmt::Func(_, ref parameters, ref body) => (parameters, body),
-            _ => panic!("Cannot build a LoxFunc with a Stmt other than Stmt::Func"),
-        };
+        let mut arguments = arguments.into_iter();
 
-        let body = match **body {
-            Stmt::Block(ref statements) => statements,
-            _ => panic!("Cannot build a LoxFunc with a body Stmt other than Stmt::Block"),
-        };
+        for param in self.parameters.iter() {
+            let value = match *param {
+                Parameter::Required(_) => arguments
+                    .next()
+                    .expect("Mismatched argument and parameter sizes"),
+                Parameter::Optional(_, ref default) => match arguments.next() {
+                    Some(value) => value,
+                    None => interpreter.interpret_expr_in(default, env.clone())?,
+                },
+                Parameter::Rest(_) => LoxValue::list(arguments.by_ref().collect()),
+            };
+
+            env.borrow_mut().define(&param.name().lexeme, value);
+        }
+
+        if let Some(ref code) = self.generator {
+            let frame = Frame::new(code.clone(), env);
+            let generator = LoxGenerator::new(self.name().to_string(), frame);
+
+            if self.is_async {
+                return AsyncCall::start(generator.into_rc(), interpreter);
+            }
 
-        for (i, param) in parameters.iter().enumerate() {
-            env.define(
-                param.lexeme.clone(),
-                arguments
-                    .get(i)
-                    .expect("Mismatched argument and parameter sizes")
-                    .clone(),
-            );
+            return Ok(generator.into_value());
         }
 
-        let result = match interpreter.interpret_block(body, RefCell::new(env))? {
+        let result = match interpreter.interpret_block(&self.body, env)? {
             Some(result) => Ok(result),
             None => Ok(LoxValue::Nil),
         };
@@ -87,7 +170,7 @@ impl Callable for LoxFunc {
         if self.is_initializer {
             return Ok(self.closure
                 .borrow()
-                .get_at(&"this".to_string(), 0)
+                .get_at(0, 0)
                 .expect("Couldn't find reference to `this` in initializer"));
         }
 
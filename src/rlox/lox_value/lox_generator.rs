@@ -0,0 +1,217 @@
+use std::any::Any;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use rlox::callables::{Arity, Callable};
+use rlox::interpreter::{Frame, Interpreter, Step};
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::LoxValue;
+use rlox::gc::{self, GcRef, Trace};
+
+/// Returned by calling a function that uses `yield`. The body of the function runs a piece at
+/// a time, every call to `next()` or `resume()` runs it until the next `yield`.
+#[derive(Debug)]
+pub struct LoxGenerator {
+    name: String,
+    // Taken out of the generator while it runs, and `None` once it finished
+    frame: Option<Frame>,
+    running: bool,
+    // Value found by `hasNext()`, returned by the following `next()`
+    peeked: Option<LoxValue>,
+}
+
+impl LoxGenerator {
+    pub fn new(name: String, frame: Frame) -> LoxGenerator {
+        LoxGenerator {
+            name,
+            frame: Some(frame),
+            running: false,
+            peeked: None,
+        }
+    }
+
+    /// Wraps the generator in a `LoxValue`, registering it with the garbage collector.
+    pub fn into_value(self) -> LoxValue {
+        LoxValue::Generator(self.into_rc())
+    }
+
+    /// Registers the generator with the garbage collector, without exposing it as a value. Used
+    /// for async functions, which return a promise instead.
+    pub fn into_rc(self) -> Rc<RefCell<LoxGenerator>> {
+        let generator = Rc::new(RefCell::new(self));
+        gc::track(GcRef::Generator(generator.clone()));
+        generator
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_done(&self) -> bool {
+        !self.running && self.frame.is_none() && self.peeked.is_none()
+    }
+
+    /// Drops the state of the generator, finishing it. Used by the garbage collector to break
+    /// reference cycles.
+    pub fn clear(&mut self) {
+        self.frame = None;
+        self.peeked = None;
+    }
+
+    /// Returns the next value, or `nil` if the generator already finished.
+    pub fn next(
+        generator: &Rc<RefCell<LoxGenerator>>,
+        interpreter: &mut Interpreter,
+    ) -> Result<LoxValue, RuntimeError> {
+        LoxGenerator::resume(generator, interpreter, LoxValue::Nil)
+    }
+
+    /// Runs the generator until its next `yield`, unless it already did so in a previous call
+    /// to `hasNext()`.
+    pub fn has_next(
+        generator: &Rc<RefCell<LoxGenerator>>,
+        interpreter: &mut Interpreter,
+    ) -> Result<bool, RuntimeError> {
+        if generator.borrow().peeked.is_some() {
+            return Ok(true);
+        }
+
+        match LoxGenerator::advance(generator, interpreter, LoxValue::Nil)? {
+            Step::Yielded(value) => {
+                generator.borrow_mut().peeked = Some(value);
+                Ok(true)
+            }
+            Step::Returned(_) => Ok(false),
+        }
+    }
+
+    /// Continues the generator, with `value` as the result of the `yield` it stopped at, and
+    /// returns the next yielded value or `nil` once it finishes.
+    pub fn resume(
+        generator: &Rc<RefCell<LoxGenerator>>,
+        interpreter: &mut Interpreter,
+        value: LoxValue,
+    ) -> Result<LoxValue, RuntimeError> {
+        let peeked = generator.borrow_mut().peeked.take();
+
+        if let Some(peeked) = peeked {
+            return Ok(peeked);
+        }
+
+        // The value a generator returns is discarded, it only signals that it finished
+        LoxGenerator::advance(generator, interpreter, value).map(|step| match step {
+            Step::Yielded(value) => value,
+            Step::Returned(_) => LoxValue::Nil,
+        })
+    }
+
+    /// Runs the function until it yields or returns. Once it finished it always returns `nil`.
+    pub fn advance(
+        generator: &Rc<RefCell<LoxGenerator>>,
+        interpreter: &mut Interpreter,
+        sent: LoxValue,
+    ) -> Result<Step, RuntimeError> {
+        let mut frame = {
+            let mut generator = generator.borrow_mut();
+
+            if generator.running {
+                return Err(RuntimeError::GeneratorAlreadyRunning(generator.name.clone()));
+            }
+
+            match generator.frame.take() {
+                Some(frame) => frame,
+                None => return Ok(Step::Returned(LoxValue::Nil)),
+            }
+        };
+
+        generator.borrow_mut().running = true;
+        let result = interpreter.resume_generator(&mut frame, sent);
+
+        let mut generator = generator.borrow_mut();
+        generator.running = false;
+
+        // Finishing, with a `return` or an error, drops the frame for good
+        if let Ok(Step::Yielded(_)) = result {
+            generator.frame = Some(frame);
+        }
+
+        result
+    }
+}
+
+impl Trace for LoxGenerator {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        if let Some(ref frame) = self.frame {
+            frame.trace(children);
+        }
+
+        if let Some(ref peeked) = self.peeked {
+            peeked.trace(children);
+        }
+    }
+}
+
+/// `next()`, `hasNext()`, `resume(value)` and `done()`, bound to a generator.
+#[derive(Debug)]
+pub struct GeneratorMethod {
+    generator: Rc<RefCell<LoxGenerator>>,
+    kind: GeneratorMethodKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum GeneratorMethodKind {
+    Next,
+    HasNext,
+    Resume,
+    Done,
+}
+
+impl GeneratorMethod {
+    pub fn find(generator: &Rc<RefCell<LoxGenerator>>, name: &str) -> Option<LoxValue> {
+        let kind = match name {
+            "next" => GeneratorMethodKind::Next,
+            "hasNext" => GeneratorMethodKind::HasNext,
+            "resume" => GeneratorMethodKind::Resume,
+            "done" => GeneratorMethodKind::Done,
+            _ => return None,
+        };
+
+        let method: Rc<Callable> = Rc::new(GeneratorMethod {
+            generator: generator.clone(),
+            kind,
+        });
+
+        Some(LoxValue::Func(method))
+    }
+}
+
+impl Callable for GeneratorMethod {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        match self.kind {
+            GeneratorMethodKind::Resume => Arity::between(0, 1),
+            _ => Arity::exactly(0),
+        }
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        match self.kind {
+            GeneratorMethodKind::Next => LoxGenerator::next(&self.generator, interpreter),
+            GeneratorMethodKind::HasNext => {
+                LoxGenerator::has_next(&self.generator, interpreter).map(LoxValue::Bool)
+            }
+            GeneratorMethodKind::Resume => {
+                let value = arguments.into_iter().next().unwrap_or(LoxValue::Nil);
+                LoxGenerator::resume(&self.generator, interpreter, value)
+            }
+            GeneratorMethodKind::Done => Ok(LoxValue::Bool(self.generator.borrow().is_done())),
+        }
+    }
+}
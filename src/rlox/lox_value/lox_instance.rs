@@ -4,8 +4,9 @@ use std::collections::hash_map::HashMap;
 
 use rlox::interpreter::errors::RuntimeError;
 use rlox::token::Token;
-use rlox::lox_value::LoxValue;
+use rlox::lox_value::{LoxFunc, LoxValue};
 use rlox::lox_value::lox_class::LoxClassInternal;
+use rlox::gc::{GcRef, Trace};
 
 #[derive(Debug, Clone)]
 pub struct LoxInstance {
@@ -21,16 +22,48 @@ impl LoxInstance {
         }
     }
 
-    pub fn get(&self, name: &Token) -> Result<LoxValue, RuntimeError> {
-        self.state
-            .get(&name.lexeme)
-            .map(|property| property.clone())
+    pub fn get(
+        instance: &Rc<RefCell<LoxInstance>>,
+        name: &Token,
+    ) -> Result<LoxValue, RuntimeError> {
+        let property = instance.borrow().state.get(&name.lexeme).cloned();
+
+        property
             .or_else(|| {
-                self.class
-                    .find_method(&name.lexeme, Rc::new(RefCell::new(self.clone())))
-                    .map(|method| LoxValue::Func(Rc::new(method)))
+                let class = instance.borrow().class.clone();
+
+                class
+                    .find_method(&name.lexeme, instance.clone())
+                    .map(|method| method.into_value())
+            })
+            .ok_or_else(|| {
+                if instance.borrow().class.has_setter(&name.lexeme) {
+                    RuntimeError::WriteOnlyProperty(name.clone())
+                } else {
+                    RuntimeError::UndefinedProperty(name.clone())
+                }
             })
-            .ok_or(RuntimeError::UndefinedProperty(name.clone()))
+    }
+
+    /// Looks up a method in the class of the instance, bound to it. Unlike `get`, properties
+    /// set on the instance are not taken into account.
+    pub fn find_method(instance: &Rc<RefCell<LoxInstance>>, name: &str) -> Option<LoxFunc> {
+        let class = instance.borrow().class.clone();
+        class.find_method(name, instance.clone())
+    }
+
+    pub fn find_getter(instance: &Rc<RefCell<LoxInstance>>, name: &str) -> Option<LoxFunc> {
+        let class = instance.borrow().class.clone();
+        class.find_getter(name, instance.clone())
+    }
+
+    pub fn find_setter(instance: &Rc<RefCell<LoxInstance>>, name: &str) -> Option<LoxFunc> {
+        let class = instance.borrow().class.clone();
+        class.find_setter(name, instance.clone())
+    }
+
+    pub fn has_getter(&self, name: &str) -> bool {
+        self.class.has_getter(name)
     }
 
     pub fn set(&mut self, name: &str, value: LoxValue) {
@@ -40,4 +73,33 @@ impl LoxInstance {
     pub fn get_class_name(&self) -> &str {
         &self.class.name
     }
+
+    pub fn class(&self) -> &LoxClassInternal {
+        &self.class
+    }
+
+    /// Names of the properties set on the instance and of the methods, getters and setters of
+    /// its class and superclasses, sorted.
+    pub fn property_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.state.keys().cloned().collect();
+        names.extend(self.class.member_names());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Drops every property, used by the garbage collector to break reference cycles.
+    pub fn clear(&mut self) {
+        self.state.clear();
+    }
+}
+
+impl Trace for LoxInstance {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        children.push(GcRef::ClassInternal(self.class.clone()));
+
+        for value in self.state.values() {
+            value.trace(children);
+        }
+    }
 }
@@ -0,0 +1,302 @@
+use std::any::Any;
+use std::mem;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use rlox::callables::{Arity, Callable};
+use rlox::interpreter::{Interpreter, Step};
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::{LoxGenerator, LoxValue};
+use rlox::gc::{self, GcRef, Trace};
+
+/// Value that will be available later on, returned by async functions and `sleep()`. Promises
+/// are only ever resolved, a runtime error stops the whole program instead.
+#[derive(Debug)]
+pub struct LoxPromise {
+    value: Option<LoxValue>,
+    // Called with the value through the event loop once the promise resolves
+    callbacks: Vec<Rc<Callable>>,
+}
+
+impl LoxPromise {
+    /// Builds a pending promise, registering it with the garbage collector.
+    pub fn new() -> Rc<RefCell<LoxPromise>> {
+        let promise = Rc::new(RefCell::new(LoxPromise {
+            value: None,
+            callbacks: Vec::new(),
+        }));
+        gc::track(GcRef::Promise(promise.clone()));
+        promise
+    }
+
+    /// Drops the value and callbacks of the promise. Used by the garbage collector to break
+    /// reference cycles.
+    pub fn clear(&mut self) {
+        self.value = None;
+        self.callbacks.clear();
+    }
+
+    /// Resolves the promise, unless it already was. Resolving it with another promise makes it
+    /// wait for that one and take its value.
+    pub fn resolve(
+        promise: &Rc<RefCell<LoxPromise>>,
+        interpreter: &mut Interpreter,
+        value: LoxValue,
+    ) {
+        if let LoxValue::Promise(ref other) = value {
+            let callback = Rc::new(ResolveCallback {
+                promise: promise.clone(),
+            });
+            LoxPromise::subscribe(other, interpreter, callback);
+            return;
+        }
+
+        let callbacks = {
+            let mut promise = promise.borrow_mut();
+
+            if promise.value.is_some() {
+                return;
+            }
+
+            promise.value = Some(value.clone());
+            mem::take(&mut promise.callbacks)
+        };
+
+        for callback in callbacks {
+            interpreter
+                .event_loop()
+                .schedule(callback, vec![value.clone()]);
+        }
+    }
+
+    /// Calls `callback` with the value of the promise once it resolves. Callbacks always run
+    /// from the event loop, even if the promise already has a value.
+    pub fn subscribe(
+        promise: &Rc<RefCell<LoxPromise>>,
+        interpreter: &mut Interpreter,
+        callback: Rc<Callable>,
+    ) {
+        let value = promise.borrow().value.clone();
+
+        match value {
+            Some(value) => interpreter.event_loop().schedule(callback, vec![value]),
+            None => promise.borrow_mut().callbacks.push(callback),
+        }
+    }
+}
+
+impl Trace for LoxPromise {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        if let Some(ref value) = self.value {
+            value.trace(children);
+        }
+
+        for callback in self.callbacks.iter() {
+            children.push(GcRef::Func(callback.clone()));
+        }
+    }
+}
+
+/// `then(callback)`, bound to a promise.
+#[derive(Debug)]
+pub struct PromiseMethod {
+    promise: Rc<RefCell<LoxPromise>>,
+    kind: PromiseMethodKind,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum PromiseMethodKind {
+    Then,
+}
+
+impl PromiseMethod {
+    pub fn find(promise: &Rc<RefCell<LoxPromise>>, name: &str) -> Option<LoxValue> {
+        let kind = match name {
+            "then" => PromiseMethodKind::Then,
+            _ => return None,
+        };
+
+        let method: Rc<Callable> = Rc::new(PromiseMethod {
+            promise: promise.clone(),
+            kind,
+        });
+
+        Some(LoxValue::Func(method))
+    }
+
+    // Returns a new promise, resolved with the result of `callback`
+    fn then(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let callback = match arguments.first().and_then(|value| value.get_callable()) {
+            Some(callback) if callback.arity().accepts(0) || callback.arity().accepts(1) => {
+                callback
+            }
+            _ => {
+                return Err(RuntimeError::InvalidArgument(
+                    "then".to_string(),
+                    "expected a function that takes the value of the promise".to_string(),
+                ))
+            }
+        };
+
+        let promise = LoxPromise::new();
+        let then: Rc<Callable> = Rc::new(ThenCallback {
+            callback,
+            promise: promise.clone(),
+        });
+        LoxPromise::subscribe(&self.promise, interpreter, then);
+
+        Ok(LoxValue::Promise(promise))
+    }
+}
+
+impl Callable for PromiseMethod {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        match self.kind {
+            PromiseMethodKind::Then => Arity::exactly(1),
+        }
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        match self.kind {
+            PromiseMethodKind::Then => self.then(interpreter, arguments),
+        }
+    }
+}
+
+// Resolves a promise with the value it's called with, or with `nil` when used as a timer
+#[derive(Debug)]
+pub struct ResolveCallback {
+    pub promise: Rc<RefCell<LoxPromise>>,
+}
+
+impl Callable for ResolveCallback {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::between(0, 1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let value = arguments.into_iter().next().unwrap_or(LoxValue::Nil);
+        LoxPromise::resolve(&self.promise, interpreter, value);
+        Ok(LoxValue::Nil)
+    }
+}
+
+// Runs the callback given to `then()`, which can ignore the value, and resolves the promise
+// `then()` returned with its result
+#[derive(Debug)]
+struct ThenCallback {
+    callback: Rc<Callable>,
+    promise: Rc<RefCell<LoxPromise>>,
+}
+
+impl Callable for ThenCallback {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let arguments = if self.callback.arity().accepts(1) {
+            arguments
+        } else {
+            Vec::new()
+        };
+
+        let result = self.callback.call(interpreter, arguments)?;
+        LoxPromise::resolve(&self.promise, interpreter, result);
+        Ok(LoxValue::Nil)
+    }
+}
+
+/// Drives the body of an async function. Every `await` suspends it until the awaited promise
+/// resolves, or until the next turn of the event loop for any other value, and the promise
+/// returned by the call resolves with the value the function returns.
+#[derive(Debug)]
+pub struct AsyncCall {
+    generator: Rc<RefCell<LoxGenerator>>,
+    promise: Rc<RefCell<LoxPromise>>,
+}
+
+impl AsyncCall {
+    /// Runs the function until its first `await`, and returns the promise for its result.
+    pub fn start(
+        generator: Rc<RefCell<LoxGenerator>>,
+        interpreter: &mut Interpreter,
+    ) -> Result<LoxValue, RuntimeError> {
+        let call = AsyncCall {
+            generator,
+            promise: LoxPromise::new(),
+        };
+
+        call.resume(interpreter, LoxValue::Nil)?;
+        Ok(LoxValue::Promise(call.promise))
+    }
+
+    fn resume(&self, interpreter: &mut Interpreter, value: LoxValue) -> Result<(), RuntimeError> {
+        match LoxGenerator::advance(&self.generator, interpreter, value)? {
+            Step::Yielded(LoxValue::Promise(ref awaited)) => {
+                LoxPromise::subscribe(awaited, interpreter, self.continuation())
+            }
+            Step::Yielded(value) => interpreter
+                .event_loop()
+                .schedule(self.continuation(), vec![value]),
+            Step::Returned(value) => LoxPromise::resolve(&self.promise, interpreter, value),
+        }
+
+        Ok(())
+    }
+
+    fn continuation(&self) -> Rc<Callable> {
+        Rc::new(AsyncCall {
+            generator: self.generator.clone(),
+            promise: self.promise.clone(),
+        })
+    }
+}
+
+impl Callable for AsyncCall {
+    fn as_any(&self) -> &Any {
+        self
+    }
+
+    fn arity(&self) -> Arity {
+        Arity::exactly(1)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        arguments: Vec<LoxValue>,
+    ) -> Result<LoxValue, RuntimeError> {
+        let value = arguments.into_iter().next().unwrap_or(LoxValue::Nil);
+        self.resume(interpreter, value)?;
+        Ok(LoxValue::Nil)
+    }
+}
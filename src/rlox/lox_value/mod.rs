@@ -2,15 +2,20 @@ mod errors;
 mod lox_class;
 mod lox_instance;
 mod lox_func;
+mod lox_generator;
+mod lox_promise;
 
 use std;
 use std::rc::Rc;
 use std::cell::RefCell;
 
 use rlox::callables::Callable;
+use rlox::gc::{self, GcRef, Trace};
 pub use self::lox_class::{LoxClass, LoxClassInternal};
 pub use self::lox_instance::LoxInstance;
 pub use self::lox_func::LoxFunc;
+pub use self::lox_generator::{GeneratorMethod, LoxGenerator};
+pub use self::lox_promise::{AsyncCall, LoxPromise, PromiseMethod, ResolveCallback};
 pub use self::errors::ValueError;
 
 #[derive(Debug)]
@@ -21,25 +26,102 @@ pub enum LoxValue {
     Func(Rc<Callable>),
     Class(Rc<LoxClass>),
     Instance(Rc<RefCell<LoxInstance>>),
+    List(Rc<RefCell<Vec<LoxValue>>>),
+    Generator(Rc<RefCell<LoxGenerator>>),
+    Promise(Rc<RefCell<LoxPromise>>),
     Nil,
 }
 
 impl std::fmt::Display for LoxValue {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
-            LoxValue::Number(number) => write!(f, "{}", number),
+            LoxValue::Number(number) => f.write_str(&format_number(number)),
             LoxValue::String(ref string) => write!(f, "{}", string),
             LoxValue::Bool(b) => write!(f, "{}", b),
-            LoxValue::Func(_) => f.write_str("func"),
+            LoxValue::Func(ref func) => match func.as_any().downcast_ref::<LoxFunc>() {
+                Some(func) => write!(f, "<fn {}>", func.name()),
+                None => f.write_str("<native fn>"),
+            },
             LoxValue::Class(ref class) => write!(f, "class <{}>", class.get_name()),
             LoxValue::Instance(ref instance) => {
                 write!(f, "instance of <{}>", instance.borrow().get_class_name())
             }
+            LoxValue::List(ref list) => {
+                let string = format_list(list, &mut |item| Ok(item.to_string()))?;
+                f.write_str(&string)
+            }
+            LoxValue::Generator(ref generator) => {
+                write!(f, "<generator {}>", generator.borrow().name())
+            }
+            LoxValue::Promise(_) => f.write_str("<promise>"),
             LoxValue::Nil => f.write_str("nil"),
         }
     }
 }
 
+thread_local! {
+    // Lists that are being formatted, to detect lists that contain themselves.
+    static FORMATTING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Formats a list as `[a, "b"]`. Strings are quoted and every other element is converted with
+/// `format_item`. A list that contains itself shows `[...]` where it repeats.
+pub fn format_list<E, F>(list: &Rc<RefCell<Vec<LoxValue>>>, format_item: &mut F) -> Result<String, E>
+where
+    F: FnMut(&LoxValue) -> Result<String, E>,
+{
+    let id = Rc::as_ptr(list) as usize;
+
+    if FORMATTING.with(|formatting| formatting.borrow().contains(&id)) {
+        return Ok("[...]".to_string());
+    }
+
+    FORMATTING.with(|formatting| formatting.borrow_mut().push(id));
+
+    let items = list.borrow().clone();
+    let mut parts = Vec::with_capacity(items.len());
+    let mut result = Ok(());
+
+    for item in items.iter() {
+        match *item {
+            LoxValue::String(ref string) => parts.push(format!("\"{}\"", string)),
+            _ => match format_item(item) {
+                Ok(part) => parts.push(part),
+                Err(err) => {
+                    result = Err(err);
+                    break;
+                }
+            },
+        }
+    }
+
+    FORMATTING.with(|formatting| formatting.borrow_mut().pop());
+
+    result.map(|_| format!("[{}]", parts.join(", ")))
+}
+
+const MAX_SAFE_INTEGER: f64 = 9007199254740992.0;
+
+/// Numbers are printed with the shortest representation that reads back as the same number,
+/// and without a fractional part when they have none, e.g. `3` rather than `3.0`. Numbers of
+/// magnitude `1e21` or more, or smaller than `1e-6`, use exponent notation such as `1e21` or
+/// `1.5e-7`. NaN is printed as `NaN` and infinities as `Infinity` and `-Infinity`.
+pub fn format_number(number: f64) -> String {
+    if number.is_nan() {
+        "NaN".to_string()
+    } else if number.is_infinite() {
+        if number > 0.0 {
+            "Infinity".to_string()
+        } else {
+            "-Infinity".to_string()
+        }
+    } else if number != 0.0 && (number.abs() >= 1e21 || number.abs() < 1e-6) {
+        format!("{:e}", number)
+    } else {
+        format!("{}", number)
+    }
+}
+
 impl std::clone::Clone for LoxValue {
     fn clone(&self) -> LoxValue {
         match *self {
@@ -50,11 +132,37 @@ impl std::clone::Clone for LoxValue {
             LoxValue::Func(ref func) => LoxValue::Func(func.clone()),
             LoxValue::Class(ref class) => LoxValue::Class(class.clone()),
             LoxValue::Instance(ref instance) => LoxValue::Instance(instance.clone()),
+            LoxValue::List(ref list) => LoxValue::List(list.clone()),
+            LoxValue::Generator(ref generator) => LoxValue::Generator(generator.clone()),
+            LoxValue::Promise(ref promise) => LoxValue::Promise(promise.clone()),
+        }
+    }
+}
+
+impl Trace for LoxValue {
+    fn trace(&self, children: &mut Vec<GcRef>) {
+        match *self {
+            LoxValue::Func(ref func) => children.push(GcRef::Func(func.clone())),
+            LoxValue::Class(ref class) => children.push(GcRef::Class(class.clone())),
+            LoxValue::Instance(ref instance) => children.push(GcRef::Instance(instance.clone())),
+            LoxValue::List(ref list) => children.push(GcRef::List(list.clone())),
+            LoxValue::Generator(ref generator) => {
+                children.push(GcRef::Generator(generator.clone()))
+            }
+            LoxValue::Promise(ref promise) => children.push(GcRef::Promise(promise.clone())),
+            LoxValue::Number(_) | LoxValue::String(_) | LoxValue::Bool(_) | LoxValue::Nil => {}
         }
     }
 }
 
 impl LoxValue {
+    /// Builds a list value, registering it with the garbage collector.
+    pub fn list(items: Vec<LoxValue>) -> LoxValue {
+        let list = Rc::new(RefCell::new(items));
+        gc::track(GcRef::List(list.clone()));
+        LoxValue::List(list)
+    }
+
     pub fn is_truthy(&self) -> bool {
         match *self {
             LoxValue::Bool(b) => b,
@@ -97,6 +205,116 @@ impl LoxValue {
         }
     }
 
+    /// Remainder of a floored division, so the result has the same sign as the divisor:
+    /// `-7 % 3` is `2`, matching `-7 ~/ 3` being `-3`.
+    pub fn modulo(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        match (self, other) {
+            (&LoxValue::Number(left_number), LoxValue::Number(right_number)) => {
+                if right_number == 0.0 {
+                    return Err(ValueError::DivideByZero);
+                }
+
+                let remainder = left_number % right_number;
+
+                if remainder != 0.0 && (remainder < 0.0) != (right_number < 0.0) {
+                    Ok(LoxValue::Number(remainder + right_number))
+                } else {
+                    Ok(LoxValue::Number(remainder))
+                }
+            }
+            _ => Err(ValueError::TypeError),
+        }
+    }
+
+    /// Division rounded down to the closest integer.
+    pub fn integer_divide(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        match (self, other) {
+            (&LoxValue::Number(left_number), LoxValue::Number(right_number)) => {
+                if right_number != 0.0 {
+                    Ok(LoxValue::Number((left_number / right_number).floor()))
+                } else {
+                    Err(ValueError::DivideByZero)
+                }
+            }
+            _ => Err(ValueError::TypeError),
+        }
+    }
+
+    pub fn power(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        match (self, other) {
+            (&LoxValue::Number(left_number), LoxValue::Number(right_number)) => {
+                Ok(LoxValue::Number(left_number.powf(right_number)))
+            }
+            _ => Err(ValueError::TypeError),
+        }
+    }
+
+    /// Numbers used with bitwise operators have to be integers that a `f64` can represent
+    /// exactly, i.e. between `-(2 ** 53)` and `2 ** 53`.
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            LoxValue::Number(number)
+                if number.fract() == 0.0 && number.abs() <= MAX_SAFE_INTEGER =>
+            {
+                Some(number as i64)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn bitwise_and(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        self.integer_operation(other, |left, right| Some(left & right))
+    }
+
+    pub fn bitwise_or(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        self.integer_operation(other, |left, right| Some(left | right))
+    }
+
+    pub fn bitwise_xor(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        self.integer_operation(other, |left, right| Some(left ^ right))
+    }
+
+    pub fn shift_left(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        self.integer_operation(other, |left, right| {
+            if (0..64).contains(&right) {
+                Some(left << right)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Arithmetic shift, the sign of the left side is kept.
+    pub fn shift_right(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
+        self.integer_operation(other, |left, right| {
+            if (0..64).contains(&right) {
+                Some(left >> right)
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn bitwise_not(&self) -> Result<LoxValue, ValueError> {
+        match self.as_integer() {
+            Some(integer) => Ok(LoxValue::Number(!integer as f64)),
+            None => Err(ValueError::TypeError),
+        }
+    }
+
+    // `operation` returns `None` when the right side is not a valid shift amount
+    fn integer_operation<F>(&self, other: LoxValue, operation: F) -> Result<LoxValue, ValueError>
+    where
+        F: Fn(i64, i64) -> Option<i64>,
+    {
+        match (self.as_integer(), other.as_integer()) {
+            (Some(left), Some(right)) => operation(left, right)
+                .map(|result| LoxValue::Number(result as f64))
+                .ok_or(ValueError::InvalidShift),
+            _ => Err(ValueError::TypeError),
+        }
+    }
+
     pub fn multiply(&self, other: LoxValue) -> Result<LoxValue, ValueError> {
         match (self, other) {
             (&LoxValue::Number(left_number), LoxValue::Number(right_number)) => {
@@ -173,6 +391,9 @@ impl LoxValue {
             (&LoxValue::Func(ref f), &LoxValue::Func(ref other)) => Rc::ptr_eq(f, other),
             (&LoxValue::Class(ref c), &LoxValue::Class(ref other)) => Rc::ptr_eq(c, other),
             (&LoxValue::Instance(ref i), &LoxValue::Instance(ref other)) => Rc::ptr_eq(i, other),
+            (&LoxValue::List(ref l), &LoxValue::List(ref other)) => Rc::ptr_eq(l, other),
+            (&LoxValue::Generator(ref g), &LoxValue::Generator(ref other)) => Rc::ptr_eq(g, other),
+            (&LoxValue::Promise(ref p), &LoxValue::Promise(ref other)) => Rc::ptr_eq(p, other),
             _ => false,
         };
 
@@ -7,5 +7,11 @@ mod interpreter;
 mod environment;
 mod callables;
 mod resolver;
+mod optimizer;
+mod gc;
+mod event_loop;
+mod capabilities;
+mod formatter;
+mod repl;
 pub mod wasm;
 pub mod api;
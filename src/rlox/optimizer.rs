@@ -0,0 +1,298 @@
+use rlox::interpreter::apply_binary_operator;
+use rlox::interpreter::generator::yields;
+use rlox::lox_value::LoxValue;
+use rlox::parser::{Expr, Parameter, Stmt};
+use rlox::token::{Literal, Token, TokenType};
+
+/// Simplifies a resolved program without changing what it does. Operations between literals
+/// are folded into their result, branches and loops behind a constant condition that can't
+/// run are removed, and `!!x` becomes `x` where only its truthiness matters. Operations that
+/// would fail, like `1 / 0`, are kept as they are, so they still fail when and where they
+/// would have. Scopes are untouched, so the distances found by the resolver stay valid.
+pub fn optimize(ast: Vec<Stmt>) -> Vec<Stmt> {
+    statements(ast)
+}
+
+fn statements(stmts: Vec<Stmt>) -> Vec<Stmt> {
+    stmts.into_iter().filter_map(statement).collect()
+}
+
+// The optimized statement, or `None` if it doesn't do anything
+fn statement(stmt: Stmt) -> Option<Stmt> {
+    let stmt = match stmt {
+        Stmt::Print(keyword, expr) => Stmt::Print(keyword, expression(expr)),
+        Stmt::Expr(expr) => Stmt::Expr(expression(expr)),
+        Stmt::Var(name, expr) => Stmt::Var(name, expression(expr)),
+        Stmt::Block(stmts) => Stmt::Block(statements(stmts)),
+        Stmt::If(condition, then_branch, else_branch) => {
+            let condition = truthiness(expression(condition));
+            let then_branch = body(*then_branch);
+            let else_branch = else_branch.map(body);
+
+            // A branch with a `yield` or `await` makes its function a generator or async even
+            // if it never runs, so it has to stay
+            match constant(&condition) {
+                Some(true) if !else_branch.as_ref().is_some_and(yields) => return Some(then_branch),
+                Some(false) if !yields(&then_branch) => return else_branch,
+                _ => Stmt::If(condition, Box::new(then_branch), Box::new(else_branch)),
+            }
+        }
+        Stmt::While(condition, loop_body) => {
+            let condition = truthiness(expression(condition));
+            let loop_body = body(*loop_body);
+
+            if constant(&condition) == Some(false) && !yields(&loop_body) {
+                return None;
+            }
+
+            Stmt::While(condition, Box::new(loop_body))
+        }
+        Stmt::ForIn(name, iterable, loop_body) => {
+            Stmt::ForIn(name, expression(iterable), Box::new(body(*loop_body)))
+        }
+        Stmt::Func(name, params, func_body) => {
+            Stmt::Func(name, parameters(params), Box::new(body(*func_body)))
+        }
+        Stmt::Return(keyword, value) => Stmt::Return(keyword, Box::new(expression(*value))),
+        Stmt::Class(name, superclass, methods) => Stmt::Class(
+            name,
+            superclass,
+            methods
+                .into_iter()
+                .map(|(kind, method)| (kind, body(method)))
+                .collect(),
+        ),
+        Stmt::Match(keyword, value, arms) => Stmt::Match(
+            keyword,
+            expression(value),
+            arms.into_iter()
+                .map(|(pattern, guard, arm_body)| {
+                    let guard = guard.map(|guard| truthiness(expression(guard)));
+                    (pattern, guard, body(arm_body))
+                })
+                .collect(),
+        ),
+    };
+
+    Some(stmt)
+}
+
+// A statement that has to be there, like the body of a loop, is left empty instead of removed
+fn body(stmt: Stmt) -> Stmt {
+    statement(stmt).unwrap_or_else(|| Stmt::Block(Vec::new()))
+}
+
+fn parameters(params: Vec<Parameter>) -> Vec<Parameter> {
+    params
+        .into_iter()
+        .map(|param| match param {
+            Parameter::Optional(name, default) => Parameter::Optional(name, expression(default)),
+            param => param,
+        })
+        .collect()
+}
+
+fn expression(expr: Expr) -> Expr {
+    match expr {
+        Expr::Binary(left, operator, right) => {
+            let left = expression(*left);
+            let right = expression(*right);
+
+            if let Some(result) = fold_binary(&left, &operator, &right) {
+                return Expr::Literal(result);
+            }
+
+            Expr::Binary(Box::new(left), operator, Box::new(right))
+        }
+        Expr::Grouping(expr) => match expression(*expr) {
+            Expr::Literal(literal) => Expr::Literal(literal),
+            expr => Expr::Grouping(Box::new(expr)),
+        },
+        Expr::Unary(operator, expr) => {
+            let expr = match operator.token_type {
+                TokenType::Bang => truthiness(expression(*expr)),
+                _ => expression(*expr),
+            };
+
+            if let Some(result) = fold_unary(&operator, &expr) {
+                return Expr::Literal(result);
+            }
+
+            Expr::Unary(operator, Box::new(expr))
+        }
+        // `and` and `or` evaluate to one of their sides, which one is known if the left is
+        // a literal
+        Expr::Logical(left, operator, right) => {
+            let left = expression(*left);
+            let right = expression(*right);
+
+            match (operator.token_type, constant(&left)) {
+                (TokenType::And, Some(true)) | (TokenType::Or, Some(false)) => right,
+                (_, Some(_)) => left,
+                _ => Expr::Logical(Box::new(left), operator, Box::new(right)),
+            }
+        }
+        Expr::Conditional(condition, then_branch, else_branch) => {
+            let condition = truthiness(expression(*condition));
+
+            match constant(&condition) {
+                Some(true) => expression(*then_branch),
+                Some(false) => expression(*else_branch),
+                None => Expr::Conditional(
+                    Box::new(condition),
+                    Box::new(expression(*then_branch)),
+                    Box::new(expression(*else_branch)),
+                ),
+            }
+        }
+        Expr::Assign(name, value, distance) => {
+            Expr::Assign(name, Box::new(expression(*value)), distance)
+        }
+        Expr::CompoundAssign(target, operator, value) => Expr::CompoundAssign(
+            Box::new(expression(*target)),
+            operator,
+            Box::new(expression(*value)),
+        ),
+        Expr::Postfix(target, operator) => Expr::Postfix(Box::new(expression(*target)), operator),
+        Expr::Call(callee, arguments, paren) => Expr::Call(
+            Box::new(expression(*callee)),
+            expressions(arguments),
+            paren,
+        ),
+        Expr::Get(target, name) => Expr::Get(Box::new(expression(*target)), name),
+        Expr::Set(target, name, value) => Expr::Set(
+            Box::new(expression(*target)),
+            name,
+            Box::new(expression(*value)),
+        ),
+        Expr::Stringify(token, expr) => Expr::Stringify(token, Box::new(expression(*expr))),
+        Expr::Lambda(keyword, params, func_body) => {
+            Expr::Lambda(keyword, parameters(params), Box::new(body(*func_body)))
+        }
+        Expr::List(bracket, items) => Expr::List(bracket, expressions(items)),
+        Expr::Index(target, bracket, index) => Expr::Index(
+            Box::new(expression(*target)),
+            bracket,
+            Box::new(expression(*index)),
+        ),
+        Expr::SetIndex(target, bracket, index, value) => Expr::SetIndex(
+            Box::new(expression(*target)),
+            bracket,
+            Box::new(expression(*index)),
+            Box::new(expression(*value)),
+        ),
+        Expr::Spread(token, expr) => Expr::Spread(token, Box::new(expression(*expr))),
+        Expr::Yield(keyword, expr) => Expr::Yield(keyword, Box::new(expression(*expr))),
+        expr @ Expr::Literal(_)
+        | expr @ Expr::Var(_, _)
+        | expr @ Expr::This(_, _)
+        | expr @ Expr::Super(_, _, _) => expr,
+    }
+}
+
+fn expressions(exprs: Vec<Expr>) -> Vec<Expr> {
+    exprs.into_iter().map(expression).collect()
+}
+
+// Simplifies an expression whose value is only used to check if it's truthy, like a condition
+fn truthiness(expr: Expr) -> Expr {
+    match expr {
+        Expr::Unary(operator, expr) => match (operator.token_type, *expr) {
+            (TokenType::Bang, Expr::Unary(inner, expr)) if inner.token_type == TokenType::Bang => {
+                truthiness(*expr)
+            }
+            (_, expr) => Expr::Unary(operator, Box::new(expr)),
+        },
+        Expr::Grouping(expr) => truthiness(*expr),
+        Expr::Logical(left, operator, right) => Expr::Logical(
+            Box::new(truthiness(*left)),
+            operator,
+            Box::new(truthiness(*right)),
+        ),
+        expr => expr,
+    }
+}
+
+// The value of the expression if it's a literal
+fn value(expr: &Expr) -> Option<LoxValue> {
+    match *expr {
+        Expr::Literal(ref literal) => literal.value(),
+        _ => None,
+    }
+}
+
+// Whether the expression is a literal that is truthy, or one that isn't
+fn constant(expr: &Expr) -> Option<bool> {
+    value(expr).map(|value| value.is_truthy())
+}
+
+// The result of an operation between literals, unless it's an error, which is left for the
+// interpreter to raise
+fn fold_binary(left: &Expr, operator: &Token, right: &Expr) -> Option<Literal> {
+    apply_binary_operator(value(left)?, operator, value(right)?)
+        .ok()
+        .and_then(Literal::from_value)
+}
+
+fn fold_unary(operator: &Token, expr: &Expr) -> Option<Literal> {
+    let value = value(expr)?;
+
+    let result = match operator.token_type {
+        TokenType::Minus => value.negate_number(),
+        TokenType::Bang => value.negate(),
+        TokenType::Tilde => value.bitwise_not(),
+        _ => return None,
+    };
+
+    result.ok().and_then(Literal::from_value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rlox::parser;
+    use rlox::resolver;
+
+    fn optimized(code: &str) -> String {
+        let mut ast = parser::parse(code.to_string()).unwrap();
+        resolver::resolve(&mut ast).unwrap();
+
+        optimize(ast)
+            .iter()
+            .map(|stmt| stmt.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn folds_constants() {
+        assert_eq!(optimized("print x * 60 * 60;"), "(print (* (* (var x) 60) 60))");
+        assert_eq!(optimized("print x * (60 * 60 * 24);"), "(print (* (var x) 86400))");
+        assert_eq!(optimized("print \"a\" + \"b\" == \"ab\";"), "(print true)");
+        assert_eq!(optimized("print false or x;"), "(print (var x))");
+        assert_eq!(optimized("print !(1 < 2) ? 1 : x;"), "(print (var x))");
+        assert_eq!(optimized("print 1 / (2 - 2);"), "(print (/ 1 0))");
+    }
+
+    #[test]
+    fn removes_dead_code() {
+        assert_eq!(optimized("if (false) print 1; print 2;"), "(print 2)");
+        assert_eq!(optimized("if (0) print 1; else print 2;"), "(print 1)");
+        assert_eq!(optimized("while (nil) print 1;"), "");
+        assert_eq!(
+            optimized("while (x) if (false) print 1;"),
+            "(while (var x) (block))"
+        );
+    }
+
+    #[test]
+    fn simplifies_double_negations_in_conditions() {
+        assert_eq!(optimized("if (!!x) print 1;"), "(if (var x) (print 1))");
+        assert_eq!(optimized("print !!!x;"), "(print (! (var x)))");
+        assert_eq!(optimized("print !!x;"), "(print (! (! (var x))))");
+        assert_eq!(
+            optimized("while (!!x and !!(y)) print 1;"),
+            "(while (and (var x) (var y)) (print 1))"
+        );
+    }
+}
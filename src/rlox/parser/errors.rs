@@ -6,28 +6,61 @@ pub enum ParsingError {
     UnexpectedTokenError(Token, String),
     UnexpectedEofError,
     InvalidAssignmentError(Token),
+    // A required parameter after an optional one, which calls could never leave out
+    MissingDefaultError(Token),
     TooManyArgumentsError,
     TooManyParametersError,
+    // A syntax tree given as JSON that can't be read
+    InvalidAstError(String),
     InternalError(String)
 }
 
-impl std::fmt::Display for ParsingError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl ParsingError {
+    pub fn token(&self) -> Option<&Token> {
+        match *self {
+            ParsingError::UnexpectedTokenError(ref token, _)
+            | ParsingError::InvalidAssignmentError(ref token)
+            | ParsingError::MissingDefaultError(ref token) => Some(token),
+            _ => None,
+        }
+    }
+
+    /// The error without the line it happened on.
+    pub fn message(&self) -> String {
         match *self {
             ParsingError::UnexpectedTokenError(ref token, ref message) => {
-                write!(f,
-                       "[line {}] UnexpectedTokenError: {} {}",
-                       token.line,
-                       message,
-                       token.lexeme)
+                format!("{} {}", message, token.lexeme)
+            }
+            ParsingError::UnexpectedEofError => "Unexpected end of input".to_string(),
+            ParsingError::InvalidAssignmentError(_) => "Invalid assignment target".to_string(),
+            ParsingError::MissingDefaultError(ref name) => format!(
+                "Missing default value for `{}`, which follows an optional parameter.",
+                name.lexeme
+            ),
+            ParsingError::InvalidAstError(ref message) => {
+                format!("Invalid syntax tree: {}", message)
+            }
+            ParsingError::InternalError(ref message) => format!("Internal error: {}", message),
+            ParsingError::TooManyArgumentsError => {
+                "Too many arguments, max number is 255".to_string()
             }
-            ParsingError::UnexpectedEofError => f.write_str("Unexpected end of input"),
-            ParsingError::InvalidAssignmentError(ref token) => {
-                write!(f, "[line {}] Invalid assignment target", token.line)
+            ParsingError::TooManyParametersError => {
+                "Too many parameters, max number is 255".to_string()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ParsingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            ParsingError::UnexpectedTokenError(ref token, _) => {
+                write!(f, "[line {}] UnexpectedTokenError: {}", token.line, self.message())
             }
-            ParsingError::InternalError(ref message) => write!(f, "Internal error: {}", message),
-            ParsingError::TooManyArgumentsError => f.write_str("Too many arguments, max number is 8"),
-            ParsingError::TooManyParametersError => f.write_str("Too many parameters, max number is 8")
+            _ => match self.token() {
+                Some(token) => write!(f, "[line {}] {}", token.line, self.message()),
+                None => f.write_str(&self.message()),
+            },
         }
     }
 }
@@ -38,6 +71,8 @@ impl std::error::Error for ParsingError {
             ParsingError::UnexpectedTokenError(_, _) => "UnexpectedTokenError",
             ParsingError::UnexpectedEofError => "UnexpectedEofError",
             ParsingError::InvalidAssignmentError(_) => "InvalidAssignmentError",
+            ParsingError::MissingDefaultError(_) => "MissingDefaultError",
+            ParsingError::InvalidAstError(_) => "InvalidAstError",
             ParsingError::InternalError(_) => "InternalError",
             ParsingError::TooManyArgumentsError => "TooManyArgumentsError",
             ParsingError::TooManyParametersError => "TooManyParametersError"
@@ -1,20 +1,41 @@
 use std;
 use rlox::token::{Literal, Token};
+use rlox::parser::{Parameter, Stmt};
+use rlox::environment::Binding;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "fields")]
 pub enum Expr {
     Binary(Box<Expr>, Token, Box<Expr>),
     Grouping(Box<Expr>),
     Literal(Literal),
     Unary(Token, Box<Expr>),
-    Var(Token, Option<usize>),
-    Assign(Token, Box<Expr>, Option<usize>),
+    // What the resolver finds out about variables isn't part of the syntax, so it isn't
+    // serialized
+    Var(Token, #[serde(skip)] Option<Binding>),
+    Assign(Token, Box<Expr>, #[serde(skip)] Option<Binding>),
+    Conditional(Box<Expr>, Box<Expr>, Box<Expr>),
+    // `target += value`, with the token of the binary operator. The target is a `Var`, `Get`
+    // or `Index` expression.
+    CompoundAssign(Box<Expr>, Token, Box<Expr>),
+    // `target++` or `target--`, evaluating to the value before the update
+    Postfix(Box<Expr>, Token),
     Logical(Box<Expr>, Token, Box<Expr>),
     Call(Box<Expr>, Vec<Expr>, Token),
     Get(Box<Expr>, Token),
     Set(Box<Expr>, Token, Box<Expr>),
-    This(Token, Option<usize>),
-    Super(Token, Token, Option<usize>),
+    This(Token, #[serde(skip)] Option<usize>),
+    Super(Token, Token, #[serde(skip)] Option<usize>),
+    Stringify(Token, Box<Expr>),
+    Lambda(Token, Vec<Parameter>, Box<Stmt>),
+    List(Token, Vec<Expr>),
+    Index(Box<Expr>, Token, Box<Expr>),
+    SetIndex(Box<Expr>, Token, Box<Expr>, Box<Expr>),
+    // `...list`, only valid as an argument or as an element of a list literal
+    Spread(Token, Box<Expr>),
+    // `yield value` or `await promise`, with the keyword. Only valid as a statement or as the
+    // value of a variable declaration or assignment.
+    Yield(Token, Box<Expr>),
 }
 
 impl std::fmt::Display for Expr {
@@ -28,16 +49,56 @@ impl std::fmt::Display for Expr {
             Expr::Unary(ref operator, ref expr) => write!(f, "({} {})", operator.lexeme, expr),
             Expr::Var(ref token, _) => write!(f, "(var {})", token.lexeme),
             Expr::Assign(ref token, ref expr, _) => write!(f, "(assign {} {})", token.lexeme, expr),
+            Expr::Conditional(ref condition, ref then_branch, ref else_branch) => {
+                write!(f, "(? {} {} {})", condition, then_branch, else_branch)
+            }
+            Expr::CompoundAssign(ref target, ref operator, ref expr) => {
+                write!(f, "({}= {} {})", operator.lexeme, target, expr)
+            }
+            Expr::Postfix(ref target, ref operator) => {
+                write!(f, "(postfix {}{} {})", operator.lexeme, operator.lexeme, target)
+            }
             Expr::Logical(ref left, ref operator, ref right) => {
                 write!(f, "({} {} {})", operator.lexeme, left, right)
             }
             Expr::Call(ref callee, ref arguments, _) => {
-                write!(f, "(call {} {:?})", callee, arguments)
+                write!(f, "(call {}{})", callee, Expressions(arguments))
             }
             Expr::Get(ref expr, ref token) => write!(f, "(get {} {})", token.lexeme, expr),
             Expr::Set(ref expr, ref token, _) => write!(f, "(set {} {})", token.lexeme, expr),
             Expr::This(_, _) => write!(f, "this"),
             Expr::Super(_, ref method, _) => write!(f, "(super {})", method.lexeme),
+            Expr::Stringify(_, ref expr) => write!(f, "(str {})", expr),
+            Expr::Lambda(_, ref parameters, ref body) => write!(
+                f,
+                "(fun ({}) {})",
+                parameters
+                    .iter()
+                    .map(|parameter| parameter.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                body
+            ),
+            Expr::List(_, ref items) => write!(f, "(list{})", Expressions(items)),
+            Expr::Index(ref target, _, ref index) => write!(f, "(index {} {})", target, index),
+            Expr::SetIndex(ref target, _, ref index, _) => {
+                write!(f, "(set-index {} {})", target, index)
+            }
+            Expr::Spread(_, ref expr) => write!(f, "(... {})", expr),
+            Expr::Yield(ref keyword, ref expr) => write!(f, "({} {})", keyword.lexeme, expr),
         }
     }
 }
+
+// Expressions separated by spaces, each of them preceded by one
+struct Expressions<'a>(&'a [Expr]);
+
+impl<'a> std::fmt::Display for Expressions<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for expr in self.0 {
+            write!(f, " {}", expr)?;
+        }
+
+        Ok(())
+    }
+}
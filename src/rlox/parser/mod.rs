@@ -1,13 +1,18 @@
 mod token_parser;
 mod stmt;
+mod validation;
 pub mod errors;
 pub mod expr;
 
+use serde_json;
+
 use rlox::token::Token;
+use rlox::scanner::Scanner;
+use rlox::errors::Error;
 use self::errors::ParsingError;
 use self::token_parser::TokenParser;
 pub use self::expr::Expr;
-pub use self::stmt::Stmt;
+pub use self::stmt::{MethodKind, Parameter, Pattern, Stmt};
 
 pub struct Parser {
     tokens: Vec<Token>,
@@ -21,4 +26,67 @@ impl Parser {
     pub fn ast(&self) -> Result<Vec<Stmt>, Vec<ParsingError>> {
         TokenParser::new(self.tokens.clone()).parse()
     }
-}
\ No newline at end of file
+}
+
+/// Scans and parses `code`. Scanner errors are reported before parser ones, which aren't
+/// reported at all if there are any of the former.
+pub fn parse(code: String) -> Result<Vec<Stmt>, Vec<Error>> {
+    let scanner = Scanner::new(code);
+    let (tokens, scanner_errors) = scanner.scan_tokens();
+    let parser = Parser::new(tokens);
+    let ast = parser.ast();
+
+    if scanner_errors.len() > 0 {
+        return Err(scanner_errors
+            .into_iter()
+            .map(|err| Error::Scanner(err))
+            .collect());
+    }
+
+    ast.map_err(|errors| errors.into_iter().map(|err| Error::Parser(err)).collect())
+}
+
+/// Version of the JSON form of syntax trees, for changes that readers of older ones can't handle.
+pub const AST_VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct AstRef<'a> {
+    version: u32,
+    statements: &'a [Stmt],
+}
+
+#[derive(Deserialize)]
+struct Ast {
+    version: u32,
+    statements: Vec<Stmt>,
+}
+
+/// The syntax tree as JSON, an object with the `version` of the format and the `statements`.
+/// Statements, expressions, literals, parameters and patterns are objects with their `type`,
+/// like `Binary`, and their `fields`: the only one, or an array with all of them. Tokens are
+/// objects with their `token_type`, `lexeme`, `literal`, `line` and `column`. Variables aren't
+/// resolved, that's done after reading the tree back.
+pub fn to_json(ast: &[Stmt]) -> String {
+    let ast = AstRef {
+        version: AST_VERSION,
+        statements: ast,
+    };
+
+    serde_json::to_string(&ast).expect("Error serializing a syntax tree")
+}
+
+/// Reads a syntax tree written by `to_json`, rejecting trees the parser couldn't have produced.
+pub fn from_json(json: &str) -> Result<Vec<Stmt>, Vec<Error>> {
+    let invalid = |message: String| vec![Error::Parser(ParsingError::InvalidAstError(message))];
+    let ast: Ast = serde_json::from_str(json).map_err(|err| invalid(err.to_string()))?;
+
+    if ast.version != AST_VERSION {
+        return Err(invalid(format!(
+            "unsupported version {}, expected {}",
+            ast.version, AST_VERSION
+        )));
+    }
+
+    validation::validate(&ast.statements).map_err(invalid)?;
+    Ok(ast.statements)
+}
@@ -1,15 +1,204 @@
+use std;
 use rlox::parser::Expr;
-use rlox::token::Token;
+use rlox::token::{Literal, Token};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "fields")]
 pub enum Stmt {
-    Print(Expr),
+    Print(Token, Expr),
     Expr(Expr),
     Var(Token, Expr),
     Block(Vec<Stmt>),
     If(Expr, Box<Stmt>, Box<Option<Stmt>>),
     While(Expr, Box<Stmt>),
-    Func(Token, Vec<Token>, Box<Stmt>),
+    // `for (name in iterable) body`, with `name` scoped to each iteration
+    ForIn(Token, Expr, Box<Stmt>),
+    Func(Token, Vec<Parameter>, Box<Stmt>),
     Return(Token, Box<Expr>),
-    Class(Token, Option<Expr>, Vec<Stmt>),
+    Class(Token, Option<Expr>, Vec<(MethodKind, Stmt)>),
+    // `match (value) { pattern if guard => body ... }`, each arm with an optional guard
+    Match(Token, Expr, Vec<(Pattern, Option<Expr>, Stmt)>),
+}
+
+impl std::fmt::Display for Stmt {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Stmt::Print(_, ref expr) => write!(f, "(print {})", expr),
+            Stmt::Expr(ref expr) => write!(f, "(; {})", expr),
+            Stmt::Var(ref name, ref expr) => write!(f, "(var {} {})", name.lexeme, expr),
+            Stmt::Block(ref stmts) => write!(f, "(block{})", Statements(stmts)),
+            Stmt::If(ref condition, ref then_branch, ref else_branch) => match **else_branch {
+                Some(ref else_branch) => {
+                    write!(f, "(if {} {} {})", condition, then_branch, else_branch)
+                }
+                None => write!(f, "(if {} {})", condition, then_branch),
+            },
+            Stmt::While(ref condition, ref body) => write!(f, "(while {} {})", condition, body),
+            Stmt::ForIn(ref name, ref iterable, ref body) => {
+                write!(f, "(for-in {} {} {})", name.lexeme, iterable, body)
+            }
+            Stmt::Func(ref name, ref parameters, ref body) => write!(
+                f,
+                "(fun {} ({}) {})",
+                name.lexeme,
+                Parameters(parameters),
+                body
+            ),
+            Stmt::Return(_, ref value) => write!(f, "(return {})", value),
+            Stmt::Class(ref name, ref superclass, ref methods) => {
+                write!(f, "(class {}", name.lexeme)?;
+
+                if let Some(ref superclass) = *superclass {
+                    write!(f, " < {}", superclass)?;
+                }
+
+                for &(kind, ref method) in methods {
+                    match (kind, method) {
+                        (MethodKind::Method, method) => write!(f, " {}", method)?,
+                        (kind, &Stmt::Func(ref name, ref parameters, ref body)) => write!(
+                            f,
+                            " ({} {} ({}) {})",
+                            kind,
+                            name.lexeme,
+                            Parameters(parameters),
+                            body
+                        )?,
+                        (_, method) => write!(f, " {}", method)?,
+                    }
+                }
+
+                write!(f, ")")
+            }
+            Stmt::Match(_, ref value, ref arms) => {
+                write!(f, "(match {}", value)?;
+
+                for &(ref pattern, ref guard, ref body) in arms {
+                    match *guard {
+                        Some(ref guard) => write!(f, " ({} if {} {})", pattern, guard, body)?,
+                        None => write!(f, " ({} {})", pattern, body)?,
+                    }
+                }
+
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+// Statements separated by spaces, each of them preceded by one
+struct Statements<'a>(&'a [Stmt]);
+
+impl<'a> std::fmt::Display for Statements<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for stmt in self.0 {
+            write!(f, " {}", stmt)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Parameters<'a>(&'a [Parameter]);
+
+impl<'a> std::fmt::Display for Parameters<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let parameters: Vec<String> = self.0.iter().map(|parameter| parameter.to_string()).collect();
+        write!(f, "{}", parameters.join(" "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MethodKind {
+    Method,
+    // Declared without a parameter list, runs when reading the property
+    Getter,
+    // Declared with `set`, runs when assigning to the property
+    Setter,
+    // Declared with `class` or `static`, called on the class itself
+    Static,
+}
+
+impl std::fmt::Display for MethodKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            MethodKind::Method => write!(f, "method"),
+            MethodKind::Getter => write!(f, "get"),
+            MethodKind::Setter => write!(f, "set"),
+            MethodKind::Static => write!(f, "static"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "fields")]
+pub enum Pattern {
+    // `_`, matches any value
+    Wildcard(Token),
+    // A number, string, boolean or `nil`, matches values equal to it
+    Literal(Token, Literal),
+    // `name`, matches any value and binds it to `name`
+    Binding(Token),
+    // `Point p` or `Point _`, matches instances of the class or of one of its subclasses
+    Class(Expr, Option<Token>),
+}
+
+impl Pattern {
+    /// Whether the pattern matches any value, which makes the arms after it unreachable.
+    pub fn is_irrefutable(&self) -> bool {
+        matches!(*self, Pattern::Wildcard(_) | Pattern::Binding(_))
+    }
+
+    pub fn binding(&self) -> Option<&Token> {
+        match *self {
+            Pattern::Binding(ref name) | Pattern::Class(_, Some(ref name)) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Pattern::Wildcard(_) => write!(f, "_"),
+            Pattern::Literal(_, Literal::String(ref string)) => write!(f, "{:?}", string),
+            Pattern::Literal(_, ref literal) => write!(f, "{}", literal),
+            Pattern::Binding(ref name) => write!(f, "{}", name.lexeme),
+            Pattern::Class(ref class, ref name) => {
+                let name = name.as_ref().map_or("_", |name| name.lexeme.as_str());
+                write!(f, "(is {} {})", class, name)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "fields")]
+pub enum Parameter {
+    Required(Token),
+    // `name = default`, the default is evaluated on every call that doesn't pass the argument
+    Optional(Token, Expr),
+    // `...name`, collects the remaining arguments into a list
+    Rest(Token),
+}
+
+impl Parameter {
+    pub fn name(&self) -> &Token {
+        match *self {
+            Parameter::Required(ref name)
+            | Parameter::Optional(ref name, _)
+            | Parameter::Rest(ref name) => name,
+        }
+    }
+}
+
+impl std::fmt::Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Parameter::Required(ref name) => write!(f, "{}", name.lexeme),
+            Parameter::Optional(ref name, ref default) => {
+                write!(f, "(= {} {})", name.lexeme, default)
+            }
+            Parameter::Rest(ref name) => write!(f, "...{}", name.lexeme),
+        }
+    }
 }
@@ -1,6 +1,9 @@
 use rlox::token::{Literal, Token, TokenType};
 use rlox::parser::errors::ParsingError;
-use rlox::parser::{Expr, Stmt};
+use rlox::parser::{Expr, MethodKind, Parameter, Pattern, Stmt};
+
+// Maximum number of parameters of a function, and of arguments in a call
+const MAX_ARGUMENTS: usize = 255;
 
 pub struct TokenParser {
     tokens: Vec<Token>,
@@ -35,7 +38,8 @@ impl TokenParser {
             self.class_declaration()
         } else if self.next_is(vec![TokenType::Var]) {
             self.var_declaration()
-        } else if self.next_is(vec![TokenType::Fun]) {
+        } else if self.check(TokenType::Fun) && !self.check_next(TokenType::LeftParen) {
+            self.advance();
             self.fun_declaration("function")
         } else {
             self.statement()
@@ -70,7 +74,7 @@ impl TokenParser {
 
         let mut methods = Vec::new();
         while !self.check(TokenType::RightBrace) && !self.is_over() {
-            methods.push(self.fun_declaration("method")?);
+            methods.push(self.method_declaration()?);
         }
 
         self.consume(
@@ -81,6 +85,43 @@ impl TokenParser {
         Ok(Stmt::Class(name, superclass, methods))
     }
 
+    fn method_declaration(&mut self) -> Result<(MethodKind, Stmt), ParsingError> {
+        if self.next_is(vec![TokenType::Class]) || self.next_is_contextual("static") {
+            return Ok((MethodKind::Static, self.fun_declaration("static method")?));
+        }
+
+        if self.next_is_contextual("set") {
+            let setter = self.fun_declaration("setter")?;
+
+            if let Stmt::Func(ref name, ref parameters, _) = setter {
+                let is_single_value =
+                    parameters.len() == 1 && !matches!(parameters[0], Parameter::Rest(_));
+
+                if !is_single_value {
+                    return Err(ParsingError::UnexpectedTokenError(
+                        name.clone(),
+                        "Expected exactly one parameter for setter".to_string(),
+                    ));
+                }
+            }
+
+            return Ok((MethodKind::Setter, setter));
+        }
+
+        if self.check(TokenType::Identifier) && self.check_next(TokenType::LeftBrace) {
+            let name = self.advance().clone();
+            self.consume(
+                TokenType::LeftBrace,
+                "Expected `{` before getter body.".to_string(),
+            )?;
+            let body = self.block_statement()?;
+
+            return Ok((MethodKind::Getter, Stmt::Func(name, Vec::new(), Box::new(body))));
+        }
+
+        Ok((MethodKind::Method, self.fun_declaration("method")?))
+    }
+
     fn var_declaration(&mut self) -> Result<Stmt, ParsingError> {
         let name = self.consume(TokenType::Identifier, "Expected variable name".to_string())?;
 
@@ -104,23 +145,36 @@ impl TokenParser {
             TokenType::LeftParen,
             format!("Expected `(` after {} name.", kind),
         )?;
-        let mut parameters: Vec<Token> = Vec::new();
+        let parameters = self.parameters()?;
 
-        if !self.check(TokenType::RightParen) {
-            parameters
-                .push(self.consume(TokenType::Identifier, "Expected parameter name".to_string())?);
+        self.consume(
+            TokenType::LeftBrace,
+            format!("Expected `{{` before {} body.", kind),
+        )?;
+
+        let body = self.block_statement()?;
 
-            while self.next_is(vec![TokenType::Comma]) {
-                if parameters.len() >= 8 {
+        Ok(Stmt::Func(name, parameters, Box::new(body)))
+    }
+
+    // Parses a parameter list, including the closing `)`
+    fn parameters(&mut self) -> Result<Vec<Parameter>, ParsingError> {
+        let mut parameters: Vec<Parameter> = Vec::new();
+
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if parameters.len() >= MAX_ARGUMENTS {
                     // TODO: The reference interpreter doesn't bail on this error,
                     // it keeps on parsing but reports it.
                     return Err(ParsingError::TooManyParametersError);
                 }
 
-                parameters.push(self.consume(
-                    TokenType::Identifier,
-                    "Expected parameter name".to_string(),
-                )?)
+                let parameter = self.parameter(&parameters)?;
+                parameters.push(parameter);
+
+                if !self.next_is(vec![TokenType::Comma]) {
+                    break;
+                }
             }
         }
 
@@ -129,14 +183,121 @@ impl TokenParser {
             "Expect `)` after parameters.".to_string(),
         )?;
 
+        Ok(parameters)
+    }
+
+    fn parameter(&mut self, previous: &[Parameter]) -> Result<Parameter, ParsingError> {
+        if let Some(&Parameter::Rest(_)) = previous.last() {
+            return Err(ParsingError::UnexpectedTokenError(
+                self.peek().clone(),
+                "Expected `)` after rest parameter".to_string(),
+            ));
+        }
+
+        if self.next_is(vec![TokenType::Ellipsis]) {
+            let name = self.consume(
+                TokenType::Identifier,
+                "Expected parameter name after `...`".to_string(),
+            )?;
+            return Ok(Parameter::Rest(name));
+        }
+
+        let name = self.consume(TokenType::Identifier, "Expected parameter name".to_string())?;
+
+        if self.next_is(vec![TokenType::Equal]) {
+            let default = self.expression()?;
+            return Ok(Parameter::Optional(name, default));
+        }
+
+        let follows_optional = previous
+            .iter()
+            .any(|parameter| matches!(*parameter, Parameter::Optional(_, _)));
+
+        if follows_optional {
+            return Err(ParsingError::MissingDefaultError(name));
+        }
+
+        Ok(Parameter::Required(name))
+    }
+
+    // `fun (a, b) { ... }`
+    fn lambda(&mut self) -> Result<Expr, ParsingError> {
+        let keyword = self.previous().clone();
+
+        self.consume(
+            TokenType::LeftParen,
+            "Expected `(` after `fun`.".to_string(),
+        )?;
+        let parameters = self.parameters()?;
+
         self.consume(
             TokenType::LeftBrace,
-            format!("Expected `{{` before {} body.", kind),
+            "Expected `{` before function body.".to_string(),
         )?;
-
         let body = self.block_statement()?;
 
-        Ok(Stmt::Func(name, parameters, Box::new(body)))
+        Ok(Expr::Lambda(keyword, parameters, Box::new(body)))
+    }
+
+    // `(a, b) => a + b` or `(a, b) => { ... }`
+    fn arrow_function(&mut self) -> Result<Expr, ParsingError> {
+        let paren = self.consume(TokenType::LeftParen, "Expected `(`.".to_string())?;
+        let parameters = self.parameters()?;
+        let arrow = self.consume(
+            TokenType::Arrow,
+            "Expected `=>` after parameters.".to_string(),
+        )?;
+
+        let body = if self.next_is(vec![TokenType::LeftBrace]) {
+            self.block_statement()?
+        } else {
+            let value = self.expression()?;
+            Stmt::Block(vec![Stmt::Return(arrow, Box::new(value))])
+        };
+
+        Ok(Expr::Lambda(paren, parameters, Box::new(body)))
+    }
+
+    // Looks ahead from a `(` to tell a parameter list followed by `=>` from a grouping
+    fn is_arrow_function(&self) -> bool {
+        self.token_after_parens(self.current) == Some(TokenType::Arrow)
+    }
+
+    // `match` isn't a reserved word, it only starts a statement when followed by
+    // `(subject) {`, which can't be a valid call.
+    fn is_match_statement(&self) -> bool {
+        self.check(TokenType::Identifier) && self.peek().lexeme == "match"
+            && self.check_next(TokenType::LeftParen)
+            && self.token_after_parens(self.current + 1) == Some(TokenType::LeftBrace)
+    }
+
+    // Type of the token right after the balanced parentheses that open at `start`. A `;` only
+    // ends the search outside of braces, since the parentheses can have functions with a block
+    // body inside, like the default value in `(f = fun () { return 1; }) => f()`.
+    fn token_after_parens(&self, start: usize) -> Option<TokenType> {
+        let mut depth = 0;
+        let mut braces = 0;
+
+        for (i, token) in self.tokens.iter().enumerate().skip(start) {
+            match token.token_type {
+                TokenType::LeftParen => depth += 1,
+                TokenType::RightParen => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        return self.tokens.get(i + 1).map(|token| token.token_type);
+                    }
+                }
+                TokenType::LeftBrace => braces += 1,
+                TokenType::RightBrace if braces == 0 => return None,
+                TokenType::RightBrace => braces -= 1,
+                TokenType::Semicolon if braces == 0 => return None,
+                TokenType::Eof => return None,
+                _ => {}
+            }
+        }
+
+        None
     }
 
     fn statement(&mut self) -> Result<Stmt, ParsingError> {
@@ -152,6 +313,9 @@ impl TokenParser {
             self.for_statement()
         } else if self.next_is(vec![TokenType::Return]) {
             self.return_statement()
+        } else if self.is_match_statement() {
+            self.advance();
+            self.match_statement()
         } else {
             self.expression_statement()
         }
@@ -225,6 +389,10 @@ impl TokenParser {
     fn for_statement(&mut self) -> Result<Stmt, ParsingError> {
         self.consume(TokenType::LeftParen, "Expected `(` after `for`".to_string())?;
 
+        if self.is_for_in() {
+            return self.for_in_statement();
+        }
+
         let initializer = if self.next_is(vec![TokenType::Semicolon]) {
             None
         } else if self.next_is(vec![TokenType::Var]) {
@@ -271,6 +439,44 @@ impl TokenParser {
         Ok(body)
     }
 
+    fn for_in_statement(&mut self) -> Result<Stmt, ParsingError> {
+        self.next_is(vec![TokenType::Var]);
+        let name = self.consume(
+            TokenType::Identifier,
+            "Expected loop variable name".to_string(),
+        )?;
+
+        // Skips `in`, already checked by `is_for_in`
+        self.advance();
+        let iterable = self.expression()?;
+        self.consume(
+            TokenType::RightParen,
+            "Expect `)` after for clause.".to_string(),
+        )?;
+
+        let body = self.statement()?;
+
+        Ok(Stmt::ForIn(name, iterable, Box::new(body)))
+    }
+
+    // `in` isn't a reserved word, a loop is a for-in when its clause starts with `name in` or
+    // `var name in`
+    fn is_for_in(&self) -> bool {
+        let start = if self.check(TokenType::Var) {
+            self.current + 1
+        } else {
+            self.current
+        };
+
+        match (self.tokens.get(start), self.tokens.get(start + 1)) {
+            (Some(name), Some(keyword)) => {
+                name.token_type == TokenType::Identifier
+                    && keyword.token_type == TokenType::Identifier && keyword.lexeme == "in"
+            }
+            _ => false,
+        }
+    }
+
     fn return_statement(&mut self) -> Result<Stmt, ParsingError> {
         let keyword = self.previous().clone();
 
@@ -288,14 +494,116 @@ impl TokenParser {
         Ok(Stmt::Return(keyword, Box::new(value)))
     }
 
+    fn match_statement(&mut self) -> Result<Stmt, ParsingError> {
+        let keyword = self.previous().clone();
+
+        self.consume(
+            TokenType::LeftParen,
+            "Expected `(` after `match`".to_string(),
+        )?;
+        let subject = self.expression()?;
+        self.consume(
+            TokenType::RightParen,
+            "Expected `)` after match subject".to_string(),
+        )?;
+        self.consume(
+            TokenType::LeftBrace,
+            "Expected `{` before match arms".to_string(),
+        )?;
+
+        let mut arms = Vec::new();
+
+        while !self.check(TokenType::RightBrace) && !self.is_over() {
+            let pattern = self.pattern()?;
+
+            let guard = if self.next_is(vec![TokenType::If]) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+
+            self.consume(
+                TokenType::Arrow,
+                "Expected `=>` after pattern".to_string(),
+            )?;
+            let body = self.statement()?;
+
+            // Arms can optionally be separated by commas
+            self.next_is(vec![TokenType::Comma]);
+            arms.push((pattern, guard, body));
+        }
+
+        self.consume(
+            TokenType::RightBrace,
+            "Expected `}` after match arms".to_string(),
+        )?;
+
+        Ok(Stmt::Match(keyword, subject, arms))
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, ParsingError> {
+        if self.next_is(vec![
+            TokenType::Number,
+            TokenType::String,
+            TokenType::False,
+            TokenType::True,
+            TokenType::Nil,
+        ]) {
+            let token = self.previous().clone();
+
+            return match token.literal.clone() {
+                Some(literal) => Ok(Pattern::Literal(token, literal)),
+                None => Err(ParsingError::InternalError(
+                    "Missing literal value".to_string(),
+                )),
+            };
+        }
+
+        if self.next_is(vec![TokenType::Minus]) {
+            let token = self.consume(
+                TokenType::Number,
+                "Expected number after `-` in pattern".to_string(),
+            )?;
+
+            return match token.literal {
+                Some(Literal::Number(number)) => {
+                    Ok(Pattern::Literal(token, Literal::Number(-number)))
+                }
+                _ => Err(ParsingError::InternalError(
+                    "Missing literal value".to_string(),
+                )),
+            };
+        }
+
+        let name = self.consume(TokenType::Identifier, "Expected pattern".to_string())?;
+
+        if self.next_is(vec![TokenType::Identifier]) {
+            let binding = self.previous().clone();
+            let binding = if binding.lexeme == "_" {
+                None
+            } else {
+                Some(binding)
+            };
+
+            return Ok(Pattern::Class(Expr::Var(name, None), binding));
+        }
+
+        if name.lexeme == "_" {
+            Ok(Pattern::Wildcard(name))
+        } else {
+            Ok(Pattern::Binding(name))
+        }
+    }
+
     fn print_statement(&mut self) -> Result<Stmt, ParsingError> {
+        let keyword = self.previous().clone();
         let expr = self.expression()?;
 
         match self.consume(
             TokenType::Semicolon,
             "Expect ';' after expression.".to_string(),
         ) {
-            Ok(_) => Ok(Stmt::Print(expr)),
+            Ok(_) => Ok(Stmt::Print(keyword, expr)),
             Err(err) => Err(err),
         }
     }
@@ -305,7 +613,11 @@ impl TokenParser {
     }
 
     fn assignment(&mut self) -> Result<Expr, ParsingError> {
-        let expr = self.or()?;
+        if self.next_is(vec![TokenType::Yield, TokenType::Await]) {
+            return self.yield_expression();
+        }
+
+        let expr = self.conditional()?;
 
         if self.next_is(vec![TokenType::Equal]) {
             let token = self.previous().clone();
@@ -316,13 +628,61 @@ impl TokenParser {
                     return Ok(Expr::Assign(token, Box::new(value), None));
                 }
                 Expr::Get(target, token) => return Ok(Expr::Set(target, token, Box::new(value))),
+                Expr::Index(target, bracket, index) => {
+                    return Ok(Expr::SetIndex(target, bracket, index, Box::new(value)))
+                }
                 _ => return Err(ParsingError::InvalidAssignmentError(token)),
             }
         }
 
+        if self.next_is(vec![
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+        ]) {
+            let token = self.previous().clone();
+            let value = self.assignment()?;
+
+            return compound_assignment(expr, &token, value);
+        }
+
         Ok(expr)
     }
 
+    fn yield_expression(&mut self) -> Result<Expr, ParsingError> {
+        let keyword = self.previous().clone();
+
+        let value = if self.check(TokenType::Semicolon) || self.check(TokenType::RightParen) {
+            Expr::Literal(Literal::Nil)
+        } else {
+            self.assignment()?
+        };
+
+        Ok(Expr::Yield(keyword, Box::new(value)))
+    }
+
+    fn conditional(&mut self) -> Result<Expr, ParsingError> {
+        let condition = self.or()?;
+
+        if self.next_is(vec![TokenType::Question]) {
+            let then_branch = self.expression()?;
+            self.consume(
+                TokenType::Colon,
+                "Expected `:` after the then branch of a conditional expression.".to_string(),
+            )?;
+            let else_branch = self.conditional()?;
+
+            return Ok(Expr::Conditional(
+                Box::new(condition),
+                Box::new(then_branch),
+                Box::new(else_branch),
+            ));
+        }
+
+        Ok(condition)
+    }
+
     fn or(&mut self) -> Result<Expr, ParsingError> {
         let mut expr = self.and()?;
 
@@ -348,9 +708,47 @@ impl TokenParser {
     }
 
     fn equality(&mut self) -> Result<Expr, ParsingError> {
-        let mut expr = self.comparison()?;
+        let mut expr = self.bitwise_or()?;
 
         while self.next_is(vec![TokenType::BangEqual, TokenType::EqualEqual]) {
+            let operator = self.previous().clone();
+            let right = self.bitwise_or()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    // Bitwise operators bind tighter than equality, so `flags & MASK == MASK` compares the
+    // result of the `&`.
+    fn bitwise_or(&mut self) -> Result<Expr, ParsingError> {
+        let mut expr = self.bitwise_xor()?;
+
+        while self.next_is(vec![TokenType::Pipe]) {
+            let operator = self.previous().clone();
+            let right = self.bitwise_xor()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn bitwise_xor(&mut self) -> Result<Expr, ParsingError> {
+        let mut expr = self.bitwise_and()?;
+
+        while self.next_is(vec![TokenType::Caret]) {
+            let operator = self.previous().clone();
+            let right = self.bitwise_and()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn bitwise_and(&mut self) -> Result<Expr, ParsingError> {
+        let mut expr = self.comparison()?;
+
+        while self.next_is(vec![TokenType::Ampersand]) {
             let operator = self.previous().clone();
             let right = self.comparison()?;
             expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
@@ -360,7 +758,7 @@ impl TokenParser {
     }
 
     fn comparison(&mut self) -> Result<Expr, ParsingError> {
-        let mut expr = self.addition()?;
+        let mut expr = self.shift()?;
 
         while self.next_is(vec![
             TokenType::Greater,
@@ -368,6 +766,18 @@ impl TokenParser {
             TokenType::Less,
             TokenType::LessEqual,
         ]) {
+            let operator = self.previous().clone();
+            let right = self.shift()?;
+            expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
+        }
+
+        Ok(expr)
+    }
+
+    fn shift(&mut self) -> Result<Expr, ParsingError> {
+        let mut expr = self.addition()?;
+
+        while self.next_is(vec![TokenType::LessLess, TokenType::GreaterGreater]) {
             let operator = self.previous().clone();
             let right = self.addition()?;
             expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
@@ -391,7 +801,12 @@ impl TokenParser {
     fn multiplication(&mut self) -> Result<Expr, ParsingError> {
         let mut expr = self.unary()?;
 
-        while self.next_is(vec![TokenType::Slash, TokenType::Star]) {
+        while self.next_is(vec![
+            TokenType::Slash,
+            TokenType::Star,
+            TokenType::Percent,
+            TokenType::TildeSlash,
+        ]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
             expr = Expr::Binary(Box::new(expr), operator, Box::new(right));
@@ -401,13 +816,50 @@ impl TokenParser {
     }
 
     fn unary(&mut self) -> Result<Expr, ParsingError> {
-        if self.next_is(vec![TokenType::Bang, TokenType::Minus]) {
+        if self.next_is(vec![TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
             let operator = self.previous().clone();
             let right = self.unary()?;
             return Ok(Expr::Unary(operator, Box::new(right)));
         }
 
-        self.call()
+        if self.next_is(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let token = self.previous().clone();
+            let target = self.unary()?;
+            return compound_assignment(target, &token, Expr::Literal(Literal::Number(1.0)));
+        }
+
+        self.power()
+    }
+
+    // `**` binds tighter than a unary operator on its left and is right associative, so
+    // `-2 ** 2` is `-(2 ** 2)` and `2 ** 3 ** 2` is `2 ** (3 ** 2)`.
+    fn power(&mut self) -> Result<Expr, ParsingError> {
+        let expr = self.postfix()?;
+
+        if self.next_is(vec![TokenType::StarStar]) {
+            let operator = self.previous().clone();
+            let right = self.unary()?;
+            return Ok(Expr::Binary(Box::new(expr), operator, Box::new(right)));
+        }
+
+        Ok(expr)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, ParsingError> {
+        let expr = self.call()?;
+
+        if self.next_is(vec![TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let token = self.previous().clone();
+
+            return match expr {
+                Expr::Var(_, _) | Expr::Get(_, _) | Expr::Index(_, _, _) => {
+                    Ok(Expr::Postfix(Box::new(expr), binary_operator(&token)))
+                }
+                _ => Err(ParsingError::InvalidAssignmentError(token)),
+            };
+        }
+
+        Ok(expr)
     }
 
     fn call(&mut self) -> Result<Expr, ParsingError> {
@@ -422,6 +874,14 @@ impl TokenParser {
                     "Expected property name after `.`.".to_string(),
                 )?;
                 expr = Expr::Get(Box::new(expr), name);
+            } else if self.next_is(vec![TokenType::LeftBracket]) {
+                let bracket = self.previous().clone();
+                let index = self.expression()?;
+                self.consume(
+                    TokenType::RightBracket,
+                    "Expected `]` after index.".to_string(),
+                )?;
+                expr = Expr::Index(Box::new(expr), bracket, Box::new(index));
             } else {
                 break;
             }
@@ -434,15 +894,18 @@ impl TokenParser {
         let mut arguments: Vec<Expr> = Vec::new();
 
         if !self.check(TokenType::RightParen) {
-            arguments.push(self.expression()?);
-
-            while self.next_is(vec![TokenType::Comma]) {
-                if arguments.len() >= 8 {
+            loop {
+                if arguments.len() >= MAX_ARGUMENTS {
                     // TODO: The reference interpreter doesn't bail on this error,
                     // it keeps on parsing but reports it.
                     return Err(ParsingError::TooManyArgumentsError);
                 }
-                arguments.push(self.expression()?);
+
+                arguments.push(self.element()?);
+
+                if !self.next_is(vec![TokenType::Comma]) {
+                    break;
+                }
             }
         }
 
@@ -454,6 +917,39 @@ impl TokenParser {
         Ok(Expr::Call(Box::new(callee), arguments, paren))
     }
 
+    // An argument of a call or an element of a list literal, which can be spread with `...`
+    fn element(&mut self) -> Result<Expr, ParsingError> {
+        if self.next_is(vec![TokenType::Ellipsis]) {
+            let token = self.previous().clone();
+            let expr = self.expression()?;
+            Ok(Expr::Spread(token, Box::new(expr)))
+        } else {
+            self.expression()
+        }
+    }
+
+    fn list(&mut self) -> Result<Expr, ParsingError> {
+        let bracket = self.previous().clone();
+        let mut items: Vec<Expr> = Vec::new();
+
+        if !self.check(TokenType::RightBracket) {
+            loop {
+                items.push(self.element()?);
+
+                if !self.next_is(vec![TokenType::Comma]) {
+                    break;
+                }
+            }
+        }
+
+        self.consume(
+            TokenType::RightBracket,
+            "Expected `]` after list elements.".to_string(),
+        )?;
+
+        Ok(Expr::List(bracket, items))
+    }
+
     fn primary(&mut self) -> Result<Expr, ParsingError> {
         if self.next_is(vec![
             TokenType::Number,
@@ -470,6 +966,10 @@ impl TokenParser {
             };
         }
 
+        if self.next_is(vec![TokenType::Interpolation]) {
+            return self.interpolation();
+        }
+
         if self.next_is(vec![TokenType::Super]) {
             let keyword = self.previous().clone();
             self.consume(TokenType::Dot, "Expect '.' after 'super'.".to_string())?;
@@ -480,6 +980,18 @@ impl TokenParser {
             return Ok(Expr::Super(keyword, method, None));
         }
 
+        if self.next_is(vec![TokenType::Fun]) {
+            return self.lambda();
+        }
+
+        if self.check(TokenType::LeftParen) && self.is_arrow_function() {
+            return self.arrow_function();
+        }
+
+        if self.next_is(vec![TokenType::LeftBracket]) {
+            return self.list();
+        }
+
         if self.next_is(vec![TokenType::This]) {
             return Ok(Expr::This(self.previous().clone(), None));
         }
@@ -510,6 +1022,57 @@ impl TokenParser {
         }
     }
 
+    // Lowers `"a ${b} c"` into `"a " + str(b) + " c"`
+    fn interpolation(&mut self) -> Result<Expr, ParsingError> {
+        let mut expr = self.string_part()?;
+
+        loop {
+            let token = self.previous().clone();
+            let value = self.expression()?;
+            expr = self.concatenate(expr, Expr::Stringify(token, Box::new(value)));
+
+            let is_last_part = if self.next_is(vec![TokenType::Interpolation]) {
+                false
+            } else {
+                self.consume(
+                    TokenType::String,
+                    "Expected `}` after interpolated expression.".to_string(),
+                )?;
+                true
+            };
+
+            match self.string_part()? {
+                Expr::Literal(Literal::String(ref part)) if part.is_empty() => {}
+                part => expr = self.concatenate(expr, part),
+            }
+
+            if is_last_part {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn concatenate(&self, left: Expr, right: Expr) -> Expr {
+        let plus = Token::new(
+            TokenType::Plus,
+            "+".to_string(),
+            None,
+            self.previous().line,
+            self.previous().column,
+        );
+
+        Expr::Binary(Box::new(left), plus, Box::new(right))
+    }
+
+    fn string_part(&self) -> Result<Expr, ParsingError> {
+        match self.previous().literal {
+            Some(ref literal) => Ok(Expr::Literal(literal.clone())),
+            None => Err(ParsingError::InternalError(
+                "Missing literal value".to_string(),
+            )),
+        }
+    }
+
     // Infrastructure
     fn next_is(&mut self, types: Vec<TokenType>) -> bool {
         for token_type in types {
@@ -522,6 +1085,26 @@ impl TokenParser {
         false
     }
 
+    // `static` and `set` aren't reserved words, they only have a special meaning inside a class
+    // body when followed by the name of a method.
+    fn next_is_contextual(&mut self, keyword: &str) -> bool {
+        if self.check(TokenType::Identifier) && self.peek().lexeme == keyword
+            && self.check_next(TokenType::Identifier)
+        {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn check_next(&self, token_type: TokenType) -> bool {
+        match self.tokens.get(self.current + 1) {
+            Some(token) => token.token_type == token_type,
+            None => false,
+        }
+    }
+
     fn check(&self, token_type: TokenType) -> bool {
         if self.is_over() {
             false
@@ -577,7 +1160,9 @@ impl TokenParser {
                 | TokenType::If
                 | TokenType::While
                 | TokenType::Print
-                | TokenType::Return => return,
+                | TokenType::Return
+                | TokenType::Yield
+                | TokenType::Await => return,
                 _ => {}
             }
 
@@ -585,3 +1170,28 @@ impl TokenParser {
         }
     }
 }
+
+// Builds the expression for `target op= value`, also used for a prefix `++` or `--`
+fn compound_assignment(target: Expr, token: &Token, value: Expr) -> Result<Expr, ParsingError> {
+    match target {
+        Expr::Var(_, _) | Expr::Get(_, _) | Expr::Index(_, _, _) => Ok(Expr::CompoundAssign(
+            Box::new(target),
+            binary_operator(token),
+            Box::new(value),
+        )),
+        _ => Err(ParsingError::InvalidAssignmentError(token.clone())),
+    }
+}
+
+// The binary operator applied by a compound assignment, `++` or `--`
+fn binary_operator(token: &Token) -> Token {
+    let (token_type, lexeme) = match token.token_type {
+        TokenType::PlusEqual | TokenType::PlusPlus => (TokenType::Plus, "+"),
+        TokenType::MinusEqual | TokenType::MinusMinus => (TokenType::Minus, "-"),
+        TokenType::StarEqual => (TokenType::Star, "*"),
+        TokenType::SlashEqual => (TokenType::Slash, "/"),
+        _ => (token.token_type, token.lexeme.as_str()),
+    };
+
+    Token::new(token_type, lexeme.to_string(), None, token.line, token.column)
+}
@@ -0,0 +1,152 @@
+use rlox::parser::{Expr, Parameter, Pattern, Stmt};
+
+/// Checks that a syntax tree read from JSON is one the parser could have produced, as far as
+/// the resolver and the interpreter rely on it: function bodies and methods have the right
+/// statements, parameters are in an order calls can fill, and updates have a valid target.
+/// The error says what is wrong, to be reported as an `InvalidAstError`.
+pub fn validate(ast: &[Stmt]) -> Result<(), String> {
+    statements(ast)
+}
+
+fn statements(stmts: &[Stmt]) -> Result<(), String> {
+    stmts.iter().try_for_each(statement)
+}
+
+fn statement(stmt: &Stmt) -> Result<(), String> {
+    match *stmt {
+        Stmt::Print(_, ref expr) | Stmt::Expr(ref expr) | Stmt::Var(_, ref expr) => {
+            expression(expr)
+        }
+        Stmt::Block(ref stmts) => statements(stmts),
+        Stmt::If(ref condition, ref then_branch, ref else_branch) => {
+            expression(condition)?;
+            statement(then_branch)?;
+
+            match **else_branch {
+                Some(ref else_branch) => statement(else_branch),
+                None => Ok(()),
+            }
+        }
+        Stmt::While(ref condition, ref body) => {
+            expression(condition)?;
+            statement(body)
+        }
+        Stmt::ForIn(_, ref iterable, ref body) => {
+            expression(iterable)?;
+            statement(body)
+        }
+        Stmt::Func(ref name, ref params, ref body) => function(&name.lexeme, params, body),
+        Stmt::Return(_, ref value) => expression(value),
+        Stmt::Class(ref name, ref superclass, ref methods) => {
+            if let Some(ref superclass) = *superclass {
+                expression(superclass)?;
+            }
+
+            for (_, method) in methods.iter() {
+                match *method {
+                    Stmt::Func(_, _, _) => statement(method)?,
+                    _ => return Err(format!("a method of `{}` isn't a function", name.lexeme)),
+                }
+            }
+
+            Ok(())
+        }
+        Stmt::Match(_, ref value, ref arms) => {
+            expression(value)?;
+
+            for (pattern, guard, body) in arms.iter() {
+                if let Pattern::Class(ref class, _) = *pattern {
+                    expression(class)?;
+                }
+
+                if let Some(guard) = guard {
+                    expression(guard)?;
+                }
+
+                statement(body)?;
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn function(name: &str, params: &[Parameter], body: &Stmt) -> Result<(), String> {
+    let mut optional = false;
+
+    for (index, param) in params.iter().enumerate() {
+        match *param {
+            Parameter::Required(ref param) if optional => {
+                return Err(format!(
+                    "the parameter `{}` of `{}` is required after an optional one",
+                    param.lexeme, name
+                ))
+            }
+            Parameter::Rest(ref param) if index + 1 < params.len() => {
+                return Err(format!(
+                    "the rest parameter `{}` of `{}` isn't the last one",
+                    param.lexeme, name
+                ))
+            }
+            Parameter::Optional(_, ref default) => {
+                optional = true;
+                expression(default)?;
+            }
+            _ => {}
+        }
+    }
+
+    match *body {
+        Stmt::Block(ref stmts) => statements(stmts),
+        _ => Err(format!("the body of `{}` isn't a block", name)),
+    }
+}
+
+fn expression(expr: &Expr) -> Result<(), String> {
+    match *expr {
+        Expr::Literal(_) | Expr::Var(_, _) | Expr::This(_, _) | Expr::Super(_, _, _) => Ok(()),
+        Expr::Grouping(ref expr)
+        | Expr::Unary(_, ref expr)
+        | Expr::Assign(_, ref expr, _)
+        | Expr::Get(ref expr, _)
+        | Expr::Stringify(_, ref expr)
+        | Expr::Spread(_, ref expr)
+        | Expr::Yield(_, ref expr) => expression(expr),
+        Expr::Binary(ref left, _, ref right)
+        | Expr::Logical(ref left, _, ref right)
+        | Expr::Set(ref left, _, ref right)
+        | Expr::Index(ref left, _, ref right) => {
+            expression(left)?;
+            expression(right)
+        }
+        Expr::Conditional(ref condition, ref then_branch, ref else_branch) => {
+            expression(condition)?;
+            expression(then_branch)?;
+            expression(else_branch)
+        }
+        Expr::CompoundAssign(ref target, _, ref value) => {
+            update_target(target)?;
+            expression(value)
+        }
+        Expr::Postfix(ref target, _) => update_target(target),
+        Expr::Call(ref callee, ref arguments, _) => {
+            expression(callee)?;
+            arguments.iter().try_for_each(expression)
+        }
+        Expr::Lambda(_, ref params, ref body) => function("<anonymous>", params, body),
+        Expr::List(_, ref items) => items.iter().try_for_each(expression),
+        Expr::SetIndex(ref list, _, ref index, ref value) => {
+            expression(list)?;
+            expression(index)?;
+            expression(value)
+        }
+    }
+}
+
+// The target of `+=` or `++` is a variable, a property or an element of a list
+fn update_target(target: &Expr) -> Result<(), String> {
+    match *target {
+        Expr::Var(_, _) | Expr::Get(_, _) | Expr::Index(_, _, _) => expression(target),
+        _ => Err("invalid assignment target".to_string()),
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use rlox::lox_value::LoxValue;
+use rlox::token::KEYWORDS;
+
+pub const COMMANDS: [&str; 6] = [".help", ".load", ".methods", ".reset", ".type", ".vars"];
+
+/// What can be completed at the prompt: keywords, meta commands, globals, and after a `.`, the
+/// properties and methods of the instance or class in a global. A snapshot of the session, taken
+/// before reading each line.
+#[derive(Default)]
+pub struct Completions {
+    globals: Vec<String>,
+    classes: Vec<String>,
+    // Properties and methods of each global that holds an instance, static methods of each one
+    // that holds a class
+    members: HashMap<String, Vec<String>>,
+}
+
+impl Completions {
+    pub fn new(globals: &[(String, LoxValue)]) -> Completions {
+        let mut completions = Completions::default();
+
+        for &(ref name, ref value) in globals {
+            completions.globals.push(name.clone());
+
+            match *value {
+                LoxValue::Instance(ref instance) => {
+                    completions
+                        .members
+                        .insert(name.clone(), instance.borrow().property_names());
+                }
+                LoxValue::Class(ref class) => {
+                    completions.classes.push(name.clone());
+                    completions
+                        .members
+                        .insert(name.clone(), class.static_method_names());
+                }
+                _ => {}
+            }
+        }
+
+        completions
+    }
+
+    /// Candidates for the word that ends at `pos` in `line`, sorted, with the position where
+    /// that word starts.
+    pub fn complete(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let before = &line[..pos];
+        let start = word_start(before);
+        let word = &before[start..];
+        // Commands may be indented, like the REPL accepts them
+        let indent = before.len() - before.trim_start().len();
+
+        let candidates: Vec<&str> = if before[indent..].starts_with(".methods ") {
+            self.classes.iter().map(String::as_str).collect()
+        } else if before[..start].ends_with('.') {
+            let object = &before[..start - 1];
+            let object_start = word_start(object);
+
+            // `.he` at the start of the line is a meta command, not a property
+            if start == indent + 1 {
+                return (indent, matching(COMMANDS.iter().cloned(), &before[indent..]));
+            }
+
+            match self.members.get(&object[object_start..]) {
+                Some(members) => members.iter().map(String::as_str).collect(),
+                None => Vec::new(),
+            }
+        } else {
+            KEYWORDS
+                .keys()
+                .cloned()
+                .chain(self.globals.iter().map(String::as_str))
+                .collect()
+        };
+
+        (start, matching(candidates.into_iter(), word))
+    }
+}
+
+fn is_identifier(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Byte position where the identifier at the end of the text starts
+fn word_start(text: &str) -> usize {
+    text.char_indices()
+        .rev()
+        .find(|&(_, c)| !is_identifier(c))
+        .map_or(0, |(index, c)| index + c.len_utf8())
+}
+
+fn matching<'a, I: Iterator<Item = &'a str>>(candidates: I, prefix: &str) -> Vec<String> {
+    let mut matching: Vec<String> = candidates
+        .filter(|candidate| candidate.starts_with(prefix))
+        .map(|candidate| candidate.to_string())
+        .collect();
+    matching.sort();
+    matching.dedup();
+    matching
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::rc::Rc;
+    use std::cell::RefCell;
+    use rlox::interpreter::Interpreter;
+    use rlox::parser;
+    use rlox::resolver;
+
+    fn completions(code: &str) -> Completions {
+        let mut output: Vec<u8> = Vec::new();
+        let writer: &mut io::Write = &mut output;
+        let mut interpreter = Interpreter::new(Rc::new(RefCell::new(writer)));
+
+        let mut ast = parser::parse(code.to_string()).unwrap();
+        resolver::resolve(&mut ast).unwrap();
+        assert!(interpreter.interpret(ast).is_none());
+
+        Completions::new(&interpreter.globals())
+    }
+
+    #[test]
+    fn completes_keywords_and_globals() {
+        let completions = completions("var counter = 1; fun count() {}");
+
+        assert_eq!(
+            completions.complete("print co", 8),
+            (6, vec!["count".to_string(), "counter".to_string()])
+        );
+        assert_eq!(completions.complete("wh", 2), (0, vec!["while".to_string()]));
+    }
+
+    #[test]
+    fn completes_members_of_instances_and_classes() {
+        let completions = completions(
+            "class A { area { return 1; } class make() {} }
+             class B < A { init() { this.size = 1; } scale() {} }
+             var b = B();",
+        );
+
+        assert_eq!(
+            completions.complete("print b.s", 9),
+            (8, vec!["scale".to_string(), "size".to_string()])
+        );
+        assert_eq!(
+            completions.complete("b.", 2),
+            (
+                2,
+                vec![
+                    "area".to_string(),
+                    "init".to_string(),
+                    "scale".to_string(),
+                    "size".to_string(),
+                ]
+            )
+        );
+        assert_eq!(completions.complete("B.m", 3), (2, vec!["make".to_string()]));
+        assert_eq!(completions.complete("nothing.", 8), (8, vec![]));
+    }
+
+    #[test]
+    fn completes_commands_and_classes_after_methods() {
+        let completions = completions("class Shape {} var s = Shape();");
+
+        assert_eq!(
+            completions.complete(".m", 2),
+            (0, vec![".methods".to_string()])
+        );
+        assert_eq!(
+            completions.complete(".methods S", 10),
+            (9, vec!["Shape".to_string()])
+        );
+        assert_eq!(
+            completions.complete("  .m", 4),
+            (2, vec![".methods".to_string()])
+        );
+        assert_eq!(
+            completions.complete("\t.methods S", 11),
+            (10, vec!["Shape".to_string()])
+        );
+    }
+
+    #[test]
+    fn completes_after_non_ascii_characters() {
+        let completions = completions("var counter = 1; var b = [];");
+
+        assert_eq!(
+            completions.complete("print “co", 11),
+            (9, vec!["counter".to_string()])
+        );
+        assert_eq!(completions.complete("€co", 5), (3, vec!["counter".to_string()]));
+        assert_eq!(completions.complete("€b.", 5), (5, vec![]));
+    }
+}
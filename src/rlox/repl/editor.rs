@@ -0,0 +1,90 @@
+use std::env;
+use std::path::PathBuf;
+
+use rustyline::{Context, Editor, Helper};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::Validator;
+
+use super::{Completions, Input, LineReader};
+
+const HISTORY_FILE: &str = ".rlox_history";
+
+/// Reads lines from the terminal with line editing and tab completion. The history is kept in
+/// `~/.rlox_history`, and saved after every entry.
+pub struct LineEditor {
+    editor: Editor<CompletionHelper, FileHistory>,
+    history: Option<PathBuf>,
+}
+
+impl LineEditor {
+    pub fn new() -> Result<LineEditor, ReadlineError> {
+        let mut editor = Editor::new()?;
+        editor.set_helper(Some(CompletionHelper::default()));
+        let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE));
+
+        if let Some(ref history) = history {
+            // There is no history the first time
+            let _ = editor.load_history(history);
+        }
+
+        Ok(LineEditor { editor, history })
+    }
+}
+
+impl LineReader for LineEditor {
+    fn read_line(&mut self, prompt: &str) -> Input {
+        match self.editor.readline(prompt) {
+            Ok(line) => Input::Line(line),
+            Err(ReadlineError::Interrupted) => Input::Interrupted,
+            Err(ReadlineError::Eof) => Input::End,
+            Err(err) => panic!("Error reading input line: {}", err),
+        }
+    }
+
+    fn set_completions(&mut self, completions: Completions) {
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.completions = completions;
+        }
+    }
+
+    fn add_history(&mut self, entry: &str) {
+        // Failing to remember an entry shouldn't get in the way of running it
+        let _ = self.editor.add_history_entry(entry);
+
+        if let Some(ref history) = self.history {
+            let _ = self.editor.save_history(history);
+        }
+    }
+}
+
+#[derive(Default)]
+struct CompletionHelper {
+    completions: Completions,
+}
+
+impl Completer for CompletionHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _context: &Context,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.completions.complete(line, pos))
+    }
+}
+
+impl Hinter for CompletionHelper {
+    type Hint = String;
+}
+
+impl Highlighter for CompletionHelper {}
+
+impl Validator for CompletionHelper {}
+
+impl Helper for CompletionHelper {}
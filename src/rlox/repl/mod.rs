@@ -0,0 +1,502 @@
+use std::io;
+use std::mem;
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Read;
+
+use rlox::capabilities::Capabilities;
+use rlox::errors::Error;
+use rlox::interpreter::Interpreter;
+use rlox::interpreter::errors::RuntimeError;
+use rlox::lox_value::{LoxFunc, LoxValue};
+use rlox::parser::{self, Expr, MethodKind, Parameter, Stmt};
+use rlox::resolver;
+use rlox::scanner::Scanner;
+use rlox::token::{Literal, TokenType};
+
+mod completion;
+
+pub use self::completion::Completions;
+
+cfg_if! {
+    if #[cfg(not(target_arch = "wasm32"))] {
+        mod editor;
+        pub use self::editor::LineEditor;
+    }
+}
+
+const PROMPT: &str = ">> ";
+const CONTINUATION_PROMPT: &str = ".. ";
+
+const HELP: &str = "Type Lox code to run it. An entry can span several lines, the prompt changes
+to `..` while it has open brackets or strings, and an empty line runs it as it
+is. The value of an expression is shown after running it, and its `;` can be
+left out.
+
+.help                Show this help
+.load <file>         Run a file in the current session
+.reset               Forget every variable, function and class defined so far
+.vars                List the variables, functions and classes defined so far
+.type <expression>   Show the type of the value of an expression
+.methods <class>     List the methods of a class and of its superclasses
+
+Tab completes keywords, commands, globals, and the properties and methods of
+instances and classes after a `.`.";
+
+/// What the user typed after a prompt.
+pub enum Input {
+    // Without its line ending
+    Line(String),
+    // Ctrl-C, which drops the entry being typed
+    Interrupted,
+    End,
+}
+
+/// Where the REPL reads what the user types.
+pub trait LineReader {
+    /// Shows the prompt and reads a line.
+    fn read_line(&mut self, prompt: &str) -> Input;
+
+    /// Updates what can be completed, before each line is read.
+    fn set_completions(&mut self, _completions: Completions) {}
+
+    /// Remembers an entry that was run, which can span several lines.
+    fn add_history(&mut self, _entry: &str) {}
+}
+
+/// Reads lines from a `BufRead`, writing the prompts to `writer`. Without line editing or
+/// history, for input that doesn't come from a terminal. The reader is shared with the
+/// interpreter, so programs can read the lines that follow.
+pub struct PlainLines<'a> {
+    reader: Rc<RefCell<&'a mut io::BufRead>>,
+    writer: Rc<RefCell<&'a mut io::Write>>,
+}
+
+impl<'a> PlainLines<'a> {
+    pub fn new(
+        reader: Rc<RefCell<&'a mut io::BufRead>>,
+        writer: Rc<RefCell<&'a mut io::Write>>,
+    ) -> PlainLines<'a> {
+        PlainLines { reader, writer }
+    }
+}
+
+impl<'a> LineReader for PlainLines<'a> {
+    fn read_line(&mut self, prompt: &str) -> Input {
+        let mut input = String::new();
+
+        {
+            let mut writer = self.writer.borrow_mut();
+            writer
+                .write_all(prompt.as_bytes())
+                .expect("Error writing to stdout/writer");
+            writer.flush().expect("Error flushing stdout/writer");
+        }
+
+        match self.reader.borrow_mut().read_line(&mut input) {
+            Ok(0) => Input::End,
+            Ok(_) => Input::Line(input.trim_end_matches(['\n', '\r']).to_string()),
+            Err(_) => panic!("Error reading input line"),
+        }
+    }
+}
+
+/// A REPL session, which keeps the variables, functions and classes defined by each entry
+/// for the ones that follow.
+pub struct Repl<'a> {
+    interpreter: Interpreter<'a>,
+    writer: Rc<RefCell<&'a mut io::Write>>,
+    error_writer: Rc<RefCell<&'a mut io::Write>>,
+    reader: Option<Rc<RefCell<&'a mut io::BufRead>>>,
+}
+
+impl<'a> Repl<'a> {
+    /// Starts a session that writes the output of programs to `writer` and errors to
+    /// `error_writer`. Programs read from `reader`, or from stdin without one.
+    pub fn new(
+        writer: Rc<RefCell<&'a mut io::Write>>,
+        error_writer: Rc<RefCell<&'a mut io::Write>>,
+        reader: Option<Rc<RefCell<&'a mut io::BufRead>>>,
+    ) -> Repl<'a> {
+        let interpreter = Repl::new_interpreter(&writer, &error_writer, &reader);
+
+        Repl {
+            interpreter,
+            writer,
+            error_writer,
+            reader,
+        }
+    }
+
+    fn new_interpreter(
+        writer: &Rc<RefCell<&'a mut io::Write>>,
+        error_writer: &Rc<RefCell<&'a mut io::Write>>,
+        reader: &Option<Rc<RefCell<&'a mut io::BufRead>>>,
+    ) -> Interpreter<'a> {
+        let mut interpreter = Interpreter::new(writer.clone());
+        interpreter.set_error_writer(error_writer.clone());
+
+        if let Some(ref reader) = *reader {
+            interpreter.set_reader(reader.clone());
+        }
+
+        interpreter.set_capabilities(Capabilities::all());
+        interpreter
+    }
+
+    /// Runs entries read from `lines` until there are no more, returning 0, or until one of
+    /// them calls `exit()`, returning its exit code.
+    pub fn run(&mut self, lines: &mut LineReader) -> i32 {
+        self.write("Welcome to the rlox prompt\n^D to exit, .help for help\n\n");
+
+        let mut entry = String::new();
+
+        loop {
+            let prompt = if entry.is_empty() {
+                PROMPT
+            } else {
+                CONTINUATION_PROMPT
+            };
+
+            lines.set_completions(Completions::new(&self.interpreter.globals()));
+
+            let line = match lines.read_line(prompt) {
+                Input::Line(line) => line,
+                Input::Interrupted => {
+                    entry.clear();
+                    continue;
+                }
+                Input::End => return 0,
+            };
+
+            if entry.is_empty() && line.trim_start().starts_with('.') {
+                lines.add_history(line.trim());
+
+                if let Some(exit_code) = self.command(line.trim()) {
+                    return exit_code;
+                }
+
+                continue;
+            }
+
+            // An empty line gives up on completing the entry, so a stray bracket can't keep
+            // the REPL waiting forever
+            let give_up = !entry.is_empty() && line.trim().is_empty();
+
+            entry.push_str(&line);
+            entry.push('\n');
+
+            if !give_up && is_incomplete(&entry) {
+                continue;
+            }
+
+            let entry = mem::take(&mut entry);
+
+            if entry.trim().is_empty() {
+                continue;
+            }
+
+            lines.add_history(entry.trim_end());
+
+            if let Some(exit_code) = self.evaluate(entry, true) {
+                return exit_code;
+            }
+        }
+    }
+
+    // Runs a meta command, returning the exit code if it called `exit()`
+    fn command(&mut self, command: &str) -> Option<i32> {
+        let (name, argument) = match command.find(char::is_whitespace) {
+            Some(index) => (&command[..index], command[index..].trim()),
+            None => (command, ""),
+        };
+
+        match (name, argument) {
+            (".help", "") => self.write(&format!("{}\n", HELP)),
+            (".reset", "") => {
+                self.interpreter =
+                    Repl::new_interpreter(&self.writer, &self.error_writer, &self.reader);
+                self.write("Session reset\n");
+            }
+            (".load", "") => self.write_error("Usage: .load <file>"),
+            (".load", path) => match read_file(path) {
+                // A file that exits only stops the file, the session goes on
+                Ok(code) => {
+                    self.evaluate(code, false);
+                }
+                Err(err) => self.write_error(&format!("Could not read {}: {}", path, err)),
+            },
+            (".vars", "") => self.vars(),
+            (".type", "") => self.write_error("Usage: .type <expression>"),
+            (".type", code) => return self.type_of(code),
+            (".methods", "") => self.write_error("Usage: .methods <class>"),
+            (".methods", name) => self.methods(name),
+            _ => self.write_error(&format!(
+                "Unknown command `{}`, type .help for a list of commands",
+                command
+            )),
+        }
+
+        None
+    }
+
+    // Native functions are left out, they are there in every session
+    fn vars(&mut self) {
+        let globals: Vec<(String, LoxValue)> = self
+            .interpreter
+            .globals()
+            .into_iter()
+            .filter(|&(_, ref value)| !is_native(value))
+            .collect();
+
+        if globals.is_empty() {
+            self.write("No variables defined\n");
+        }
+
+        for (name, value) in globals {
+            let shown = self.show(&value);
+            self.write(&format!("{} = {}\n", name, shown));
+        }
+    }
+
+    fn type_of(&mut self, code: &str) -> Option<i32> {
+        let ast = match parse_entry(code.to_string()) {
+            Ok(ast) => ast,
+            Err(errors) => {
+                self.report(&errors);
+                return None;
+            }
+        };
+
+        if ast.len() != 1 || !matches!(ast[0], Stmt::Expr(_)) {
+            self.write_error("Usage: .type <expression>");
+            return None;
+        }
+
+        match self.execute(ast) {
+            Ok(value) => {
+                let value = value.unwrap_or(LoxValue::Nil);
+                self.write(&format!("{}\n", type_name(&value)));
+            }
+            Err(errors) => return self.fail(&errors),
+        }
+
+        None
+    }
+
+    fn methods(&mut self, name: &str) {
+        let global = self
+            .interpreter
+            .globals()
+            .into_iter()
+            .find(|&(ref global, _)| global == name);
+
+        let mut class = match global {
+            Some((_, LoxValue::Class(class))) => class,
+            Some(_) => return self.write_error(&format!("`{}` is not a class", name)),
+            None => return self.write_error(&format!("Undefined variable `{}`", name)),
+        };
+
+        loop {
+            let mut listing = format!("{}\n", class.get_name());
+
+            for (kind, name, method) in class.members() {
+                listing.push_str(&format!("  {}\n", signature(kind, name, method)));
+            }
+
+            self.write(&listing);
+
+            class = match class.superclass() {
+                Some(superclass) => superclass.clone(),
+                None => break,
+            };
+        }
+    }
+
+    // Runs an entry, returning the exit code if it called `exit()`. With `echo`, the value of
+    // an expression statement at the end is shown.
+    fn evaluate(&mut self, code: String, echo: bool) -> Option<i32> {
+        let ast = match parse_entry(code) {
+            Ok(ast) => ast,
+            Err(errors) => {
+                self.report(&errors);
+                return None;
+            }
+        };
+
+        let echo = echo && shows_value(ast.last());
+
+        match self.execute(ast) {
+            Ok(Some(LoxValue::Nil)) | Ok(None) => {}
+            Ok(Some(value)) => {
+                if echo {
+                    let shown = self.show(&value);
+                    self.write(&format!("{}\n", shown));
+                }
+            }
+            Err(errors) => return self.fail(&errors),
+        }
+
+        None
+    }
+
+    // Resolves and runs the statements of an entry, and then the event loop, returning the value
+    // of the last one if it is an expression statement
+    fn execute(&mut self, mut ast: Vec<Stmt>) -> Result<Option<LoxValue>, Vec<Error>> {
+        for warning in resolver::resolve(&mut ast)? {
+            self.interpreter.warn(&warning);
+        }
+
+        self.interpreter
+            .interpret_with_value(ast)
+            .and_then(|value| match self.interpreter.run_event_loop() {
+                Some(err) => Err(err),
+                None => Ok(value),
+            })
+            .map_err(|err| vec![Error::Runtime(err)])
+    }
+
+    // Reports the errors of an entry, unless it called `exit()`, returning its exit code then
+    fn fail(&mut self, errors: &[Error]) -> Option<i32> {
+        if let Some(&Error::Runtime(RuntimeError::Exit(code))) = errors.first() {
+            return Some(code);
+        }
+
+        self.report(errors);
+        None
+    }
+
+    // Strings are quoted, so they can be told apart from other values. Instances with a
+    // `toString()` that can't be used are shown like the ones without it.
+    fn show(&mut self, value: &LoxValue) -> String {
+        if let LoxValue::String(ref string) = *value {
+            return format!("\"{}\"", string);
+        }
+
+        let shown = self.interpreter.stringify_or(value, &|class_name| {
+            Ok(format!("instance of <{}>", class_name))
+        });
+
+        match shown {
+            Ok(string) => string,
+            Err(err) => err.to_string(),
+        }
+    }
+
+    fn report(&mut self, errors: &[Error]) {
+        for err in errors {
+            self.write_error(&err.to_string());
+        }
+    }
+
+    fn write(&mut self, text: &str) {
+        self.interpreter.write(text);
+    }
+
+    fn write_error(&mut self, line: &str) {
+        self.interpreter.write_error(line);
+    }
+}
+
+// Whether more lines are needed to complete the entry, because it has an unclosed bracket or
+// string
+fn is_incomplete(code: &str) -> bool {
+    let (tokens, errors) = Scanner::new(code.to_string()).scan_tokens();
+
+    if errors.iter().any(|err| err.is_unterminated()) {
+        return true;
+    }
+
+    let depth = tokens.iter().fold(0, |depth, token| match token.token_type {
+        TokenType::LeftParen | TokenType::LeftBrace | TokenType::LeftBracket => depth + 1,
+        TokenType::RightParen | TokenType::RightBrace | TokenType::RightBracket => depth - 1,
+        _ => depth,
+    });
+
+    depth > 0
+}
+
+// Parses an entry, which can leave out the `;` at the end, so `1 + 2` is shown as `3`
+fn parse_entry(code: String) -> Result<Vec<Stmt>, Vec<Error>> {
+    parser::parse(code.clone()).or_else(|errors| {
+        parser::parse(format!("{};", code.trim_end())).map_err(|_| errors)
+    })
+}
+
+// Only expression statements show their value, except assignments. `nil` isn't shown either,
+// so calling a function that doesn't return anything shows nothing.
+fn shows_value(stmt: Option<&Stmt>) -> bool {
+    match stmt {
+        Some(&Stmt::Expr(ref expr)) => !matches!(
+            *expr,
+            Expr::Assign(_, _, _)
+                | Expr::Set(_, _, _)
+                | Expr::SetIndex(_, _, _, _)
+                | Expr::CompoundAssign(_, _, _)
+        ),
+        _ => false,
+    }
+}
+
+fn is_native(value: &LoxValue) -> bool {
+    match *value {
+        LoxValue::Func(ref func) => func.as_any().downcast_ref::<LoxFunc>().is_none(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &LoxValue) -> String {
+    match *value {
+        LoxValue::Number(_) => "number".to_string(),
+        LoxValue::String(_) => "string".to_string(),
+        LoxValue::Bool(_) => "bool".to_string(),
+        LoxValue::Nil => "nil".to_string(),
+        LoxValue::Func(_) if is_native(value) => "native function".to_string(),
+        LoxValue::Func(_) => "function".to_string(),
+        LoxValue::Class(_) => "class".to_string(),
+        LoxValue::Instance(ref instance) => {
+            format!("instance of {}", instance.borrow().get_class_name())
+        }
+        LoxValue::List(_) => "list".to_string(),
+        LoxValue::Generator(_) => "generator".to_string(),
+        LoxValue::Promise(_) => "promise".to_string(),
+    }
+}
+
+// A member as it is declared, like `area` for a getter or `static origin()`
+fn signature(kind: MethodKind, name: &str, method: &LoxValue) -> String {
+    let parameters = match *method {
+        LoxValue::Func(ref func) => match func.as_any().downcast_ref::<LoxFunc>() {
+            Some(func) => func.parameters().iter().map(parameter).collect(),
+            None => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+
+    let parameters = parameters.join(", ");
+
+    match kind {
+        MethodKind::Method => format!("{}({})", name, parameters),
+        MethodKind::Getter => name.to_string(),
+        MethodKind::Setter => format!("set {}({})", name, parameters),
+        MethodKind::Static => format!("static {}({})", name, parameters),
+    }
+}
+
+fn parameter(parameter: &Parameter) -> String {
+    match *parameter {
+        Parameter::Required(ref name) => name.lexeme.clone(),
+        Parameter::Optional(ref name, Expr::Literal(Literal::String(ref default))) => {
+            format!("{} = {:?}", name.lexeme, default)
+        }
+        Parameter::Optional(ref name, ref default) => format!("{} = {}", name.lexeme, default),
+        Parameter::Rest(ref name) => format!("...{}", name.lexeme),
+    }
+}
+
+fn read_file(path: &str) -> io::Result<String> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    Ok(contents)
+}
@@ -0,0 +1,108 @@
+use std;
+use rlox::token::Token;
+
+#[derive(Debug)]
+pub enum ResolverError {
+    ReturnAtTopLevel(Token),
+    ReturnOnInitializer(Token),
+    ThisOutsideMethod(Token),
+    ThisInStaticMethod(Token),
+    SuperOutsideMethod(Token),
+    SuperInStaticMethod(Token),
+    SuperWithoutSuperclass(Token),
+    // `yield` or `await` somewhere other than a statement, a declaration or an assignment
+    SuspensionOutsideStatement(Token),
+    SuspensionAtTopLevel(Token),
+    SuspensionOnInitializer(Token),
+    MixedSuspension(Token),
+    // A local declared twice in the same scope
+    VariableAlreadyDeclared(Token),
+}
+
+impl ResolverError {
+    pub fn token(&self) -> &Token {
+        match *self {
+            ResolverError::ReturnAtTopLevel(ref token)
+            | ResolverError::ReturnOnInitializer(ref token)
+            | ResolverError::ThisOutsideMethod(ref token)
+            | ResolverError::ThisInStaticMethod(ref token)
+            | ResolverError::SuperOutsideMethod(ref token)
+            | ResolverError::SuperInStaticMethod(ref token)
+            | ResolverError::SuperWithoutSuperclass(ref token)
+            | ResolverError::SuspensionOutsideStatement(ref token)
+            | ResolverError::SuspensionAtTopLevel(ref token)
+            | ResolverError::SuspensionOnInitializer(ref token)
+            | ResolverError::MixedSuspension(ref token)
+            | ResolverError::VariableAlreadyDeclared(ref token) => token,
+        }
+    }
+
+    /// The error without the line it happened on.
+    pub fn message(&self) -> String {
+        match *self {
+            ResolverError::ReturnAtTopLevel(_) => {
+                "Cannot use `return` at the top level.".to_string()
+            }
+            ResolverError::ReturnOnInitializer(_) => {
+                "Cannot use `return` on an initializer.".to_string()
+            }
+            ResolverError::ThisOutsideMethod(_) => {
+                "Cannot use `this` outside of a method.".to_string()
+            }
+            ResolverError::ThisInStaticMethod(_) => {
+                "Cannot use `this` in a static method.".to_string()
+            }
+            ResolverError::SuperOutsideMethod(_) => {
+                "Cannot use `super` outside of a method.".to_string()
+            }
+            ResolverError::SuperInStaticMethod(_) => {
+                "Cannot use `super` in a static method.".to_string()
+            }
+            ResolverError::SuperWithoutSuperclass(_) => {
+                "Cannot use `super` without a superclass.".to_string()
+            }
+            ResolverError::SuspensionOutsideStatement(ref keyword) => format!(
+                "`{}` can only be used as a statement, or as the value of a variable declaration or assignment.",
+                keyword.lexeme
+            ),
+            ResolverError::SuspensionAtTopLevel(ref keyword) => {
+                format!("Cannot use `{}` at the top level.", keyword.lexeme)
+            }
+            ResolverError::SuspensionOnInitializer(ref keyword) => {
+                format!("Cannot use `{}` on an initializer.", keyword.lexeme)
+            }
+            ResolverError::MixedSuspension(_) => {
+                "Cannot use both `yield` and `await` in the same function.".to_string()
+            }
+            ResolverError::VariableAlreadyDeclared(ref name) => format!(
+                "A variable named `{}` is already declared in this scope.",
+                name.lexeme
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for ResolverError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[line {}] UnexpectedTokenError: {}", self.token().line, self.message())
+    }
+}
+
+impl std::error::Error for ResolverError {
+    fn description(&self) -> &str {
+        match *self {
+            ResolverError::ReturnAtTopLevel(_) => "ReturnAtTopLevel",
+            ResolverError::ReturnOnInitializer(_) => "ReturnOnInitializer",
+            ResolverError::ThisOutsideMethod(_) => "ThisOutsideMethod",
+            ResolverError::ThisInStaticMethod(_) => "ThisInStaticMethod",
+            ResolverError::SuperOutsideMethod(_) => "SuperOutsideMethod",
+            ResolverError::SuperInStaticMethod(_) => "SuperInStaticMethod",
+            ResolverError::SuperWithoutSuperclass(_) => "SuperWithoutSuperclass",
+            ResolverError::SuspensionOutsideStatement(_) => "SuspensionOutsideStatement",
+            ResolverError::SuspensionAtTopLevel(_) => "SuspensionAtTopLevel",
+            ResolverError::SuspensionOnInitializer(_) => "SuspensionOnInitializer",
+            ResolverError::MixedSuspension(_) => "MixedSuspension",
+            ResolverError::VariableAlreadyDeclared(_) => "VariableAlreadyDeclared",
+        }
+    }
+}
@@ -1,6 +1,11 @@
-use rlox::parser::Stmt;
+pub mod errors;
+
+use rlox::parser::{MethodKind, Parameter, Pattern, Stmt};
 use rlox::parser::Expr;
-use rlox::token::Token;
+use rlox::token::{Token, TokenType};
+use rlox::errors::Error;
+use rlox::environment::{Binding, Symbol};
+use self::errors::ResolverError;
 use std::collections::hash_map::HashMap;
 
 #[derive(Clone, PartialEq)]
@@ -16,10 +21,25 @@ enum FunctionType {
     Initializer,
 }
 
+#[derive(Default)]
+struct Scope {
+    // Slot of each variable, and whether it's defined yet, which it isn't in its own initializer
+    variables: HashMap<String, (usize, bool)>,
+    // Every declaration gets a new slot, even one that shadows a variable of the same scope,
+    // since the interpreter adds a value to the environment for each of them
+    slots: usize,
+}
+
 pub struct Resolver {
-    scopes: Vec<HashMap<String, bool>>,
+    scopes: Vec<Scope>,
     class_type: Option<ClassType>,
     function_type: Option<FunctionType>,
+    in_static_method: bool,
+    // Whether the current function uses `yield` or `await`, which can't be mixed
+    suspension: Option<TokenType>,
+    // Problems that don't prevent the program from running, and ones that do
+    warnings: Vec<String>,
+    errors: Vec<ResolverError>,
 }
 
 impl Resolver {
@@ -28,6 +48,10 @@ impl Resolver {
             scopes: Vec::new(),
             class_type: None,
             function_type: None,
+            in_static_method: false,
+            suspension: None,
+            warnings: Vec::new(),
+            errors: Vec::new(),
         }
     }
 
@@ -45,18 +69,35 @@ impl Resolver {
                 self.end_scope();
             }
             Stmt::Var(ref token, ref mut expr) => {
-                self.declare(token.lexeme.clone());
-                self.resolve_expression(expr);
+                self.declare(token);
+
+                match *expr {
+                    Expr::Yield(ref keyword, ref mut value) => self.resolve_yield(keyword, value),
+                    ref mut expr => self.resolve_expression(expr),
+                }
 
                 // TODO: Can I use a reference to the string instead of having to own it?
                 self.define(token.lexeme.clone());
             }
-            Stmt::Func(ref token, ref params, ref mut body) => {
-                self.declare(token.lexeme.clone());
+            Stmt::Func(ref token, ref mut params, ref mut body) => {
+                self.declare(token);
                 self.define(token.lexeme.clone());
 
                 self.resolve_function(params, body, Some(FunctionType::Function));
             }
+            // `yield` and `await` can only be used at the top of an expression statement, or as
+            // the value of a declaration or assignment, where the function can be suspended
+            Stmt::Expr(Expr::Yield(ref keyword, ref mut value)) => {
+                self.resolve_yield(keyword, value)
+            }
+            Stmt::Expr(Expr::Assign(ref token, ref mut value, ref mut binding)) => {
+                match **value {
+                    Expr::Yield(ref keyword, ref mut value) => self.resolve_yield(keyword, value),
+                    ref mut value => self.resolve_expression(value),
+                }
+
+                *binding = Some(self.binding(&token.lexeme));
+            }
             Stmt::Expr(ref mut expr) => self.resolve_expression(expr),
             Stmt::If(ref mut condition, ref mut then_branch, ref mut else_branch) => {
                 self.resolve_expression(condition);
@@ -66,16 +107,14 @@ impl Resolver {
                     self.resolve_statement(else_branch);
                 }
             }
-            Stmt::Print(ref mut expr) => self.resolve_expression(expr),
-            Stmt::Return(_, ref mut expr) => {
-                {
-                    let function_type = self.function_type
-                        .as_ref()
-                        .expect("UnexpectedTokenError: Cannot use `return` at the top level.");
-
-                    if *function_type == FunctionType::Initializer {
-                        panic!("UnexpectedTokenError: Cannot use `return` on an initializer.")
+            Stmt::Print(_, ref mut expr) => self.resolve_expression(expr),
+            Stmt::Return(ref keyword, ref mut expr) => {
+                match self.function_type {
+                    None => self.error(ResolverError::ReturnAtTopLevel(keyword.clone())),
+                    Some(FunctionType::Initializer) => {
+                        self.error(ResolverError::ReturnOnInitializer(keyword.clone()))
                     }
+                    _ => {}
                 }
 
                 self.resolve_expression(expr)
@@ -84,10 +123,21 @@ impl Resolver {
                 self.resolve_expression(condition);
                 self.resolve_statement(body);
             }
+            Stmt::ForIn(ref name, ref mut iterable, ref mut body) => {
+                self.resolve_expression(iterable);
+
+                self.begin_scope();
+                self.declare(name);
+                self.define(name.lexeme.clone());
+                self.resolve_statement(body);
+                self.end_scope();
+            }
             Stmt::Class(ref token, ref mut superclass, ref mut methods) => {
-                self.declare(token.lexeme.clone());
+                self.declare(token);
                 let enclosing_class_type = self.class_type.clone();
+                let enclosing_static_method = self.in_static_method;
                 self.class_type = Some(ClassType::Class);
+                self.in_static_method = false;
 
                 if let &mut Some(ref mut superclass) = superclass {
                     self.class_type = Some(ClassType::SubClass);
@@ -96,24 +146,36 @@ impl Resolver {
                     self.define("super".to_string());
                 }
 
+                // Static methods aren't bound to an instance, so they are resolved outside of
+                // the scope that holds `this`.
+                self.in_static_method = true;
+
+                for &mut (kind, ref mut method) in methods.iter_mut() {
+                    if let (MethodKind::Static, &mut Stmt::Func(_, ref mut params, ref mut body)) =
+                        (kind, method)
+                    {
+                        self.resolve_function(params, body, Some(FunctionType::Method));
+                    }
+                }
+
+                self.in_static_method = false;
                 self.begin_scope();
                 self.define("this".to_string());
 
-                for method in methods {
-                    match method {
-                        &mut Stmt::Func(ref token, ref params, ref mut body) => {
-                            self.declare(token.lexeme.clone());
-                            self.define(token.lexeme.clone());
+                for &mut (kind, ref mut method) in methods.iter_mut() {
+                    if kind == MethodKind::Static {
+                        continue;
+                    }
 
-                            let function_type = if token.lexeme == "init" {
-                                FunctionType::Initializer
-                            } else {
-                                FunctionType::Method
-                            };
+                    if let &mut Stmt::Func(ref token, ref mut params, ref mut body) = method {
+                        let function_type = if kind == MethodKind::Method && token.lexeme == "init"
+                        {
+                            FunctionType::Initializer
+                        } else {
+                            FunctionType::Method
+                        };
 
-                            self.resolve_function(params, body, Some(function_type));
-                        }
-                        _ => {}
+                        self.resolve_function(params, body, Some(function_type));
                     }
                 }
 
@@ -124,27 +186,90 @@ impl Resolver {
                 }
 
                 self.class_type = enclosing_class_type;
+                self.in_static_method = enclosing_static_method;
                 self.define(token.lexeme.clone());
             }
+            Stmt::Match(_, ref mut subject, ref mut arms) => {
+                self.resolve_expression(subject);
+                let mut catch_all: Option<String> = None;
+
+                for &mut (ref mut pattern, ref mut guard, ref mut body) in arms.iter_mut() {
+                    let line = match *pattern {
+                        Pattern::Wildcard(ref token)
+                        | Pattern::Literal(ref token, _)
+                        | Pattern::Binding(ref token)
+                        | Pattern::Class(Expr::Var(ref token, _), _) => Some(token.line),
+                        Pattern::Class(_, _) => None,
+                    };
+
+                    if let (Some(ref catch_all), Some(line)) = (catch_all.as_ref(), line) {
+                        self.warnings.push(format!(
+                            "[line {}] Warning: Unreachable match arm after `{}`",
+                            line, catch_all
+                        ));
+                    }
+
+                    if let Pattern::Class(ref mut class, _) = *pattern {
+                        self.resolve_expression(class);
+                    }
+
+                    // Each arm gets its own scope, so its binding is only visible to its
+                    // guard and body
+                    self.begin_scope();
+
+                    if let Some(name) = pattern.binding() {
+                        self.declare(name);
+                        self.define(name.lexeme.clone());
+                    }
+
+                    if let Some(ref mut guard) = *guard {
+                        self.resolve_expression(guard);
+                    }
+
+                    self.resolve_statement(body);
+                    self.end_scope();
+
+                    if catch_all.is_none() && guard.is_none() && pattern.is_irrefutable() {
+                        catch_all = match *pattern {
+                            Pattern::Wildcard(ref token) | Pattern::Binding(ref token) => {
+                                Some(token.lexeme.clone())
+                            }
+                            _ => None,
+                        };
+                    }
+                }
+            }
         }
     }
 
     fn resolve_expression(&mut self, expr: &mut Expr) {
         match *expr {
-            Expr::Var(ref token, ref mut distance) => {
+            Expr::Var(ref token, ref mut binding) => {
                 if let Some(scope) = self.scopes.last() {
-                    if let Some(is_var_available) = scope.get(&token.lexeme) {
+                    if let Some(&(_, is_var_available)) = scope.variables.get(&token.lexeme) {
                         if !is_var_available {
                             // TODO: Error
                         }
                     }
                 }
 
-                *distance = self.resolve_local(token.lexeme.clone());
+                *binding = Some(self.binding(&token.lexeme));
             }
-            Expr::Assign(ref token, ref mut expr, ref mut distance) => {
+            Expr::Assign(ref token, ref mut expr, ref mut binding) => {
                 self.resolve_expression(expr);
-                *distance = self.resolve_local(token.lexeme.clone());
+                *binding = Some(self.binding(&token.lexeme));
+            }
+            Expr::Conditional(ref mut condition, ref mut then_branch, ref mut else_branch) => {
+                self.resolve_expression(condition);
+                self.resolve_expression(then_branch);
+                self.resolve_expression(else_branch);
+            }
+            Expr::CompoundAssign(ref mut target, _, ref mut value) => {
+                self.resolve_expression(target);
+                self.resolve_expression(value);
+            }
+            Expr::Postfix(ref mut target, _) => {
+                self.resolve_expression(target);
             }
             Expr::Binary(ref mut left, _, ref mut right) => {
                 self.resolve_expression(left);
@@ -168,6 +293,32 @@ impl Resolver {
             Expr::Unary(_, ref mut expr) => {
                 self.resolve_expression(expr);
             }
+            Expr::Stringify(_, ref mut expr) => {
+                self.resolve_expression(expr);
+            }
+            Expr::Lambda(_, ref mut params, ref mut body) => {
+                self.resolve_function(params, body, Some(FunctionType::Function));
+            }
+            Expr::List(_, ref mut items) => {
+                for item in items {
+                    self.resolve_expression(item);
+                }
+            }
+            Expr::Index(ref mut target, _, ref mut index) => {
+                self.resolve_expression(target);
+                self.resolve_expression(index);
+            }
+            Expr::SetIndex(ref mut target, _, ref mut index, ref mut value) => {
+                self.resolve_expression(target);
+                self.resolve_expression(index);
+                self.resolve_expression(value);
+            }
+            Expr::Spread(_, ref mut expr) => {
+                self.resolve_expression(expr);
+            }
+            Expr::Yield(ref keyword, _) => {
+                self.error(ResolverError::SuspensionOutsideStatement(keyword.clone()))
+            }
             Expr::Get(ref mut target, _) => {
                 self.resolve_expression(target);
             }
@@ -177,38 +328,43 @@ impl Resolver {
             }
             Expr::This(ref token, ref mut distance) => {
                 if self.class_type.is_none() {
-                    panic!("UnexpectedTokenError: Cannot use `this` outside of a method.");
+                    self.error(ResolverError::ThisOutsideMethod(token.clone()));
+                } else if self.in_static_method {
+                    self.error(ResolverError::ThisInStaticMethod(token.clone()));
                 }
 
                 if let Some(scope) = self.scopes.last() {
-                    if let Some(is_var_available) = scope.get(&token.lexeme) {
+                    if let Some(&(_, is_var_available)) = scope.variables.get(&token.lexeme) {
                         if !is_var_available {
                             // TODO: Error
                         }
                     }
                 }
 
-                *distance = self.resolve_local(token.lexeme.clone());
+                *distance = self.resolve_local(&token.lexeme).map(|(depth, _)| depth);
             }
             Expr::Super(ref token, _, ref mut distance) => {
-                let class_type = self.class_type
-                    .as_ref()
-                    .expect("UnexpectedTokenError: Cannot use `super` outside of a method.");
-
-                match class_type {
-                    &ClassType::Class => {
-                        panic!("UnexpectedTokenError: Cannot use `super` without a superclass.")
+                match self.class_type {
+                    None => self.error(ResolverError::SuperOutsideMethod(token.clone())),
+                    Some(_) if self.in_static_method => {
+                        self.error(ResolverError::SuperInStaticMethod(token.clone()))
                     }
-                    _ => {
+                    Some(ClassType::Class) => {
+                        self.error(ResolverError::SuperWithoutSuperclass(token.clone()))
+                    }
+                    Some(ClassType::SubClass) => {
                         if let Some(scope) = self.scopes.last() {
-                            if let Some(is_var_available) = scope.get(&token.lexeme) {
+                            if let Some(&(_, is_var_available)) =
+                                scope.variables.get(&token.lexeme)
+                            {
                                 if !is_var_available {
                                     // TODO: Error
                                 }
                             }
                         }
 
-                        let resolved_distance = self.resolve_local(token.lexeme.clone());
+                        let resolved_distance =
+                            self.resolve_local(&token.lexeme).map(|(depth, _)| depth);
                         *distance = resolved_distance;
                     }
                 }
@@ -216,29 +372,63 @@ impl Resolver {
         }
     }
 
-    fn resolve_local(&self, lexeme: String) -> Option<usize> {
-        for (i, scope) in self.scopes.iter().rev().enumerate() {
-            if scope.contains_key(&lexeme) {
-                return Some(i);
+    fn resolve_yield(&mut self, keyword: &Token, value: &mut Expr) {
+        match self.function_type {
+            None => self.error(ResolverError::SuspensionAtTopLevel(keyword.clone())),
+            Some(FunctionType::Initializer) => {
+                self.error(ResolverError::SuspensionOnInitializer(keyword.clone()))
+            }
+            _ => {}
+        }
+
+        match self.suspension {
+            Some(token_type) if token_type != keyword.token_type => {
+                self.error(ResolverError::MixedSuspension(keyword.clone()))
+            }
+            _ => self.suspension = Some(keyword.token_type),
+        }
+
+        self.resolve_expression(value);
+    }
+
+    // How many scopes up from the current one the variable is, and its slot in there
+    fn resolve_local(&self, lexeme: &str) -> Option<(usize, usize)> {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if let Some(&(slot, _)) = scope.variables.get(lexeme) {
+                return Some((depth, slot));
             }
         }
 
         None
     }
 
+    // Variables that aren't in any scope are globals, which may be defined later on
+    fn binding(&self, lexeme: &str) -> Binding {
+        match self.resolve_local(lexeme) {
+            Some((depth, slot)) => Binding::Local(depth, slot),
+            None => Binding::Global(Symbol::intern(lexeme)),
+        }
+    }
+
     fn resolve_function(
         &mut self,
-        params: &Vec<Token>,
+        params: &mut [Parameter],
         body: &mut Stmt,
         function_type: Option<FunctionType>,
     ) {
         let enclosing_function = self.function_type.clone();
+        let enclosing_suspension = self.suspension.take();
         self.function_type = function_type;
         self.begin_scope();
 
-        for param in params {
-            self.declare(param.lexeme.clone());
-            self.define(param.lexeme.clone());
+        for param in params.iter_mut() {
+            // Defaults are evaluated when calling, and can refer to the parameters before them
+            if let Parameter::Optional(_, ref mut default) = *param {
+                self.resolve_expression(default);
+            }
+
+            self.declare(param.name());
+            self.define(param.name().lexeme.clone());
         }
 
         match body {
@@ -250,25 +440,61 @@ impl Resolver {
 
         self.end_scope();
         self.function_type = enclosing_function;
+        self.suspension = enclosing_suspension;
+    }
+
+    fn error(&mut self, error: ResolverError) {
+        self.errors.push(error);
     }
 
     fn begin_scope(&mut self) {
-        self.scopes.push(HashMap::new());
+        self.scopes.push(Scope::default());
     }
 
     fn end_scope(&mut self) {
         self.scopes.pop();
     }
 
-    fn declare(&mut self, name: String) {
-        if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(name, false);
+    // Each local gets a slot of its own, so a name can't be declared twice in the same scope,
+    // like in jlox. Globals can, since they are looked up by name.
+    fn declare(&mut self, name: &Token) {
+        let redeclared = match self.scopes.last_mut() {
+            Some(scope) if scope.variables.contains_key(&name.lexeme) => true,
+            Some(scope) => {
+                scope.variables.insert(name.lexeme.clone(), (scope.slots, false));
+                scope.slots += 1;
+                false
+            }
+            None => false,
+        };
+
+        if redeclared {
+            self.error(ResolverError::VariableAlreadyDeclared(name.clone()));
         }
     }
 
+    // Marks a declared variable as defined, or declares and defines it at once
     fn define(&mut self, name: String) {
         if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(name, true);
+            match scope.variables.get_mut(&name) {
+                Some(&mut (_, ref mut defined)) if !*defined => *defined = true,
+                _ => {
+                    scope.variables.insert(name, (scope.slots, true));
+                    scope.slots += 1;
+                }
+            }
         }
     }
 }
+
+/// Resolves the variables in `ast`, returning the warnings found.
+pub fn resolve(ast: &mut Vec<Stmt>) -> Result<Vec<String>, Vec<Error>> {
+    let mut resolver = Resolver::new();
+    resolver.resolve_ast(ast);
+
+    if !resolver.errors.is_empty() {
+        return Err(resolver.errors.into_iter().map(Error::Resolver).collect());
+    }
+
+    Ok(resolver.warnings)
+}
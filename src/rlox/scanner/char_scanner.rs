@@ -1,3 +1,4 @@
+use std;
 use rlox::token::{Token, TokenType, Literal, KEYWORDS};
 use rlox::scanner::errors::ScannerError;
 
@@ -6,36 +7,63 @@ pub struct CharScanner {
     start: usize,
     current: usize,
     line: usize,
+    // Index where the current line starts, and line and column where the current token starts
+    line_start: usize,
+    start_line: usize,
+    start_column: usize,
+    // Brace depth of each string interpolation currently being scanned, so we know which `}`
+    // closes the interpolated expression and goes back to the rest of the string.
+    interpolations: Vec<usize>,
+    // Errors that don't stop a token from being scanned, like an invalid escape sequence in a
+    // string, which still has to start its interpolations
+    errors: Vec<ScannerError>,
+    keep_comments: bool,
 }
 
 impl CharScanner {
-    pub fn new(source: Vec<char>) -> CharScanner {
+    pub fn new(source: Vec<char>, keep_comments: bool) -> CharScanner {
         CharScanner {
             source,
             start: 0,
             current: 0,
             line: 1,
+            line_start: 0,
+            start_line: 1,
+            start_column: 1,
+            interpolations: Vec::new(),
+            errors: Vec::new(),
+            keep_comments,
         }
     }
 
     pub fn scan_tokens(&mut self) -> (Vec<Token>, Vec<ScannerError>) {
-        let mut errors: Vec<ScannerError> = Vec::new();
         let mut tokens: Vec<Token> = Vec::new();
 
         while !self.is_eof() {
             self.start = self.current;
+            self.start_line = self.line;
+            self.start_column = self.start - self.line_start + 1;
 
             self.advance();
 
             match self.scan_token() {
                 Ok(Some(token)) => tokens.push(token),
                 Ok(None) => {}
-                Err(err) => errors.push(err),
+                Err(err) => self.errors.push(err),
             }
         }
 
-        tokens.push(Token::new(TokenType::Eof, "".to_string(), None, self.line));
-        (tokens, errors)
+        if !self.interpolations.is_empty() {
+            self.errors.push(ScannerError::UnterminatedError(
+                self.start_line,
+                self.start_column,
+                "Unterminated string interpolation".to_string(),
+            ));
+        }
+
+        let column = self.current - self.line_start + 1;
+        tokens.push(Token::new(TokenType::Eof, "".to_string(), None, self.line, column));
+        (tokens, std::mem::take(&mut self.errors))
     }
 
     fn scan_token(&mut self) -> Result<Option<Token>, ScannerError> {
@@ -44,14 +72,84 @@ impl CharScanner {
         match ch {
             '(' => Ok(self.build_non_literal_token(TokenType::LeftParen)),
             ')' => Ok(self.build_non_literal_token(TokenType::RightParen)),
-            '{' => Ok(self.build_non_literal_token(TokenType::LeftBrace)),
-            '}' => Ok(self.build_non_literal_token(TokenType::RightBrace)),
+            '{' => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+
+                Ok(self.build_non_literal_token(TokenType::LeftBrace))
+            }
+            '}' => match self.interpolations.pop() {
+                Some(0) => self.scan_string_literal(),
+                Some(depth) => {
+                    self.interpolations.push(depth - 1);
+                    Ok(self.build_non_literal_token(TokenType::RightBrace))
+                }
+                None => Ok(self.build_non_literal_token(TokenType::RightBrace)),
+            },
             ',' => Ok(self.build_non_literal_token(TokenType::Comma)),
-            '.' => Ok(self.build_non_literal_token(TokenType::Dot)),
-            '-' => Ok(self.build_non_literal_token(TokenType::Minus)),
-            '+' => Ok(self.build_non_literal_token(TokenType::Plus)),
+            '[' => Ok(self.build_non_literal_token(TokenType::LeftBracket)),
+            ']' => Ok(self.build_non_literal_token(TokenType::RightBracket)),
+            '.' => {
+                let token_type = if self.peek() == '.' && self.peek_next() == '.' {
+                    self.advance();
+                    self.advance();
+                    TokenType::Ellipsis
+                } else {
+                    TokenType::Dot
+                };
+
+                Ok(self.build_non_literal_token(token_type))
+            }
+            '-' => {
+                let token_type = if self.is_match('-') {
+                    TokenType::MinusMinus
+                } else if self.is_match('=') {
+                    TokenType::MinusEqual
+                } else {
+                    TokenType::Minus
+                };
+
+                Ok(self.build_non_literal_token(token_type))
+            }
+            '+' => {
+                let token_type = if self.is_match('+') {
+                    TokenType::PlusPlus
+                } else if self.is_match('=') {
+                    TokenType::PlusEqual
+                } else {
+                    TokenType::Plus
+                };
+
+                Ok(self.build_non_literal_token(token_type))
+            }
+            '?' => Ok(self.build_non_literal_token(TokenType::Question)),
+            ':' => Ok(self.build_non_literal_token(TokenType::Colon)),
             ';' => Ok(self.build_non_literal_token(TokenType::Semicolon)),
-            '*' => Ok(self.build_non_literal_token(TokenType::Star)),
+            '*' => {
+                let token_type = if self.is_match('*') {
+                    TokenType::StarStar
+                } else if self.is_match('=') {
+                    TokenType::StarEqual
+                } else {
+                    TokenType::Star
+                };
+
+                Ok(self.build_non_literal_token(token_type))
+            }
+            '%' => Ok(self.build_non_literal_token(TokenType::Percent)),
+            '~' => {
+                let token_type = if self.is_match('/') {
+                    TokenType::TildeSlash
+                } else {
+                    TokenType::Tilde
+                };
+
+                Ok(self.build_non_literal_token(token_type))
+            }
+            '&' => Ok(self.build_non_literal_token(TokenType::Ampersand)),
+            '|' => Ok(self.build_non_literal_token(TokenType::Pipe)),
+            '^' => Ok(self.build_non_literal_token(TokenType::Caret)),
             '!' => {
                 let token_type = if self.is_match('=') {
                     TokenType::BangEqual
@@ -64,6 +162,8 @@ impl CharScanner {
             '=' => {
                 let token_type = if self.is_match('=') {
                     TokenType::EqualEqual
+                } else if self.is_match('>') {
+                    TokenType::Arrow
                 } else {
                     TokenType::Equal
                 };
@@ -73,6 +173,8 @@ impl CharScanner {
             '<' => {
                 let token_type = if self.is_match('=') {
                     TokenType::LessEqual
+                } else if self.is_match('<') {
+                    TokenType::LessLess
                 } else {
                     TokenType::Less
                 };
@@ -82,6 +184,8 @@ impl CharScanner {
             '>' => {
                 let token_type = if self.is_match('=') {
                     TokenType::GreaterEqual
+                } else if self.is_match('>') {
+                    TokenType::GreaterGreater
                 } else {
                     TokenType::Greater
                 };
@@ -94,7 +198,13 @@ impl CharScanner {
                         self.advance();
                     }
 
-                    Ok(None)
+                    if self.keep_comments {
+                        Ok(self.build_non_literal_token(TokenType::Comment))
+                    } else {
+                        Ok(None)
+                    }
+                } else if self.is_match('=') {
+                    Ok(self.build_non_literal_token(TokenType::SlashEqual))
                 } else {
                     Ok(self.build_non_literal_token(TokenType::Slash))
                 }
@@ -102,13 +212,14 @@ impl CharScanner {
             ' ' | '\r' | '\t' => Ok(None),
             '\n' => {
                 self.line += 1;
+                self.line_start = self.current;
                 Ok(None)
             }
             '"' => self.scan_string_literal(),
             '0'...'9' => self.scan_numeric_literal(),
             'a'...'z' | 'A'...'Z' | '_' => self.scan_identifier(),
             unknown_char => {
-                Err(ScannerError::ScannerError(self.line,
+                Err(ScannerError::ScannerError(self.start_line, self.start_column,
                                                format!("Invalid character: {}", unknown_char)))
             }
         }
@@ -136,7 +247,7 @@ impl CharScanner {
     }
 
     fn peek_next(&self) -> char {
-        if self.current >= self.source.len() + 1 {
+        if self.current + 1 >= self.source.len() {
             '\0'
         } else {
             self.source[self.current + 1]
@@ -168,52 +279,245 @@ impl CharScanner {
     }
 
     fn build_token(&self, token_type: TokenType, literal: Option<Literal>) -> Option<Token> {
-        Some(Token::new(token_type, self.current_lexeme(), literal, self.line))
+        Some(Token::new(
+            token_type,
+            self.current_lexeme(),
+            literal,
+            self.start_line,
+            self.start_column,
+        ))
     }
 
+    /// Scans the contents of a string up to the closing `"`, or up to the start of an
+    /// interpolated expression (`${`). In the latter case an `Interpolation` token is returned
+    /// and the rest of the string is scanned once the matching `}` is found.
     fn scan_string_literal(&mut self) -> Result<Option<Token>, ScannerError> {
-        while self.peek() != '"' && !self.is_eof() {
-            if self.peek() == '\n' {
-                self.line += 1;
+        let mut literal = String::new();
+        let mut invalid = false;
+
+        let token_type = loop {
+            if self.is_eof() {
+                return Err(ScannerError::UnterminatedError(
+                    self.start_line,
+                    self.start_column,
+                    "Unterminated string".to_string(),
+                ));
             }
 
+            let ch = self.source[self.current];
             self.advance();
-        }
 
+            match ch {
+                '"' => break TokenType::String,
+                '$' if self.peek() == '{' => {
+                    self.advance();
+                    self.interpolations.push(0);
+                    break TokenType::Interpolation;
+                }
+                '\\' => match self.scan_escape_sequence() {
+                    Ok(escaped) => literal.push(escaped),
+                    Err(err) => {
+                        // Keep going until the end of the string so its contents aren't
+                        // scanned as code, but report only the first invalid sequence. The
+                        // token is still returned, as it may start an interpolation.
+                        if !invalid {
+                            invalid = true;
+                            self.errors.push(err);
+                        }
+                    }
+                },
+                '\n' => {
+                    self.line += 1;
+                    self.line_start = self.current;
+                    literal.push(ch);
+                }
+                _ => literal.push(ch),
+            }
+        };
+
+        Ok(self.build_literal_token(token_type, Literal::String(literal)))
+    }
+
+    fn scan_escape_sequence(&mut self) -> Result<char, ScannerError> {
         if self.is_eof() {
-            return Err(ScannerError::ScannerError(self.line, "Unterminated string".to_string()));
+            return Err(ScannerError::UnterminatedError(
+                self.start_line,
+                self.start_column,
+                "Unterminated string".to_string(),
+            ));
         }
 
-        // Once more to cover the closing "
+        let ch = self.source[self.current];
         self.advance();
 
-        let literal = self.source[self.start + 1..self.current - 1]
-            .iter()
-            .map(|c| c.to_string())
-            .collect::<Vec<String>>()
-            .join("");
+        match ch {
+            'n' => Ok('\n'),
+            't' => Ok('\t'),
+            'r' => Ok('\r'),
+            '0' => Ok('\0'),
+            '\\' => Ok('\\'),
+            '"' => Ok('"'),
+            '$' => Ok('$'),
+            'u' => self.scan_unicode_escape(),
+            '\n' => {
+                self.line += 1;
+                self.line_start = self.current;
+                Err(ScannerError::ScannerError(
+                    self.start_line,
+                    self.start_column,
+                    "Invalid escape sequence: \\ at end of line".to_string(),
+                ))
+            }
+            unknown => Err(ScannerError::ScannerError(
+                self.start_line,
+                self.start_column,
+                format!("Invalid escape sequence: \\{}", unknown),
+            )),
+        }
+    }
+
+    fn scan_unicode_escape(&mut self) -> Result<char, ScannerError> {
+        if !self.is_match('{') {
+            return Err(ScannerError::ScannerError(
+                self.start_line,
+                self.start_column,
+                "Expected `{` after \\u".to_string(),
+            ));
+        }
+
+        let mut digits = String::new();
 
-        Ok(self.build_literal_token(TokenType::String, Literal::String(literal)))
+        while self.peek().is_ascii_hexdigit() {
+            digits.push(self.peek());
+            self.advance();
+        }
+
+        if !self.is_match('}') {
+            return Err(ScannerError::ScannerError(
+                self.start_line,
+                self.start_column,
+                format!("Invalid unicode escape sequence: \\u{{{}", digits),
+            ));
+        }
+
+        if digits.is_empty() || digits.len() > 6 {
+            return Err(ScannerError::ScannerError(
+                self.start_line,
+                self.start_column,
+                format!("Invalid unicode escape sequence: \\u{{{}}}", digits),
+            ));
+        }
+
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(std::char::from_u32)
+            .ok_or_else(|| {
+                ScannerError::ScannerError(
+                    self.start_line,
+                    self.start_column,
+                    format!("Invalid unicode code point: \\u{{{}}}", digits),
+                )
+            })
     }
 
     fn scan_numeric_literal(&mut self) -> Result<Option<Token>, ScannerError> {
-        while self.peek().is_digit(10) {
+        let radix = match (self.source[self.current - 1], self.peek()) {
+            ('0', 'x') | ('0', 'X') => 16,
+            ('0', 'b') | ('0', 'B') => 2,
+            _ => 10,
+        };
+
+        if radix != 10 {
+            // Consume the x or b
             self.advance();
+
+            let start = self.current;
+            self.skip_digits(radix);
+            let digits = self.digits_since(start)?;
+
+            let literal = u64::from_str_radix(&digits, radix).map_err(|_| {
+                ScannerError::ScannerError(
+                    self.start_line,
+                    self.start_column,
+                    format!("Numeric literal too large: {}", self.current_lexeme()),
+                )
+            })?;
+
+            return Ok(self.build_literal_token(TokenType::Number, Literal::Number(literal as f64)));
         }
 
+        self.skip_digits(10);
+        let start = self.start;
+        let mut number = self.digits_since(start)?;
+
         if self.peek() == '.' && self.peek_next().is_digit(10) {
             // Consume the .
             self.advance();
 
-            while self.peek().is_digit(10) {
+            let start = self.current;
+            self.skip_digits(10);
+            number.push('.');
+            number.push_str(&self.digits_since(start)?);
+        }
+
+        if self.is_exponent_start() {
+            // Consume the e and its sign
+            self.advance();
+            number.push('e');
+
+            if self.peek() == '+' || self.peek() == '-' {
+                number.push(self.peek());
                 self.advance();
             }
+
+            let start = self.current;
+            self.skip_digits(10);
+            number.push_str(&self.digits_since(start)?);
         }
 
-        let literal = self.current_lexeme().parse::<f64>().unwrap();
+        let literal = number.parse::<f64>().unwrap();
         Ok(self.build_literal_token(TokenType::Number, Literal::Number(literal)))
     }
 
+    // `e` or `E`, optionally followed by a sign, and then by a digit
+    fn is_exponent_start(&self) -> bool {
+        if self.peek() != 'e' && self.peek() != 'E' {
+            return false;
+        }
+
+        match self.peek_next() {
+            '+' | '-' => match self.source.get(self.current + 2) {
+                Some(c) => c.is_digit(10),
+                None => false,
+            },
+            c => c.is_digit(10),
+        }
+    }
+
+    fn skip_digits(&mut self, radix: u32) {
+        while self.peek().is_digit(radix) || self.peek() == '_' {
+            self.advance();
+        }
+    }
+
+    // Returns the digits scanned since `start` without the `_` separators, which are only
+    // allowed between two digits.
+    fn digits_since(&self, start: usize) -> Result<String, ScannerError> {
+        let digits: String = self.source[start..self.current].iter().collect();
+
+        if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_')
+            || digits.contains("__")
+        {
+            return Err(ScannerError::ScannerError(
+                self.start_line,
+                self.start_column,
+                format!("Invalid numeric literal: {}", self.current_lexeme()),
+            ));
+        }
+
+        Ok(digits.replace('_', ""))
+    }
+
     fn scan_identifier(&mut self) -> Result<Option<Token>, ScannerError> {
         while self.peek().is_alphanumeric() || self.peek() == '_' {
             self.advance();
@@ -2,23 +2,57 @@ use std;
 
 #[derive(Debug)]
 pub enum ScannerError {
-    ScannerError(usize, String),
+    // Line and column where the token with the error starts
+    ScannerError(usize, usize, String),
+    // A string or string interpolation still open at the end of the source, which more source
+    // could fix
+    UnterminatedError(usize, usize, String),
 }
 
-impl std::fmt::Display for ScannerError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl ScannerError {
+    /// Whether the error is about a string that was still open at the end of the source, in
+    /// which case more source could fix it.
+    pub fn is_unterminated(&self) -> bool {
+        match *self {
+            ScannerError::UnterminatedError(_, _, _) => true,
+            ScannerError::ScannerError(_, _, _) => false,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        match *self {
+            ScannerError::ScannerError(line, _, _)
+            | ScannerError::UnterminatedError(line, _, _) => line,
+        }
+    }
+
+    pub fn column(&self) -> usize {
         match *self {
-            ScannerError::ScannerError(ref line, ref message) => {
-                write!(f, "[line {}] ScannerError: {}", line, message)
-            }
+            ScannerError::ScannerError(_, column, _)
+            | ScannerError::UnterminatedError(_, column, _) => column,
         }
     }
+
+    /// The error without the line it happened on.
+    pub fn message(&self) -> &str {
+        match *self {
+            ScannerError::ScannerError(_, _, ref message)
+            | ScannerError::UnterminatedError(_, _, ref message) => message,
+        }
+    }
+}
+
+impl std::fmt::Display for ScannerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "[line {}] ScannerError: {}", self.line(), self.message())
+    }
 }
 
 impl std::error::Error for ScannerError {
     fn description(&self) -> &str {
         match *self {
-            ScannerError::ScannerError(_, _) => "ScannerError",
+            ScannerError::ScannerError(_, _, _) => "ScannerError",
+            ScannerError::UnterminatedError(_, _, _) => "UnterminatedError",
         }
     }
 }
\ No newline at end of file
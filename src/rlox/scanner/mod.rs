@@ -15,7 +15,14 @@ impl Scanner {
     }
 
     pub fn scan_tokens(&self) -> (Vec<Token>, Vec<ScannerError>) {
-        let mut scanner = CharScanner::new(self.source.chars().collect());
+        let mut scanner = CharScanner::new(self.source.chars().collect(), false);
+        scanner.scan_tokens()
+    }
+
+    /// Same as `scan_tokens`, with a `Comment` token for each comment. The parser doesn't
+    /// expect them, they are only useful to tools like the formatter.
+    pub fn scan_tokens_with_comments(&self) -> (Vec<Token>, Vec<ScannerError>) {
+        let mut scanner = CharScanner::new(self.source.chars().collect(), true);
         scanner.scan_tokens()
     }
 }
@@ -38,7 +45,7 @@ mod tests {
 
     #[test]
     fn error_on_unknown_character() {
-        let scanner = Scanner::new("%".to_string());
+        let scanner = Scanner::new("@".to_string());
         let (tokens, errors) = scanner.scan_tokens();
         assert_eq!(tokens.len(), 1); // Eof token
         assert_eq!(errors.len(), 1);
@@ -57,6 +64,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn column_numbers() {
+        let scanner = Scanner::new("var x = \"a\nb\" + 1;\n  @".to_string());
+        let (tokens, errors) = scanner.scan_tokens();
+
+        let positions: Vec<(usize, usize)> =
+            tokens.iter().map(|token| (token.line, token.column)).collect();
+        assert_eq!(
+            positions,
+            vec![(1, 1), (1, 5), (1, 7), (1, 9), (2, 4), (2, 6), (2, 7), (3, 4)]
+        );
+
+        assert!(!errors[0].is_unterminated());
+        assert_eq!((errors[0].line(), errors[0].column()), (3, 3));
+    }
+
+    #[test]
+    fn unterminated_strings_point_at_their_start() {
+        let scanner = Scanner::new("print 1;
+print \"a\nb".to_string());
+        let (_, errors) = scanner.scan_tokens();
+
+        assert!(errors[0].is_unterminated());
+        assert_eq!((errors[0].line(), errors[0].column()), (2, 7));
+    }
+
+    #[test]
+    fn comments_are_kept_on_request() {
+        let scanner = Scanner::new("1 // one\n// two".to_string());
+        let (tokens, errors) = scanner.scan_tokens_with_comments();
+
+        assert_eq!(errors.len(), 0);
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[1].token_type, TokenType::Comment);
+        assert_eq!(tokens[1].lexeme, "// one");
+        assert_eq!(tokens[2].lexeme, "// two");
+        assert_eq!(tokens[2].line, 2);
+    }
+
     mod tokens {
         use super::*;
 
@@ -92,16 +138,37 @@ mod tests {
         test_token!(right_paren, ")", TokenType::RightParen);
         test_token!(left_brace, "{", TokenType::LeftBrace);
         test_token!(right_brace, "}", TokenType::RightBrace);
+        test_token!(left_bracket, "[", TokenType::LeftBracket);
+        test_token!(right_bracket, "]", TokenType::RightBracket);
         test_token!(comma, ",", TokenType::Comma);
         test_token!(dot, ".", TokenType::Dot);
+        test_token!(ellipsis, "...", TokenType::Ellipsis);
         test_token!(minus, "-", TokenType::Minus);
         test_token!(plus, "+", TokenType::Plus);
         test_token!(semicolon, ";", TokenType::Semicolon);
         test_token!(star, "*", TokenType::Star);
+        test_token!(star_star, "**", TokenType::StarStar);
+        test_token!(plus_equal, "+=", TokenType::PlusEqual);
+        test_token!(minus_equal, "-=", TokenType::MinusEqual);
+        test_token!(star_equal, "*=", TokenType::StarEqual);
+        test_token!(slash_equal, "/=", TokenType::SlashEqual);
+        test_token!(plus_plus, "++", TokenType::PlusPlus);
+        test_token!(minus_minus, "--", TokenType::MinusMinus);
+        test_token!(question, "?", TokenType::Question);
+        test_token!(colon, ":", TokenType::Colon);
+        test_token!(ampersand, "&", TokenType::Ampersand);
+        test_token!(pipe, "|", TokenType::Pipe);
+        test_token!(caret, "^", TokenType::Caret);
+        test_token!(tilde, "~", TokenType::Tilde);
+        test_token!(less_less, "<<", TokenType::LessLess);
+        test_token!(greater_greater, ">>", TokenType::GreaterGreater);
+        test_token!(percent, "%", TokenType::Percent);
+        test_token!(tilde_slash, "~/", TokenType::TildeSlash);
         test_token!(bang, "!", TokenType::Bang);
         test_token!(bang_equal, "!=", TokenType::BangEqual);
         test_token!(equal, "=", TokenType::Equal);
         test_token!(equal_equal, "==", TokenType::EqualEqual);
+        test_token!(arrow, "=>", TokenType::Arrow);
         test_token!(less, "<", TokenType::Less);
         test_token!(less_equal, "<=", TokenType::LessEqual);
         test_token!(greater, ">", TokenType::Greater);
@@ -132,6 +199,102 @@ mod tests {
                 }
             }
 
+            macro_rules! test_string {
+                ($name:ident, $code:expr, $expected:expr) => {
+                    #[test]
+                    fn $name() {
+                        let scanner = Scanner::new($code.to_string());
+                        let (tokens, errors) = scanner.scan_tokens();
+
+                        assert_eq!(errors.len(), 0);
+                        let token = tokens.get(0).unwrap();
+
+                        match token.literal.as_ref().expect("Missing literal in token") {
+                            &Literal::String(ref value) => assert_eq!(value, $expected),
+                            _ => assert!(false, "Should be a Literal::String"),
+                        }
+                    }
+                }
+            }
+
+            macro_rules! test_string_error {
+                ($name:ident, $code:expr) => {
+                    #[test]
+                    fn $name() {
+                        let scanner = Scanner::new($code.to_string());
+                        let (_, errors) = scanner.scan_tokens();
+
+                        assert_eq!(errors.len(), 1);
+                    }
+                }
+            }
+
+            mod escapes {
+                use super::*;
+
+                test_string!(newline, r#""a\nb""#, "a\nb");
+                test_string!(tab, r#""a\tb""#, "a\tb");
+                test_string!(backslash, r#""a\\b""#, "a\\b");
+                test_string!(quote, r#""a\"b""#, "a\"b");
+                test_string!(dollar, r#""\${a}""#, "${a}");
+                test_string!(unicode, r#""\u{48}\u{1F600}""#, "H\u{1F600}");
+                test_string_error!(unknown_escape, r#""\q""#);
+                test_string_error!(unicode_without_braces, r#""\u48""#);
+                test_string_error!(unicode_out_of_range, r#""\u{110000}""#);
+                test_string_error!(unterminated_unicode, r#""\u{48""#);
+            }
+
+            #[test]
+            fn interpolation() {
+                let scanner = Scanner::new("\"a ${b} c ${ {} } d\"".to_string());
+                let (tokens, errors) = scanner.scan_tokens();
+
+                assert_eq!(errors.len(), 0);
+
+                let token_types: Vec<TokenType> =
+                    tokens.iter().map(|token| token.token_type).collect();
+                assert_eq!(
+                    token_types,
+                    vec![
+                        TokenType::Interpolation,
+                        TokenType::Identifier,
+                        TokenType::Interpolation,
+                        TokenType::LeftBrace,
+                        TokenType::RightBrace,
+                        TokenType::String,
+                        TokenType::Eof,
+                    ]
+                );
+
+                match tokens[2].literal {
+                    Some(Literal::String(ref value)) => assert_eq!(value, " c "),
+                    _ => assert!(false, "Should be a Literal::String"),
+                }
+            }
+
+            test_string_error!(unterminated_interpolation, r#""a ${b"#);
+
+            #[test]
+            fn interpolation_after_an_invalid_escape() {
+                let scanner = Scanner::new(r#""\q ${b} c" d"#.to_string());
+                let (tokens, errors) = scanner.scan_tokens();
+
+                assert_eq!(errors.len(), 1);
+
+                let token_types: Vec<TokenType> =
+                    tokens.iter().map(|token| token.token_type).collect();
+                assert_eq!(
+                    token_types,
+                    vec![
+                        TokenType::Interpolation,
+                        TokenType::Identifier,
+                        TokenType::String,
+                        TokenType::Identifier,
+                        TokenType::Eof,
+                    ]
+                );
+            }
+
             #[test]
             fn number() {
                 let scanner = Scanner::new("123.45".to_string());
@@ -151,6 +314,45 @@ mod tests {
                 }
             }
 
+            mod numbers {
+                use super::*;
+
+                macro_rules! test_number {
+                    ($name:ident, $code:expr, $expected:expr) => {
+                        #[test]
+                        fn $name() {
+                            let scanner = Scanner::new($code.to_string());
+                            let (tokens, errors) = scanner.scan_tokens();
+
+                            assert_eq!(errors.len(), 0);
+                            let token = tokens.get(0).unwrap();
+
+                            match token.literal.as_ref().expect("Missing literal in token") {
+                                &Literal::Number(value) => assert_eq!(value, $expected),
+                                _ => assert!(false, "Should be a Literal::Number"),
+                            }
+                        }
+                    }
+                }
+
+                test_number!(integer, "42", 42.0);
+                test_number!(hexadecimal, "0xFF", 255.0);
+                test_number!(hexadecimal_uppercase, "0XfF", 255.0);
+                test_number!(binary, "0b1010", 10.0);
+                test_number!(underscores, "1_000_000", 1000000.0);
+                test_number!(underscores_in_fraction, "1_000.000_5", 1000.0005);
+                test_number!(hexadecimal_underscores, "0xFF_FF", 65535.0);
+                test_number!(exponent, "1e21", 1e21);
+                test_number!(negative_exponent, "1.5e-7", 1.5e-7);
+                test_number!(positive_exponent, "2E+3", 2000.0);
+                test_string_error!(missing_hexadecimal_digits, "0x");
+                test_string_error!(invalid_binary_digit, "0b2");
+                test_string_error!(trailing_underscore, "1_");
+                test_string_error!(double_underscore, "1__0");
+                test_string_error!(underscore_before_fraction, "1_.5");
+                test_string_error!(too_large, "0x1_0000_0000_0000_0000");
+            }
+
             #[test]
             fn identifier() {
                 let scanner = Scanner::new("my_var".to_string());
@@ -167,6 +369,7 @@ mod tests {
                 use super::*;
 
                 test_token!(and, "and", TokenType::And);
+                test_token!(await_token, "await", TokenType::Await);
                 test_token!(class, "class", TokenType::Class);
                 test_token!(else_token, "else", TokenType::Else);
                 test_token!(false_token, "false", TokenType::False);
@@ -182,6 +385,7 @@ mod tests {
                 test_token!(true_token, "true", TokenType::True);
                 test_token!(var, "var", TokenType::Var);
                 test_token!(while_token, "while", TokenType::While);
+                test_token!(yield_token, "yield", TokenType::Yield);
             }
         }
     }
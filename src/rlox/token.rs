@@ -2,14 +2,51 @@ use std;
 use std::collections::HashMap;
 use rlox::lox_value::LoxValue;
 
-#[derive(Debug,PartialEq,Clone)]
+#[derive(Debug,PartialEq,Clone,Serialize,Deserialize)]
+#[serde(tag = "type", content = "fields")]
 pub enum Literal {
-    Number(f64),
+    Number(#[serde(with = "number")] f64),
     String(String),
     Bool(bool),
     Nil,
 }
 
+// JSON has no infinite numbers, which come from literals too large for an f64, so they are
+// written as the strings "Infinity" and "-Infinity"
+mod number {
+    use serde::de::{self, Deserialize, Deserializer};
+    use serde::Serializer;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Number {
+        Finite(f64),
+        Named(String),
+    }
+
+    pub fn serialize<S: Serializer>(number: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if number.is_finite() {
+            serializer.serialize_f64(*number)
+        } else if number.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if *number > 0.0 {
+            serializer.serialize_str("Infinity")
+        } else {
+            serializer.serialize_str("-Infinity")
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        match Number::deserialize(deserializer)? {
+            Number::Finite(number) => Ok(number),
+            Number::Named(ref name) if name == "Infinity" => Ok(f64::INFINITY),
+            Number::Named(ref name) if name == "-Infinity" => Ok(f64::NEG_INFINITY),
+            Number::Named(ref name) if name == "NaN" => Ok(f64::NAN),
+            Number::Named(name) => Err(de::Error::custom(format!("invalid number: {}", name))),
+        }
+    }
+}
+
 impl std::fmt::Display for Literal {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
@@ -30,38 +67,55 @@ impl Literal {
             Literal::Nil => Some(LoxValue::Nil),
         }
     }
+
+    /// The literal for a value, if it's a number, string, boolean or `nil`.
+    pub fn from_value(value: LoxValue) -> Option<Literal> {
+        match value {
+            LoxValue::Number(number) => Some(Literal::Number(number)),
+            LoxValue::String(string) => Some(Literal::String(string)),
+            LoxValue::Bool(b) => Some(Literal::Bool(b)),
+            LoxValue::Nil => Some(Literal::Nil),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Token {
     pub token_type: TokenType,
     pub literal: Option<Literal>,
     pub lexeme: String,
     pub line: usize,
+    // Of the first character of the token, starting at 1
+    pub column: usize,
 }
 
 impl Token {
     pub fn new(token_type: TokenType,
                lexeme: String,
                literal: Option<Literal>,
-               line: usize)
+               line: usize,
+               column: usize)
                -> Token {
         Token {
             token_type,
             lexeme,
             literal,
             line,
+            column,
         }
     }
 }
 
-#[derive(Debug,Clone,Copy,PartialEq)]
+#[derive(Debug,Clone,Copy,PartialEq,Serialize,Deserialize)]
 pub enum TokenType {
     // Single-character tokens.
     LeftParen,
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
     Comma,
     Dot,
     Minus,
@@ -69,6 +123,13 @@ pub enum TokenType {
     Semicolon,
     Slash,
     Star,
+    Percent,
+    Question,
+    Colon,
+    Ampersand,
+    Pipe,
+    Caret,
+    Tilde,
 
     // One or two character tokens.
     Bang,
@@ -79,14 +140,29 @@ pub enum TokenType {
     GreaterEqual,
     Less,
     LessEqual,
+    Arrow,
+    Ellipsis,
+    StarStar,
+    TildeSlash,
+    PlusEqual,
+    MinusEqual,
+    StarEqual,
+    SlashEqual,
+    PlusPlus,
+    MinusMinus,
+    LessLess,
+    GreaterGreater,
 
     // Literals.
     Identifier,
     String,
+    // Part of a string that is followed by an interpolated expression, e.g. `"Hello ${`
+    Interpolation,
     Number,
 
     // Keywords.
     And,
+    Await,
     Class,
     Else,
     False,
@@ -102,6 +178,10 @@ pub enum TokenType {
     True,
     Var,
     While,
+    Yield,
+
+    // `// ...`, only kept when scanning for the formatter
+    Comment,
 
     Eof,
 }
@@ -110,6 +190,7 @@ lazy_static! {
     pub static ref KEYWORDS: HashMap<&'static str, TokenType> = {
         let mut m = HashMap::new();
         m.insert("and", TokenType::And);
+        m.insert("await", TokenType::Await);
         m.insert("class", TokenType::Class);
         m.insert("else", TokenType::Else);
         m.insert("false", TokenType::False);
@@ -125,6 +206,7 @@ lazy_static! {
         m.insert("true", TokenType::True);
         m.insert("var", TokenType::Var);
         m.insert("while", TokenType::While);
+        m.insert("yield", TokenType::Yield);
         m
     };
 }
\ No newline at end of file
@@ -12,7 +12,7 @@ pub fn run_from_wasm(data: *const c_char) -> *const c_char {
         incoming_str = CStr::from_ptr(data).to_str().unwrap().to_owned();
     }
 
-    CString::new(run_string(incoming_str)).unwrap().into_raw()
+    CString::new(run_string(incoming_str).to_string()).unwrap().into_raw()
 }
 
 #[no_mangle]
@@ -0,0 +1,167 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn conditional_expression() {
+    let output = utils::execute(
+        r#"
+        print true ? "yes" : "no";
+        print nil ? "yes" : "no";
+        print false ? 1 : false ? 2 : 3;
+
+        var x = 1 < 2 ? 10 : 20;
+        print x;
+    "#,
+    );
+
+    assert_eq!(output[0], "yes");
+    assert_eq!(output[1], "no");
+    assert_eq!(output[2], "3");
+    assert_eq!(output[3], "10");
+}
+
+#[test]
+fn conditional_only_evaluates_one_branch() {
+    let output = utils::execute(
+        r#"
+        fun say(message) {
+            print message;
+            return message;
+        }
+
+        true ? say("then") : say("else");
+    "#,
+    );
+
+    assert_eq!(output, vec!["then"]);
+}
+
+#[test]
+fn compound_assignment() {
+    let output = utils::execute(
+        r#"
+        var x = 10;
+        x += 5;
+        print x;
+        x -= 3;
+        print x;
+        x *= 2;
+        print x;
+        x /= 4;
+        print x;
+
+        var s = "a";
+        s += "b";
+        print s;
+    "#,
+    );
+
+    assert_eq!(output, vec!["15", "12", "24", "6", "ab"]);
+}
+
+#[test]
+fn increment_and_decrement() {
+    let output = utils::execute(
+        r#"
+        var i = 0;
+        print i++;
+        print i;
+        print ++i;
+        print i--;
+        print --i;
+
+        for (var j = 0; j < 3; j++) print j;
+    "#,
+    );
+
+    assert_eq!(output, vec!["0", "1", "2", "2", "0", "0", "1", "2"]);
+}
+
+#[test]
+fn compound_assignment_on_properties_and_elements() {
+    let output = utils::execute(
+        r#"
+        class Counter {}
+        var counter = Counter();
+        counter.count = 1;
+        counter.count += 1;
+        counter.count++;
+        print counter.count;
+
+        var list = [1, 2];
+        list[1] *= 10;
+        list[0]--;
+        print list;
+    "#,
+    );
+
+    assert_eq!(output[0], "3");
+    assert_eq!(output[1], "[0, 20]");
+}
+
+#[test]
+fn target_is_evaluated_once() {
+    let output = utils::execute(
+        r#"
+        class Box {}
+        var box = Box();
+        box.value = 1;
+        var calls = 0;
+
+        fun getBox() {
+            calls++;
+            return box;
+        }
+
+        getBox().value += 10;
+        print box.value;
+        print calls;
+
+        var list = [0, 0];
+        var i = 0;
+        list[i++] += 5;
+        print list;
+        print i;
+    "#,
+    );
+
+    assert_eq!(output, vec!["11", "1", "[5, 0]", "1"]);
+}
+
+#[test]
+fn compound_assignment_uses_setters() {
+    let output = utils::execute(
+        r#"
+        class Temperature {
+            init() {
+                this._celsius = 0;
+            }
+
+            celsius {
+                return this._celsius;
+            }
+
+            set celsius(value) {
+                print "set ${value}";
+                this._celsius = value;
+            }
+        }
+
+        var t = Temperature();
+        t.celsius += 5;
+    "#,
+    );
+
+    assert_eq!(output[0], "set 5");
+}
+
+#[test]
+fn invalid_compound_assignment_target() {
+    let output = utils::execute("1 += 2;");
+
+    assert_eq!(output[0], "[line 1] Invalid assignment target");
+
+    let output = utils::execute("(1)++;");
+
+    assert_eq!(output[0], "[line 1] Invalid assignment target");
+}
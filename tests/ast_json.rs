@@ -0,0 +1,125 @@
+extern crate rlox;
+extern crate serde_json;
+
+use std::fs;
+
+use rlox::RunOptions;
+
+fn run(json: &str) -> rlox::RunResult {
+    rlox::run_ast_json(json, RunOptions::default())
+}
+
+#[test]
+fn schema() {
+    let json = rlox::ast_json("print -x;".to_string()).unwrap();
+
+    assert_eq!(
+        json,
+        r#"{"version":2,"statements":[{"type":"Print","fields":[{"token_type":"Print","literal":null,"lexeme":"print","line":1,"column":1},{"type":"Unary","fields":[{"token_type":"Minus","literal":null,"lexeme":"-","line":1,"column":7},{"type":"Var","fields":[{"token_type":"Identifier","literal":null,"lexeme":"x","line":1,"column":8}]}]}]}]}"#
+    );
+}
+
+#[test]
+fn samples_run_the_same_after_a_round_trip() {
+    for entry in fs::read_dir("samples").unwrap() {
+        let path = entry.unwrap().path();
+        let code = fs::read_to_string(&path).unwrap();
+
+        let json = rlox::ast_json(code.clone()).unwrap();
+        let expected = rlox::run_string(code);
+        let result = run(&json);
+
+        assert!(expected.errors.is_empty(), "{:?} failed", path);
+        assert_eq!(result.output, expected.output, "{:?} runs differently", path);
+        assert!(result.errors.is_empty());
+    }
+}
+
+#[test]
+fn round_trip_keeps_every_kind_of_node() {
+    let code = r#"
+        class Base { init(n) { this.n = n; } describe() { return "base ${this.n}"; } }
+        class Point < Base {
+          init(x, y = 2, ...rest) { super.init(x); this.y = y; this.rest = rest; }
+          norm { return this.n ** 2 + this.y ** 2; }
+          set norm(value) { this.n = value; }
+          class origin() { return Point(0, 0); }
+          describe() { return super.describe() + "!"; }
+        }
+        var p = Point(3, 4, 5, 6);
+        p.n += 1;
+        p.n++;
+        var items = [1, ...p.rest, "three"];
+        items[0] = items[0] * 10;
+        for (item in items) print item;
+        fun numbers() { yield 1; yield 2; }
+        for (n in numbers()) print n;
+        var f = fun (a) { return a > 1 ? "big" : "small"; };
+        print f(2) + " " + f(0);
+        if (!(p.n == 5) and true or nil) print "unreachable"; else print p.norm;
+        var i = 0;
+        while (i < 2) { i = i + 1; }
+        match (p) {
+          Point q if q.y > 10 => print "far";
+          Point _ => print p.describe();
+          _ => print "other";
+        }
+        print Point.origin().norm;
+    "#;
+
+    let expected = rlox::run_string(code.to_string());
+    assert!(expected.errors.is_empty(), "{}", expected);
+
+    let result = run(&rlox::ast_json(code.to_string()).unwrap());
+    assert_eq!(result.to_string(), expected.to_string());
+}
+
+#[test]
+fn invalid_trees() {
+    let result = run(r#"{"version":2,"statements":[{"type":"Nope"}]}"#);
+    assert_eq!(result.errors[0].variant(), "InvalidAstError");
+    assert_eq!(result.exit_code, 65);
+
+    let result = run(r#"{"version":3,"statements":[]}"#);
+    assert_eq!(
+        result.to_string(),
+        "Invalid syntax tree: unsupported version 3, expected 2"
+    );
+
+    // Trees with the right shape that the parser can't produce
+    let mut tree: serde_json::Value =
+        serde_json::from_str(&rlox::ast_json("fun f() { print 1; }".to_string()).unwrap()).unwrap();
+    let print = tree["statements"][0]["fields"][2]["fields"][0].clone();
+    tree["statements"][0]["fields"][2] = print;
+
+    let result = run(&tree.to_string());
+    assert_eq!(result.to_string(), "Invalid syntax tree: the body of `f` isn't a block");
+    assert_eq!(result.exit_code, 65);
+
+    let mut tree: serde_json::Value =
+        serde_json::from_str(&rlox::ast_json("x++; x += 1;".to_string()).unwrap()).unwrap();
+    let literal = serde_json::json!({"type": "Literal", "fields": {"type": "Number", "fields": 1}});
+    tree["statements"][0]["fields"]["fields"][0] = literal.clone();
+
+    let result = run(&tree.to_string());
+    assert_eq!(result.to_string(), "Invalid syntax tree: invalid assignment target");
+
+    tree["statements"][0] = tree["statements"][1].clone();
+    tree["statements"][0]["fields"]["fields"][0] = literal;
+
+    let result = run(&tree.to_string());
+    assert_eq!(result.to_string(), "Invalid syntax tree: invalid assignment target");
+}
+
+#[test]
+fn round_trip_keeps_infinite_numbers() {
+    let code = format!("print 1{0}; print -1{0};", "0".repeat(400));
+
+    let json = rlox::ast_json(code.clone()).unwrap();
+    assert!(json.contains(r#"{"type":"Number","fields":"Infinity"}"#));
+
+    let result = run(&json);
+    assert!(result.errors.is_empty(), "{}", result);
+    assert_eq!(result.output, rlox::run_string(code).output);
+    assert_eq!(result.output, "Infinity\n-Infinity\n");
+}
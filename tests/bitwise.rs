@@ -0,0 +1,64 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn bitwise_operators() {
+    let output = utils::execute(
+        r#"
+        print 12 & 10;
+        print 12 | 10;
+        print 12 ^ 10;
+        print ~5;
+        print 1 << 4;
+        print 256 >> 2;
+        print -16 >> 2;
+    "#,
+    );
+
+    assert_eq!(output, vec!["8", "14", "6", "-6", "16", "64", "-4"]);
+}
+
+#[test]
+fn precedence() {
+    let output = utils::execute(
+        r#"
+        var READ = 1;
+        var WRITE = 2;
+        var flags = READ | WRITE;
+
+        print flags & WRITE == WRITE;
+        print 1 | 2 ^ 3 & 4;
+        print 1 << 2 + 1;
+        print 1 << 3 > 4;
+    "#,
+    );
+
+    assert_eq!(output[0], "true");
+    assert_eq!(output[1], "3");
+    assert_eq!(output[2], "8");
+    assert_eq!(output[3], "true");
+}
+
+#[test]
+fn operands_must_be_integers() {
+    let output = utils::execute("print 1.5 & 1;");
+    assert_eq!(output[0], "[line 1] Operands of `&` must be integers");
+
+    let output = utils::execute("print \"a\" | 1;");
+    assert_eq!(output[0], "[line 1] Operands of `|` must be integers");
+
+    let output = utils::execute("print ~0.5;");
+    assert_eq!(output[0], "[line 1] Operands of `~` must be integers");
+
+    let output = utils::execute("print 2 ** 60 ^ 1;");
+    assert_eq!(output[0], "[line 1] Operands of `^` must be integers");
+}
+
+#[test]
+fn shift_amount_must_be_in_range() {
+    let output = utils::execute("print 1 << 64;");
+    assert_eq!(output[0], "[line 1] Shift amount must be between 0 and 63");
+
+    let output = utils::execute("print 1 >> -1;");
+    assert_eq!(output[0], "[line 1] Shift amount must be between 0 and 63");
+}
@@ -0,0 +1,276 @@
+extern crate rlox;
+mod utils;
+use utils::execute;
+
+#[test]
+fn getter_runs_on_property_access() {
+    let output = execute(
+        r#"
+        class Circle {
+            init(radius) {
+                this.radius = radius;
+            }
+
+            area {
+                return 3 * this.radius * this.radius;
+            }
+        }
+
+        print Circle(2).area;
+    "#,
+    );
+
+    assert_eq!(output[0], "12");
+}
+
+#[test]
+fn setter_runs_on_assignment() {
+    let output = execute(
+        r#"
+        class Circle {
+            init(radius) {
+                this.radius = radius;
+            }
+
+            set diameter(value) {
+                this.radius = value / 2;
+            }
+        }
+
+        var circle = Circle(1);
+        print circle.diameter = 10;
+        print circle.radius;
+    "#,
+    );
+
+    assert_eq!(output[0], "10");
+    assert_eq!(output[1], "5");
+}
+
+#[test]
+fn getter_and_setter_with_the_same_name() {
+    let output = execute(
+        r#"
+        class Temperature {
+            init() {
+                this.celsius = 0;
+            }
+
+            fahrenheit {
+                return this.celsius * 9 / 5 + 32;
+            }
+
+            set fahrenheit(value) {
+                this.celsius = (value - 32) * 5 / 9;
+            }
+        }
+
+        var temperature = Temperature();
+        temperature.fahrenheit = 212;
+        print temperature.celsius;
+        print temperature.fahrenheit;
+    "#,
+    );
+
+    assert_eq!(output[0], "100");
+    assert_eq!(output[1], "212");
+}
+
+#[test]
+fn getters_and_setters_are_inherited() {
+    let output = execute(
+        r#"
+        class Base {
+            name {
+                return "base " + this._name;
+            }
+
+            set name(value) {
+                this._name = value;
+            }
+        }
+
+        class Derived < Base {}
+
+        var derived = Derived();
+        derived.name = "derived";
+        print derived.name;
+    "#,
+    );
+
+    assert_eq!(output[0], "base derived");
+}
+
+#[test]
+fn assigning_a_property_with_only_a_getter() {
+    let output = execute(
+        r#"
+        class Something {
+            value {
+                return 1;
+            }
+        }
+
+        Something().value = 2;
+    "#,
+    );
+
+    assert_eq!(output[0], "[line 8] Property `value` has a getter but no setter.");
+}
+
+#[test]
+fn reading_a_property_with_only_a_setter() {
+    let output = execute(
+        r#"
+        class Something {
+            set value(v) {}
+        }
+
+        print Something().value;
+    "#,
+    );
+
+    assert_eq!(output[0], "[line 6] Property `value` has a setter but no getter.");
+}
+
+#[test]
+fn setter_must_take_one_parameter() {
+    let output = execute(
+        r#"
+        class Something {
+            set value(a, b) {}
+        }
+    "#,
+    );
+
+    assert!(output[0].ends_with("Expected exactly one parameter for setter value"));
+}
+
+#[test]
+fn static_methods() {
+    let output = execute(
+        r#"
+        class Math {
+            class square(n) {
+                return n * n;
+            }
+
+            static cube(n) {
+                return n * n * n;
+            }
+        }
+
+        print Math.square(3);
+        print Math.cube(2);
+    "#,
+    );
+
+    assert_eq!(output[0], "9");
+    assert_eq!(output[1], "8");
+}
+
+#[test]
+fn static_methods_are_inherited() {
+    let output = execute(
+        r#"
+        class Base {
+            class create() {
+                return "created";
+            }
+        }
+
+        class Derived < Base {}
+
+        print Derived.create();
+    "#,
+    );
+
+    assert_eq!(output[0], "created");
+}
+
+#[test]
+fn static_methods_are_not_available_on_instances() {
+    let output = execute(
+        r#"
+        class Something {
+            class create() {}
+        }
+
+        Something().create();
+    "#,
+    );
+
+    assert_eq!(output[0], "[line 6] Undefined property `create`.");
+}
+
+#[test]
+fn undefined_static_method() {
+    let output = execute(
+        r#"
+        class Something {}
+        Something.create();
+    "#,
+    );
+
+    assert_eq!(output[0], "[line 3] Undefined property `create`.");
+}
+
+#[test]
+fn static_and_set_can_still_be_used_as_names() {
+    let output = execute(
+        r#"
+        class Something {
+            set(value) {
+                return "set " + value;
+            }
+
+            static() {
+                return "static";
+            }
+        }
+
+        var static = Something();
+        print static.set(1);
+        print static.static();
+    "#,
+    );
+
+    assert_eq!(output[0], "set 1");
+    assert_eq!(output[1], "static");
+}
+
+#[test]
+fn using_this_in_static_method_fails() {
+    let output = execute(
+        r#"
+        class Something {
+            class create() {
+                return this;
+            }
+        }
+    "#,
+    );
+
+    assert_eq!(output[0], "[line 4] UnexpectedTokenError: Cannot use `this` in a static method.");
+}
+
+#[test]
+fn methods_can_call_global_functions_with_the_same_name() {
+    let output = execute(
+        r#"
+        fun describe() {
+            return "global";
+        }
+
+        class Something {
+            describe() {
+                return describe();
+            }
+        }
+
+        print Something().describe();
+    "#,
+    );
+
+    assert_eq!(output[0], "global");
+}
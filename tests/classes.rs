@@ -90,6 +90,33 @@ fn call_method_on_instance() {
     assert_eq!(output[0], "42");
 }
 
+#[test]
+fn methods_arent_variables_inside_the_class() {
+    let output = utils::execute(
+        r#"
+        fun greet() {
+            return "function";
+        }
+
+        class Greeter {
+            greet() {
+                return "method";
+            }
+
+            greeting {
+                return greet() + " and " + this.greet();
+            }
+
+            set greeting(value) {}
+        }
+
+        print Greeter().greeting;
+    "#,
+    );
+
+    assert_eq!(output[0], "function and method");
+}
+
 #[test]
 fn reach_this_from_instance_method() {
     let output = utils::execute(
@@ -153,25 +180,27 @@ fn callback_with_bound_this() {
 }
 
 #[test]
-#[should_panic(expected = "UnexpectedTokenError: Cannot use `this` outside of a method.")]
 fn using_this_in_root_fails() {
-    utils::execute(
+    let output = utils::execute(
         r#"
         print this;
     "#,
     );
+
+    assert_eq!(output[0], "[line 2] UnexpectedTokenError: Cannot use `this` outside of a method.");
 }
 
 #[test]
-#[should_panic(expected = "UnexpectedTokenError: Cannot use `this` outside of a method.")]
 fn using_this_in_non_method_fails() {
-    utils::execute(
+    let output = utils::execute(
         r#"
         fun thisShouldFail() {
             print this;
         }
     "#,
     );
+
+    assert_eq!(output[0], "[line 3] UnexpectedTokenError: Cannot use `this` outside of a method.");
 }
 
 #[test]
@@ -228,9 +257,8 @@ fn calling_init_returns_the_same_instance() {
 }
 
 #[test]
-#[should_panic(expected = "UnexpectedTokenError: Cannot use `return` on an initializer.")]
 fn return_from_init_throws_an_error() {
-    utils::execute(
+    let output = utils::execute(
         r#"
         class DeepThought {
             init() {
@@ -239,6 +267,8 @@ fn return_from_init_throws_an_error() {
         }
     "#,
     );
+
+    assert_eq!(output[0], "[line 4] UnexpectedTokenError: Cannot use `return` on an initializer.");
 }
 
 #[test]
@@ -0,0 +1,164 @@
+extern crate rlox;
+mod utils;
+use utils::execute;
+
+#[test]
+fn print_uses_to_string_method() {
+    let output = execute(
+        r#"
+        class Point {
+            init(x, y) {
+                this.x = x;
+                this.y = y;
+            }
+
+            toString() {
+                return "(" + this.x + ", " + this.y + ")";
+            }
+        }
+
+        print Point(1, 2);
+    "#,
+    );
+
+    assert_eq!(output[0], "(1, 2)");
+}
+
+#[test]
+fn to_string_is_inherited() {
+    let output = execute(
+        r#"
+        class Animal {
+            toString() {
+                return "an animal";
+            }
+        }
+
+        class Dog < Animal {}
+
+        print Dog();
+    "#,
+    );
+
+    assert_eq!(output[0], "an animal");
+}
+
+#[test]
+fn interpolation_and_str_use_to_string() {
+    let output = execute(
+        r#"
+        class Money {
+            init(amount) {
+                this.amount = amount;
+            }
+
+            toString() {
+                return "$" + this.amount;
+            }
+        }
+
+        var price = Money(5);
+        print "costs ${price}";
+        print str(price);
+    "#,
+    );
+
+    assert_eq!(output[0], "costs $5");
+    assert_eq!(output[1], "$5");
+}
+
+#[test]
+fn instances_without_to_string() {
+    let output = execute(
+        r#"
+        class Something {}
+        print str(Something());
+    "#,
+    );
+
+    assert_eq!(output[0], "instance of <Something>");
+}
+
+#[test]
+fn to_string_must_return_a_string() {
+    let output = execute(
+        r#"
+        class Something {
+            toString() {
+                return 42;
+            }
+        }
+
+        print Something();
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 8] `toString` of class `Something` must take no arguments and return a string."
+    );
+
+    let conversions = vec![
+        "var s = \"${Something()}\";",
+        "var s = \"a\" + Something();",
+        "str(Something());",
+    ];
+
+    for conversion in conversions {
+        let code = format!("class Something {{ toString(a) {{}} }}\n{}", conversion);
+        let result = rlox::run_string(code);
+        let report = &result.error_reports()[0];
+
+        assert_eq!(report.line, Some(2), "{}", conversion);
+        assert!(report.message.contains("toString"), "{}", conversion);
+    }
+}
+
+#[test]
+fn plus_with_a_string_converts_the_other_side() {
+    let output = execute(
+        r#"
+        print "count: " + 3;
+        print 3 + " apples";
+        print "is " + true + " or " + nil;
+    "#,
+    );
+
+    assert_eq!(output[0], "count: 3");
+    assert_eq!(output[1], "3 apples");
+    assert_eq!(output[2], "is true or nil");
+}
+
+#[test]
+fn plus_without_strings_still_requires_numbers() {
+    let output = execute("print true + 1;");
+
+    assert!(output[0].ends_with("Both sides of an addition must be either strings or numbers"));
+}
+
+#[test]
+fn number_formatting() {
+    let output = execute(
+        r#"
+        print 3;
+        print 3.0;
+        print 2.5;
+        print str(10 / 4);
+
+        var big = 1;
+        for (var i = 0; i < 400; i = i + 1) {
+            big = big * 10;
+        }
+
+        print big;
+        print -big;
+    "#,
+    );
+
+    assert_eq!(output[0], "3");
+    assert_eq!(output[1], "3");
+    assert_eq!(output[2], "2.5");
+    assert_eq!(output[3], "2.5");
+    assert_eq!(output[4], "Infinity");
+    assert_eq!(output[5], "-Infinity");
+}
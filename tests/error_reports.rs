@@ -0,0 +1,151 @@
+extern crate rlox;
+
+use std::io::Cursor;
+
+use rlox::{ErrorReport, RunOptions, StackFrame};
+
+fn report(
+    kind: &str,
+    variant: &str,
+    message: &str,
+    line: usize,
+    column: usize,
+    stack: Option<Vec<StackFrame>>,
+) -> ErrorReport {
+    ErrorReport {
+        kind: kind.to_string(),
+        variant: variant.to_string(),
+        message: message.to_string(),
+        file: None,
+        line: Some(line),
+        column: Some(column),
+        stack,
+    }
+}
+
+fn frame(function: &str, line: usize) -> StackFrame {
+    StackFrame {
+        function: function.to_string(),
+        line,
+    }
+}
+
+#[test]
+fn errors_of_every_kind() {
+    let result = rlox::run_string("var s = \"a\";\nvar x = @;".to_string());
+    assert_eq!(
+        result.error_reports(),
+        vec![report("scanner", "ScannerError", "Invalid character: @", 2, 9, None)]
+    );
+
+    let result = rlox::run_string("print (1;".to_string());
+    assert_eq!(
+        result.error_reports(),
+        vec![
+            report(
+                "parser",
+                "UnexpectedTokenError",
+                "Expected ')' after expression. ;",
+                1,
+                9,
+                None,
+            ),
+        ]
+    );
+
+    let result = rlox::run_string("fun f() {\n  print this;\n}".to_string());
+    assert_eq!(
+        result.error_reports(),
+        vec![
+            report(
+                "resolver",
+                "ThisOutsideMethod",
+                "Cannot use `this` outside of a method.",
+                2,
+                9,
+                None,
+            ),
+        ]
+    );
+    assert_eq!(result.exit_code, 65);
+}
+
+#[test]
+fn runtime_errors_have_a_stack() {
+    let result = rlox::run_string(
+        r#"fun inner(x) {
+  return x + nil;
+}
+fun outer() { return inner(1); }
+print "before";
+outer();"#.to_string(),
+    );
+
+    assert_eq!(result.output, "before\n");
+    assert_eq!(result.exit_code, 70);
+    assert_eq!(
+        result.error_reports(),
+        vec![
+            report(
+                "runtime",
+                "PlusTypeError",
+                "Both sides of an addition must be either strings or numbers",
+                2,
+                12,
+                Some(vec![frame("inner", 4), frame("outer", 6)]),
+            ),
+        ]
+    );
+    assert_eq!(
+        result.to_string(),
+        "[line 2] Both sides of an addition must be either strings or numbers"
+    );
+}
+
+#[test]
+fn exiting_isnt_an_error() {
+    let result = rlox::run_string("print 1;\nexit(3);".to_string());
+
+    assert!(result.errors.is_empty());
+    assert_eq!(result.exit_code, 3);
+    assert_eq!(result.to_json(), r#"{"errors":[],"exit_code":3,"output":"1\n"}"#);
+}
+
+#[test]
+fn json_errors() {
+    let mut output: Vec<u8> = Vec::new();
+    let mut errors: Vec<u8> = Vec::new();
+    let mut input = Cursor::new(Vec::new());
+
+    let exit_code = rlox::run_source_with_json_errors(
+        Some("script.lox"),
+        "print 1;\nprint undefined;".to_string(),
+        RunOptions::default(),
+        &mut output,
+        &mut errors,
+        &mut input,
+    );
+
+    assert_eq!(exit_code, 70);
+    assert_eq!(String::from_utf8(output).unwrap(), "1\n");
+    assert_eq!(
+        String::from_utf8(errors).unwrap(),
+        "{\"kind\":\"runtime\",\"variant\":\"UndefinedVariable\",\
+         \"message\":\"Undefined variable `undefined`\",\"file\":\"script.lox\",\
+         \"line\":2,\"column\":7,\"stack\":[]}\n"
+    );
+}
+
+#[test]
+fn errors_of_natives_are_on_the_line_of_the_call() {
+    let result = rlox::run_string("fun f() {\n  readFile(\"a.txt\");\n}\nf();".to_string());
+    let reports = result.error_reports();
+
+    assert_eq!(reports[0].variant, "PermissionDenied");
+    assert_eq!(reports[0].line, Some(2));
+    assert_eq!(reports[0].column, None);
+    assert_eq!(
+        reports[0].stack,
+        Some(vec![frame("readFile", 2), frame("f", 4)])
+    );
+}
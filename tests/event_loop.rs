@@ -0,0 +1,223 @@
+extern crate rlox;
+mod utils;
+
+use std::rc::Rc;
+
+use rlox::{Clock, ManualClock};
+use utils::execute_with_clock as execute;
+
+#[test]
+fn timeouts_run_after_the_program_in_order() {
+    let clock = Rc::new(ManualClock::new(0.0));
+    let output = execute(
+        r#"
+        setTimeout(fun () { print "second " + str(clock()); }, 2000);
+        setTimeout(fun () { print "first " + str(clock()); }, 1000);
+        setTimeout(fun () { print "also first " + str(clock()); }, 1000);
+        setTimeout(fun () { print "next turn " + str(clock()); });
+        print "program";
+    "#,
+        clock.clone(),
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "program",
+            "next turn 0",
+            "first 1",
+            "also first 1",
+            "second 2",
+        ]
+    );
+    assert_eq!(clock.now(), 2000.0);
+}
+
+#[test]
+fn intervals_repeat_until_cleared() {
+    let output = execute(
+        r#"
+        var ticks = 0;
+        var interval;
+
+        interval = setInterval(fun () {
+            ticks = ticks + 1;
+            print "tick " + str(clock());
+            if (ticks == 3) clearInterval(interval);
+        }, 1000);
+
+        var cancelled = setTimeout(fun () { print "never"; }, 1500);
+        setTimeout(fun () { clearTimeout(cancelled); }, 1200);
+    "#,
+        Rc::new(ManualClock::new(0.0)),
+    );
+
+    assert_eq!(output, vec!["tick 1", "tick 2", "tick 3"]);
+}
+
+#[test]
+fn nested_timers() {
+    let output = execute(
+        r#"
+        setTimeout(fun () {
+            print "outer";
+            setTimeout(fun () { print "inner " + str(clock()); }, 1000);
+        }, 1000);
+
+        setTimeout(fun () { print "between " + str(clock()); }, 1500);
+    "#,
+        Rc::new(ManualClock::new(0.0)),
+    );
+
+    assert_eq!(output, vec!["outer", "between 1.5", "inner 2"]);
+}
+
+#[test]
+fn promises() {
+    let output = execute(
+        r#"
+        var promise = sleep(3000);
+        print promise;
+
+        promise
+            .then(fun () { print "slept " + str(clock()); return 1; })
+            .then(fun (value) { print value + 1; return sleep(1000); })
+            .then(fun (value) { print str(value) + " " + str(clock()); });
+    "#,
+        Rc::new(ManualClock::new(0.0)),
+    );
+
+    assert_eq!(output, vec!["<promise>", "slept 3", "2", "nil 4"]);
+}
+
+#[test]
+fn async_functions() {
+    let output = execute(
+        r#"
+        fun delayed(value, ms) {
+            await sleep(ms);
+            return value;
+        }
+
+        fun main() {
+            print "start";
+            var a = await delayed("a", 2000);
+            print a + " " + str(clock());
+
+            for (ms in [1000, 1000]) {
+                await sleep(ms);
+                print clock();
+            }
+
+            var plain = await 10;
+            return plain * 2;
+        }
+
+        main().then(fun (result) { print result; });
+        print "after main";
+    "#,
+        Rc::new(ManualClock::new(0.0)),
+    );
+
+    assert_eq!(
+        output,
+        vec!["start", "after main", "a 2", "3", "4", "20"]
+    );
+}
+
+#[test]
+fn async_functions_interleave() {
+    let output = execute(
+        r#"
+        fun worker(name, ms) {
+            for (i in [1, 2]) {
+                await sleep(ms);
+                print name + " " + str(i);
+            }
+        }
+
+        worker("slow", 3000);
+        worker("fast", 2000);
+        worker("yielding", 0);
+    "#,
+        Rc::new(ManualClock::new(0.0)),
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "yielding 1",
+            "yielding 2",
+            "fast 1",
+            "slow 1",
+            "fast 2",
+            "slow 2",
+        ]
+    );
+}
+
+#[test]
+fn event_loop_errors() {
+    let output = execute(
+        r#"
+        setTimeout(fun () { print "runs"; }, 10);
+        setTimeout(fun () { nil + 1; }, 20);
+    "#,
+        Rc::new(ManualClock::new(0.0)),
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 3] Both sides of an addition must be either strings or numbers"
+    );
+
+    let output = execute(
+        "setTimeout(fun (a) {}, 10);",
+        Rc::new(ManualClock::new(0.0)),
+    );
+    assert_eq!(
+        output[0],
+        "Invalid argument for `setTimeout`, expected a function without parameters."
+    );
+
+    let output = execute("sleep(-1);", Rc::new(ManualClock::new(0.0)));
+    assert_eq!(
+        output[0],
+        "Invalid argument for `sleep`, expected a delay of zero or more milliseconds."
+    );
+}
+
+#[test]
+fn run_string_drains_the_event_loop() {
+    let output = utils::execute(
+        r#"
+        setTimeout(fun () { print "done"; }, 1);
+    "#,
+    );
+
+    assert_eq!(output, vec!["done"]);
+}
+
+#[test]
+fn await_at_the_top_level() {
+    let output = utils::execute("await sleep(1);");
+
+    assert_eq!(output[0], "[line 1] UnexpectedTokenError: Cannot use `await` at the top level.");
+}
+
+#[test]
+fn yield_and_await_in_the_same_function() {
+    let output = utils::execute(
+        r#"
+        fun mixed() {
+            yield 1;
+            await sleep(1);
+        }
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 4] UnexpectedTokenError: Cannot use both `yield` and `await` in the same function."
+    );
+}
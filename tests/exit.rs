@@ -0,0 +1,61 @@
+extern crate rlox;
+mod utils;
+
+use utils::execute;
+
+#[test]
+fn exit_stops_the_program() {
+    let output = execute(
+        r#"
+        fun stop() {
+            print "stopping";
+            exit();
+        }
+
+        print "before";
+        stop();
+        print "after";
+    "#,
+    );
+
+    assert_eq!(output, vec!["before", "stopping"]);
+}
+
+#[test]
+fn exit_stops_the_event_loop() {
+    let output = execute(
+        r#"
+        setTimeout(fun () { print "never"; }, 20);
+        setTimeout(fun () { print "first"; exit(1); }, 10);
+    "#,
+    );
+
+    assert_eq!(output, vec!["first"]);
+}
+
+#[test]
+fn exit_codes() {
+    let exit_code = |code: &str| utils::execute_with_input(code, "").exit_code;
+
+    assert_eq!(exit_code("print 1;"), 0);
+    assert_eq!(exit_code("exit(3); print 1;"), 3);
+    assert_eq!(exit_code("print (;"), 65);
+    assert_eq!(exit_code("\"unterminated"), 65);
+    assert_eq!(exit_code("nil + 1;"), 70);
+    assert_eq!(exit_code("exit(256);"), 70);
+}
+
+#[test]
+fn exit_is_not_reported_as_an_error() {
+    let output = utils::execute_with_input("print 1; exit(2);", "");
+
+    assert_eq!(output.stdout, "1\n");
+    assert_eq!(output.stderr, "");
+    assert_eq!(output.exit_code, 2);
+
+    let output = execute("exit(1.5);");
+    assert_eq!(
+        output[0],
+        "Invalid argument for `exit`, expected an exit code between 0 and 255."
+    );
+}
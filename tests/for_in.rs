@@ -0,0 +1,189 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn for_in_list() {
+    let output = utils::execute(
+        r#"
+        var total = 0;
+
+        for (n in [1, 2, 3]) {
+            total = total + n;
+        }
+
+        print total;
+
+        for (var item in ["a", nil, true]) print item;
+    "#,
+    );
+
+    assert_eq!(output[0], "6");
+    assert_eq!(output[1], "a");
+    assert_eq!(output[2], "nil");
+    assert_eq!(output[3], "true");
+}
+
+#[test]
+fn for_in_list_sees_changes_made_by_the_body() {
+    let output = utils::execute(
+        r#"
+        var list = [1, 2, 3];
+
+        for (n in list) {
+            if (n == 1) list[1] = 20;
+            print n;
+        }
+    "#,
+    );
+
+    assert_eq!(output, vec!["1", "20", "3"]);
+}
+
+#[test]
+fn for_in_string() {
+    let output = utils::execute(
+        r#"
+        for (c in "héy") print c;
+        for (c in "") print "never";
+    "#,
+    );
+
+    assert_eq!(output, vec!["h", "é", "y"]);
+}
+
+#[test]
+fn for_in_iterator_protocol() {
+    let output = utils::execute(
+        r#"
+        class Countdown {
+            init(from) {
+                this.current = from;
+            }
+
+            hasNext() {
+                return this.current > 0;
+            }
+
+            next() {
+                this.current = this.current - 1;
+                return this.current + 1;
+            }
+        }
+
+        class Range {
+            init(start, end) {
+                this.start = start;
+                this.end = end;
+            }
+
+            iterator() {
+                return RangeIterator(this.start, this.end);
+            }
+        }
+
+        class RangeIterator < Countdown {
+            init(start, end) {
+                this.current = start;
+                this.end = end;
+            }
+
+            hasNext() {
+                return this.current < this.end;
+            }
+
+            next() {
+                this.current = this.current + 1;
+                return this.current - 1;
+            }
+        }
+
+        for (n in Countdown(3)) print n;
+
+        var range = Range(0, 2);
+        for (n in range) print n;
+        for (n in range) print n;
+    "#,
+    );
+
+    assert_eq!(output, vec!["3", "2", "1", "0", "1", "0", "1"]);
+}
+
+#[test]
+fn for_in_scopes_the_variable_per_iteration() {
+    let output = utils::execute(
+        r#"
+        var closures = [nil, nil, nil];
+        var i = 0;
+
+        for (n in [1, 2, 3]) {
+            closures[i] = fun () { return n; };
+            i = i + 1;
+        }
+
+        var n = "outer";
+
+        print closures[0]();
+        print closures[2]();
+        print n;
+    "#,
+    );
+
+    assert_eq!(output[0], "1");
+    assert_eq!(output[1], "3");
+    assert_eq!(output[2], "outer");
+}
+
+#[test]
+fn for_in_returns_from_the_enclosing_function() {
+    let output = utils::execute(
+        r#"
+        fun first_even(list) {
+            for (n in list) {
+                if (n % 2 == 0) return n;
+            }
+
+            return nil;
+        }
+
+        print first_even([1, 3, 4, 6]);
+        print first_even([1]);
+    "#,
+    );
+
+    assert_eq!(output[0], "4");
+    assert_eq!(output[1], "nil");
+}
+
+#[test]
+fn for_in_on_non_iterable() {
+    let output = utils::execute(
+        r#"
+        class Empty {}
+
+        for (x in Empty()) print x;
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 4] Can only iterate over lists, strings and instances with `iterator()`, or `hasNext()` and `next()` methods"
+    );
+
+    let output = utils::execute("for (x in 1) print x;");
+    assert_eq!(
+        output[0],
+        "[line 1] Can only iterate over lists, strings and instances with `iterator()`, or `hasNext()` and `next()` methods"
+    );
+}
+
+#[test]
+fn c_style_for_still_works() {
+    let output = utils::execute(
+        r#"
+        var in = 2;
+        for (var i = 0; i < in; i = i + 1) print i;
+    "#,
+    );
+
+    assert_eq!(output, vec!["0", "1"]);
+}
@@ -18,9 +18,10 @@ fn function_parameters() {
 }
 
 #[test]
-#[should_panic(expected = "UnexpectedTokenError: Cannot use `return` at the top level.")]
 fn return_on_top_level() {
-    execute("return 42;");
+    let output = execute("return 42;");
+
+    assert_eq!(output[0], "[line 1] UnexpectedTokenError: Cannot use `return` at the top level.");
 }
 
 #[test]
@@ -38,3 +39,138 @@ fn compared_by_identity() {
 
     assert_eq!(output[0], "true");
 }
+
+#[test]
+fn default_parameter_values() {
+    let output = execute(
+        r#"
+      fun greet(name, greeting = "Hello", punctuation = greeting == "Hello" and "!" or ".") {
+          return greeting + ", " + name + punctuation;
+      }
+
+      print greet("Ada");
+      print greet("Ada", "Bye");
+      print greet("Ada", "Bye", "?");
+    "#,
+    );
+
+    assert_eq!(output[0], "Hello, Ada!");
+    assert_eq!(output[1], "Bye, Ada.");
+    assert_eq!(output[2], "Bye, Ada?");
+
+    let output = execute("fun f(a = 1, b) {}");
+    assert_eq!(
+        output[0],
+        "[line 1] Missing default value for `b`, which follows an optional parameter."
+    );
+}
+
+#[test]
+fn rest_parameters() {
+    let output = execute(
+        r#"
+      fun count(first, ...others) {
+          return len(others);
+      }
+
+      print count(1);
+      print count(1, 2, 3);
+    "#,
+    );
+
+    assert_eq!(output[0], "0");
+    assert_eq!(output[1], "2");
+}
+
+#[test]
+fn spread_arguments() {
+    let output = execute(
+        r#"
+      fun sum(a, b, c) {
+          return a + b + c;
+      }
+
+      fun forward(...args) {
+          return sum(...args);
+      }
+
+      print sum(1, ...[2, 3]);
+      print forward(4, 5, 6);
+    "#,
+    );
+
+    assert_eq!(output[0], "6");
+    assert_eq!(output[1], "15");
+}
+
+#[test]
+fn arity_errors() {
+    let output = execute(
+        r#"
+      fun optional(a, b = 1, c = 2) {}
+      optional(1, 2, 3, 4);
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 3] Function arity error, expected 1 to 3 arguments but got 4"
+    );
+
+    let output = execute(
+        r#"
+      fun rest(a, ...b) {}
+      rest();
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 3] Function arity error, expected at least 1 argument but got 0"
+    );
+
+    let output = execute(
+        r#"
+      fun single(a) {}
+      single();
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 3] Function arity error, expected 1 argument but got 0"
+    );
+}
+
+#[test]
+fn many_arguments() {
+    let parameters: Vec<String> = (0..100).map(|i| format!("p{}", i)).collect();
+    let arguments: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+
+    let output = execute(&format!(
+        "fun last({}) {{ return p99; }} print last({});",
+        parameters.join(", "),
+        arguments.join(", ")
+    ));
+
+    assert_eq!(output[0], "99");
+}
+
+#[test]
+fn inherited_initializer_arity() {
+    let output = execute(
+        r#"
+      class A {
+          init(value) {
+              this.value = value;
+          }
+      }
+
+      class B < A {}
+
+      print B(42).value;
+    "#,
+    );
+
+    assert_eq!(output[0], "42");
+}
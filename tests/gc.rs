@@ -0,0 +1,156 @@
+extern crate rlox;
+mod utils;
+use utils::execute;
+
+#[test]
+fn reclaims_instances_pointing_at_each_other() {
+    let output = execute(
+        r#"
+        class Node {}
+
+        fun makeCycle() {
+            var a = Node();
+            var b = Node();
+            a.other = b;
+            b.other = a;
+        }
+
+        makeCycle();
+        print gc();
+        print gc();
+    "#,
+    );
+
+    assert_eq!(output[0], "2");
+    assert_eq!(output[1], "0");
+}
+
+#[test]
+fn reclaims_closure_stored_in_instance() {
+    let output = execute(
+        r#"
+        class Counter {
+            init() {
+                var self = this;
+
+                fun get() {
+                    return self;
+                }
+
+                this.get = get;
+            }
+        }
+
+        Counter();
+        print gc();
+    "#,
+    );
+
+    // The instance, the closure, the environment of `init` and the one binding `this`
+    assert_eq!(output[0], "4");
+}
+
+#[test]
+fn keeps_reachable_cycles_alive() {
+    let output = execute(
+        r#"
+        class Node {}
+
+        var a = Node();
+        var b = Node();
+        a.other = b;
+        b.other = a;
+        a.name = "a";
+
+        print gc();
+        print b.other.name;
+    "#,
+    );
+
+    assert_eq!(output[0], "0");
+    assert_eq!(output[1], "a");
+}
+
+#[test]
+fn keeps_values_referenced_from_the_running_function() {
+    let output = execute(
+        r#"
+        class Node {}
+
+        fun run() {
+            var a = Node();
+            a.self = a;
+            a.name = "still here";
+            gc();
+            return a.self.name;
+        }
+
+        print run();
+    "#,
+    );
+
+    assert_eq!(output[0], "still here");
+}
+
+#[test]
+fn methods_bind_to_the_same_instance() {
+    let output = execute(
+        r#"
+        class Box {
+            fill() {
+                this.content = 42;
+            }
+        }
+
+        var box = Box();
+        box.fill();
+        print box.content;
+    "#,
+    );
+
+    assert_eq!(output[0], "42");
+}
+
+#[test]
+fn collects_automatically() {
+    let before = rlox::gc_stats().collections;
+
+    execute(
+        r#"
+        class Node {}
+
+        for (var i = 0; i < 2000; i = i + 1) {
+            var a = Node();
+            a.self = a;
+        }
+    "#,
+    );
+
+    let stats = rlox::gc_stats();
+    assert!(stats.collections > before);
+    assert!(stats.reclaimed > 0);
+}
+
+#[test]
+fn reclaims_generator_referencing_itself() {
+    let output = execute(
+        r#"
+        fun makeGenerator() {
+            var generator;
+
+            fun numbers() {
+                yield generator;
+            }
+
+            generator = numbers();
+            generator.next();
+        }
+
+        makeGenerator();
+        print gc();
+    "#,
+    );
+
+    // The generator, the function, the environment of `makeGenerator` and the one of the call
+    assert_eq!(output[0], "4");
+}
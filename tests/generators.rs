@@ -0,0 +1,355 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn generator_next() {
+    let output = utils::execute(
+        r#"
+        fun numbers() {
+            yield 1;
+            yield 2;
+            yield;
+        }
+
+        var generator = numbers();
+        print generator;
+        print generator.next();
+        print generator.next();
+        print generator.next();
+        print generator.done();
+        print generator.next();
+        print generator.done();
+    "#,
+    );
+
+    assert_eq!(output[0], "<generator numbers>");
+    assert_eq!(output[1], "1");
+    assert_eq!(output[2], "2");
+    assert_eq!(output[3], "nil");
+    assert_eq!(output[4], "false");
+    assert_eq!(output[5], "nil");
+    assert_eq!(output[6], "true");
+}
+
+#[test]
+fn generator_is_lazy() {
+    let output = utils::execute(
+        r#"
+        fun naturals() {
+            print "started";
+            var n = 0;
+
+            while (true) {
+                yield n;
+                n = n + 1;
+            }
+        }
+
+        var generator = naturals();
+        print "created";
+        print generator.next();
+        print generator.next();
+        print generator.next();
+    "#,
+    );
+
+    assert_eq!(output, vec!["created", "started", "0", "1", "2"]);
+}
+
+#[test]
+fn generator_for_in() {
+    let output = utils::execute(
+        r#"
+        fun range(start, end, step = 1) {
+            for (var i = start; i < end; i = i + step) yield i;
+        }
+
+        fun evens(numbers) {
+            for (n in numbers) {
+                if (n % 2 == 0) yield n;
+            }
+        }
+
+        for (n in evens(range(0, 7))) print n;
+
+        var generator = range(0, 2);
+        print generator.hasNext();
+        print generator.hasNext();
+        print generator.next();
+        print generator.next();
+        print generator.hasNext();
+    "#,
+    );
+
+    assert_eq!(
+        output,
+        vec!["0", "2", "4", "6", "true", "true", "0", "1", "false"]
+    );
+}
+
+#[test]
+fn generator_scopes() {
+    let output = utils::execute(
+        r#"
+        fun letters() {
+            var prefix = "-";
+
+            for (c in "ab") {
+                var upper = c + "!";
+                yield prefix + upper;
+            }
+
+            {
+                var prefix = "+";
+                yield prefix;
+            }
+
+            yield prefix;
+        }
+
+        for (letter in letters()) print letter;
+
+        var closures = [nil, nil];
+
+        fun capture() {
+            for (i in [0, 1]) {
+                closures[i] = fun () { return i; };
+                yield i;
+            }
+        }
+
+        for (i in capture()) {}
+        print closures[0]();
+        print closures[1]();
+    "#,
+    );
+
+    assert_eq!(output, vec!["-a!", "-b!", "+", "-", "0", "1"]);
+}
+
+#[test]
+fn generator_match() {
+    let output = utils::execute(
+        r#"
+        fun describe(values) {
+            for (value in values) {
+                match (value) {
+                    1 => yield "one";
+                    n if n > 10 => {
+                        yield "big";
+                        yield n;
+                    }
+                    _ => yield "other";
+                }
+            }
+        }
+
+        for (description in describe([1, 20, 3])) print description;
+    "#,
+    );
+
+    assert_eq!(output, vec!["one", "big", "20", "other"]);
+}
+
+#[test]
+fn generator_return_finishes_it() {
+    let output = utils::execute(
+        r#"
+        fun until_negative(values) {
+            for (value in values) {
+                if (value < 0) return;
+                yield value;
+            }
+        }
+
+        for (value in until_negative([1, 2, -1, 3])) print value;
+    "#,
+    );
+
+    assert_eq!(output, vec!["1", "2"]);
+}
+
+#[test]
+fn generator_methods() {
+    let output = utils::execute(
+        r#"
+        class Tree {
+            init(value, left, right) {
+                this.value = value;
+                this.left = left;
+                this.right = right;
+            }
+
+            iterator() {
+                if (this.left != nil) {
+                    for (value in this.left) yield value;
+                }
+
+                yield this.value;
+
+                if (this.right != nil) {
+                    for (value in this.right) yield value;
+                }
+            }
+        }
+
+        var tree = Tree(2, Tree(1, nil, nil), Tree(3, nil, nil));
+
+        for (value in tree) print value;
+        for (value in tree) print value;
+    "#,
+    );
+
+    assert_eq!(output, vec!["1", "2", "3", "1", "2", "3"]);
+}
+
+#[test]
+fn coroutines() {
+    let output = utils::execute(
+        r#"
+        fun accumulator() {
+            var total = 0;
+
+            while (true) {
+                var value = yield total;
+                total = total + value;
+            }
+        }
+
+        var sum = accumulator();
+        print sum.resume();
+        print sum.resume(10);
+        print sum.resume(5);
+
+        fun echo() {
+            var received = nil;
+
+            while (received != "stop") {
+                received = yield "got " + str(received);
+            }
+        }
+
+        var coroutine = echo();
+        print coroutine.resume();
+        print coroutine.resume("a");
+        print coroutine.resume("stop");
+        print coroutine.done();
+    "#,
+    );
+
+    assert_eq!(
+        output,
+        vec!["0", "10", "15", "got nil", "got a", "nil", "true"]
+    );
+}
+
+#[test]
+fn cooperative_tasks() {
+    let output = utils::execute(
+        r#"
+        fun task(name, steps) {
+            for (var i = 1; i <= steps; i = i + 1) {
+                print name + " " + str(i);
+                yield;
+            }
+        }
+
+        var tasks = [task("a", 2), task("b", 3)];
+        var running = 2;
+
+        while (running > 0) {
+            running = 0;
+
+            for (t in tasks) {
+                t.resume();
+                if (!t.done()) running = running + 1;
+            }
+        }
+    "#,
+    );
+
+    assert_eq!(output, vec!["a 1", "b 1", "a 2", "b 2", "b 3"]);
+}
+
+#[test]
+fn generator_errors() {
+    let output = utils::execute(
+        r#"
+        var generator;
+
+        fun recursive() {
+            yield generator.next();
+        }
+
+        generator = recursive();
+        generator.next();
+    "#,
+    );
+
+    assert_eq!(output[0], "Generator `recursive` is already running");
+
+    let output = utils::execute(
+        r#"
+        fun failing() {
+            yield 1;
+            yield nil + 1;
+        }
+
+        var generator = failing();
+        print generator.next();
+        generator.next();
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 4] Both sides of an addition must be either strings or numbers"
+    );
+
+    let output = utils::execute(
+        r#"
+        fun numbers() { yield 1; }
+        numbers().previous();
+    "#,
+    );
+
+    assert_eq!(output[0], "[line 3] Undefined property `previous`.");
+}
+
+#[test]
+fn yield_at_the_top_level() {
+    let output = utils::execute("yield 1;");
+
+    assert_eq!(output[0], "[line 1] UnexpectedTokenError: Cannot use `yield` at the top level.");
+}
+
+#[test]
+fn yield_inside_an_expression() {
+    let output = utils::execute(
+        r#"
+        fun numbers() {
+            print yield 1;
+        }
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 3] UnexpectedTokenError: `yield` can only be used as a statement, or as the value of a variable declaration or assignment."
+    );
+}
+
+#[test]
+fn yield_in_an_initializer() {
+    let output = utils::execute(
+        r#"
+        class Numbers {
+            init() {
+                yield 1;
+            }
+        }
+    "#,
+    );
+
+    assert_eq!(output[0], "[line 4] UnexpectedTokenError: Cannot use `yield` on an initializer.");
+}
@@ -89,15 +89,15 @@ fn referencing_super_without_access_fails() {
 }
 
 #[test]
-#[should_panic(expected = "UnexpectedTokenError: Cannot use `super` outside of a method.")]
 fn using_super_outside_of_method_fails() {
-    execute("super.doSomething();");
+    let output = execute("super.doSomething();");
+
+    assert_eq!(output[0], "[line 1] UnexpectedTokenError: Cannot use `super` outside of a method.");
 }
 
 #[test]
-#[should_panic(expected = "UnexpectedTokenError: Cannot use `super` without a superclass.")]
 fn using_super_without_superclass_fails() {
-    execute(
+    let output = execute(
         r#"
         class Something {
             getValue() {
@@ -106,4 +106,9 @@ fn using_super_without_superclass_fails() {
         }
     "#,
     );
+
+    assert_eq!(
+        output[0],
+        "[line 4] UnexpectedTokenError: Cannot use `super` without a superclass."
+    );
 }
@@ -0,0 +1,102 @@
+extern crate rlox;
+mod utils;
+
+use std::env;
+use std::fs;
+
+use rlox::{Capabilities, Capability};
+
+#[test]
+fn io_is_denied_by_default() {
+    let denied = vec![
+        ("readLine();", "readLine", "read input"),
+        ("input(\"> \");", "input", "read input"),
+        ("readFile(\"a.txt\");", "readFile", "read files"),
+        ("listDir(\".\");", "listDir", "read files"),
+        ("writeFile(\"a.txt\", \"\");", "writeFile", "write files"),
+        ("getenv(\"HOME\");", "getenv", "read environment variables"),
+        ("args();", "args", "read command line arguments"),
+    ];
+
+    for (code, function, capability) in denied {
+        let output = utils::execute(code);
+        assert_eq!(
+            output[0],
+            format!(
+                "Permission denied for `{}`, the program is not allowed to {}.",
+                function, capability
+            )
+        );
+    }
+}
+
+#[test]
+fn files() {
+    let dir = env::temp_dir().join(format!("rlox-io-test-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let dir_name = dir.to_string_lossy().replace("\\", "/");
+
+    let output = utils::execute_with_capabilities(
+        &format!(
+            r#"
+            var dir = "{}";
+            writeFile(dir + "/b.txt", "second");
+            writeFile(dir + "/a.txt", "first line
+second line");
+            print readFile(dir + "/a.txt");
+            print listDir(dir);
+        "#,
+            dir_name
+        ),
+        Capabilities::all(),
+        vec![],
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        output,
+        vec!["first line", "second line", "[\"a.txt\", \"b.txt\"]"]
+    );
+}
+
+#[test]
+fn capabilities_are_checked_separately() {
+    let output = utils::execute_with_capabilities(
+        "writeFile(\"never.txt\", \"\");",
+        Capabilities::none().allow(Capability::ReadFiles),
+        vec![],
+    );
+
+    assert_eq!(
+        output[0],
+        "Permission denied for `writeFile`, the program is not allowed to write files."
+    );
+
+    let output = utils::execute_with_capabilities(
+        "readFile(\"rlox-file-that-does-not-exist.txt\");",
+        Capabilities::none().allow(Capability::ReadFiles),
+        vec![],
+    );
+
+    assert!(output[0].starts_with("`readFile` failed: "));
+}
+
+#[test]
+fn environment_and_args() {
+    env::set_var("RLOX_IO_TEST_VARIABLE", "value");
+
+    let output = utils::execute_with_capabilities(
+        r#"
+        print getenv("RLOX_IO_TEST_VARIABLE");
+        print getenv("RLOX_IO_TEST_UNSET_VARIABLE");
+        print args();
+    "#,
+        Capabilities::none()
+            .allow(Capability::Environment)
+            .allow(Capability::Args),
+        vec!["one", "two"],
+    );
+
+    assert_eq!(output, vec!["value", "nil", "[\"one\", \"two\"]"]);
+}
@@ -0,0 +1,143 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn anonymous_function_as_argument() {
+    let output = utils::execute(
+        r#"
+        fun twice(f, x) {
+            return f(f(x));
+        }
+
+        print twice(fun (x) { return x * 2; }, 3);
+    "#,
+    );
+
+    assert_eq!(output[0], "12");
+}
+
+#[test]
+fn anonymous_function_in_variable() {
+    let output = utils::execute(
+        r#"
+        var add = fun (a, b) { return a + b; };
+        print add(1, 2);
+    "#,
+    );
+
+    assert_eq!(output[0], "3");
+}
+
+#[test]
+fn anonymous_function_closes_over_scope() {
+    let output = utils::execute(
+        r#"
+        fun counter() {
+            var count = 0;
+            return fun () {
+                count = count + 1;
+                return count;
+            };
+        }
+
+        var next = counter();
+        next();
+        print next();
+    "#,
+    );
+
+    assert_eq!(output[0], "2");
+}
+
+#[test]
+fn immediately_invoked_anonymous_function() {
+    let output = utils::execute(
+        r#"
+        fun () { print "called"; }();
+    "#,
+    );
+
+    assert_eq!(output[0], "called");
+}
+
+#[test]
+fn arrow_function_with_expression_body() {
+    let output = utils::execute(
+        r#"
+        var double = (x) => x * 2;
+        print double(21);
+    "#,
+    );
+
+    assert_eq!(output[0], "42");
+}
+
+#[test]
+fn arrow_function_with_block_body() {
+    let output = utils::execute(
+        r#"
+        var greet = (greeting, name) => {
+            print greeting + ", " + name;
+        };
+        greet("Hello", "world");
+    "#,
+    );
+
+    assert_eq!(output[0], "Hello, world");
+}
+
+#[test]
+fn arrow_function_without_parameters() {
+    let output = utils::execute(
+        r#"
+        var answer = () => 42;
+        print answer();
+    "#,
+    );
+
+    assert_eq!(output[0], "42");
+}
+
+#[test]
+fn arrow_function_with_a_block_in_a_default_value() {
+    let output = utils::execute(
+        r#"
+        var call = (f = fun () { return "default"; }) => f();
+        print call();
+        print call(() => { return "given"; });
+    "#,
+    );
+
+    assert_eq!(output[0], "default");
+    assert_eq!(output[1], "given");
+}
+
+#[test]
+fn grouping_is_not_an_arrow_function() {
+    let output = utils::execute(
+        r#"
+        var x = 2;
+        print (x) * 3;
+    "#,
+    );
+
+    assert_eq!(output[0], "6");
+}
+
+#[test]
+fn display_functions() {
+    let output = utils::execute(
+        r#"
+        fun named() {}
+        print named;
+        print fun () {};
+        print (x) => x;
+        print clock;
+    "#,
+    );
+
+    assert_eq!(output[0], "<fn named>");
+    assert_eq!(output[1], "<fn <anonymous>>");
+    assert_eq!(output[2], "<fn <anonymous>>");
+    assert_eq!(output[3], "<native fn>");
+}
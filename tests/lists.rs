@@ -0,0 +1,119 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn list_literals() {
+    let output = utils::execute(
+        r#"
+        var empty = [];
+        var list = [1, "two", [3]];
+        print empty;
+        print list;
+        print len(list);
+    "#,
+    );
+
+    assert_eq!(output[0], "[]");
+    assert_eq!(output[1], "[1, \"two\", [3]]");
+    assert_eq!(output[2], "3");
+}
+
+#[test]
+fn indexing() {
+    let output = utils::execute(
+        r#"
+        var list = [1, 2, 3];
+        list[1] = "two";
+        print list[0];
+        print list[1];
+        print "abc"[2];
+    "#,
+    );
+
+    assert_eq!(output[0], "1");
+    assert_eq!(output[1], "two");
+    assert_eq!(output[2], "c");
+}
+
+#[test]
+fn index_out_of_bounds() {
+    let output = utils::execute("print [1, 2][2];");
+
+    assert_eq!(output[0], "[line 1] Index out of bounds for length 2.");
+}
+
+#[test]
+fn invalid_index() {
+    let output = utils::execute("print [1, 2][0.5];");
+
+    assert_eq!(output[0], "[line 1] Index must be a non-negative integer.");
+}
+
+#[test]
+fn spread_into_list() {
+    let output = utils::execute(
+        r#"
+        var middle = [2, 3];
+        print [1, ...middle, 4];
+    "#,
+    );
+
+    assert_eq!(output[0], "[1, 2, 3, 4]");
+}
+
+#[test]
+fn spread_non_list() {
+    let output = utils::execute("print [...1];");
+
+    assert_eq!(output[0], "[line 1] Only lists can be spread.");
+}
+
+#[test]
+fn compared_by_identity() {
+    let output = utils::execute(
+        r#"
+        var list = [1];
+        var same = list;
+        print list == same;
+        print list == [1];
+    "#,
+    );
+
+    assert_eq!(output[0], "true");
+    assert_eq!(output[1], "false");
+}
+
+#[test]
+fn elements_use_to_string() {
+    let output = utils::execute(
+        r#"
+        class Point {
+            init(x, y) {
+                this.x = x;
+                this.y = y;
+            }
+
+            toString() {
+                return "(${this.x}, ${this.y})";
+            }
+        }
+
+        print [Point(1, 2)];
+    "#,
+    );
+
+    assert_eq!(output[0], "[(1, 2)]");
+}
+
+#[test]
+fn list_containing_itself() {
+    let output = utils::execute(
+        r#"
+        var list = [1];
+        list[0] = list;
+        print list;
+    "#,
+    );
+
+    assert_eq!(output[0], "[[...]]");
+}
@@ -0,0 +1,215 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn match_literals() {
+    let output = utils::execute(
+        r#"
+        fun describe(value) {
+            match (value) {
+                1 => print "one";
+                -1 => print "minus one";
+                "a" => print "letter a";
+                true => print "yes";
+                nil => print "nothing";
+                _ => print "something else";
+            }
+        }
+
+        describe(1);
+        describe(-1);
+        describe("a");
+        describe(true);
+        describe(nil);
+        describe(2);
+        describe("1");
+    "#,
+    );
+
+    assert_eq!(output[0], "one");
+    assert_eq!(output[1], "minus one");
+    assert_eq!(output[2], "letter a");
+    assert_eq!(output[3], "yes");
+    assert_eq!(output[4], "nothing");
+    assert_eq!(output[5], "something else");
+    assert_eq!(output[6], "something else");
+}
+
+#[test]
+fn match_runs_only_the_first_matching_arm() {
+    let output = utils::execute(
+        r#"
+        match (1) {
+            1 => print "first";
+            1 => print "second";
+        }
+
+        match (2) {
+            1 => print "no";
+        }
+
+        print "done";
+    "#,
+    );
+
+    assert_eq!(output[0], "first");
+    assert_eq!(output[1], "done");
+}
+
+#[test]
+fn match_binds_variables() {
+    let output = utils::execute(
+        r#"
+        var x = "outer";
+
+        match (40 + 2) {
+            0 => print "zero";
+            x => {
+                x = x + 1;
+                print x;
+            }
+        }
+
+        print x;
+    "#,
+    );
+
+    assert_eq!(output[0], "43");
+    assert_eq!(output[1], "outer");
+}
+
+#[test]
+fn match_classes() {
+    let output = utils::execute(
+        r#"
+        class Shape {}
+        class Point < Shape {
+            init(x, y) {
+                this.x = x;
+                this.y = y;
+            }
+        }
+        class Circle < Shape {}
+        class Other {}
+
+        fun describe(value) {
+            match (value) {
+                Point p => print "point at " + str(p.x) + ", " + str(p.y);
+                Shape _ => print "some shape";
+                _ => print "not a shape";
+            }
+        }
+
+        describe(Point(1, 2));
+        describe(Circle());
+        describe(Other());
+        describe(Point);
+    "#,
+    );
+
+    assert_eq!(output[0], "point at 1, 2");
+    assert_eq!(output[1], "some shape");
+    assert_eq!(output[2], "not a shape");
+    assert_eq!(output[3], "not a shape");
+}
+
+#[test]
+fn match_guards() {
+    let output = utils::execute(
+        r#"
+        fun sign(n) {
+            match (n) {
+                0 => return "zero";
+                n if n < 0 => return "negative";
+                _ => return "positive";
+            }
+        }
+
+        print sign(0);
+        print sign(-5);
+        print sign(5);
+    "#,
+    );
+
+    assert_eq!(output[0], "zero");
+    assert_eq!(output[1], "negative");
+    assert_eq!(output[2], "positive");
+}
+
+#[test]
+fn match_arms_can_be_separated_by_commas() {
+    let output = utils::execute(
+        r#"
+        match ("b") {
+            "a" => print 1;,
+            "b" => { print 2; },
+            _ => { print 3; }
+        }
+    "#,
+    );
+
+    assert_eq!(output[0], "2");
+}
+
+#[test]
+fn match_is_not_a_reserved_word() {
+    let output = utils::execute(
+        r#"
+        fun match(value) {
+            return value;
+        }
+
+        var matched = match(1);
+        print matched;
+    "#,
+    );
+
+    assert_eq!(output[0], "1");
+}
+
+#[test]
+fn match_on_non_class() {
+    let output = utils::execute(
+        r#"
+        var Point = 1;
+
+        match (1) {
+            Point p => print p;
+        }
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 5] `Point` in a match pattern must be a class"
+    );
+}
+
+#[test]
+fn match_warns_about_unreachable_arms() {
+    let output = utils::execute(
+        r#"
+        match (1) {
+            _ => print "anything";
+            1 => print "one";
+        }
+
+        match (2) {
+            n if n > 5 => print "big";
+            n => print n;
+            _ => print "unreachable";
+        }
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 4] Warning: Unreachable match arm after `_`"
+    );
+    assert_eq!(
+        output[1],
+        "[line 10] Warning: Unreachable match arm after `n`"
+    );
+    assert_eq!(output[2], "anything");
+    assert_eq!(output[3], "2");
+}
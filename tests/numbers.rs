@@ -0,0 +1,142 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn modulo() {
+    let output = utils::execute(
+        r#"
+        print 7 % 3;
+        print -7 % 3;
+        print 7 % -3;
+        print 7.5 % 2;
+    "#,
+    );
+
+    assert_eq!(output[0], "1");
+    assert_eq!(output[1], "2");
+    assert_eq!(output[2], "-2");
+    assert_eq!(output[3], "1.5");
+}
+
+#[test]
+fn integer_division() {
+    let output = utils::execute(
+        r#"
+        print 7 ~/ 2;
+        print -7 ~/ 2;
+        print (-7 ~/ 2) * 2 + -7 % 2;
+    "#,
+    );
+
+    assert_eq!(output[0], "3");
+    assert_eq!(output[1], "-4");
+    assert_eq!(output[2], "-7");
+}
+
+#[test]
+fn exponentiation() {
+    let output = utils::execute(
+        r#"
+        print 2 ** 10;
+        print -2 ** 2;
+        print 2 ** 3 ** 2;
+        print 2 ** -1;
+        print 2 * 3 ** 2;
+    "#,
+    );
+
+    assert_eq!(output[0], "1024");
+    assert_eq!(output[1], "-4");
+    assert_eq!(output[2], "512");
+    assert_eq!(output[3], "0.5");
+    assert_eq!(output[4], "18");
+}
+
+#[test]
+fn division_by_zero() {
+    assert_eq!(utils::execute("print 1 / 0;")[0], "[line 1] Cannot divide by zero");
+    assert_eq!(utils::execute("print 1 % 0;")[0], "[line 1] Cannot divide by zero");
+    assert_eq!(utils::execute("print 1 ~/ 0;")[0], "[line 1] Cannot divide by zero");
+}
+
+#[test]
+fn not_a_number_is_an_error() {
+    let output = utils::execute("print (-8) ** 0.5;");
+
+    assert_eq!(
+        output[0],
+        "[line 1] Operation `**` doesn't produce a valid number"
+    );
+
+    let output = utils::execute("print 1e308 * 10 - 1e308 * 10;");
+
+    assert_eq!(
+        output[0],
+        "[line 1] Operation `-` doesn't produce a valid number"
+    );
+}
+
+#[test]
+fn numeric_literals() {
+    let output = utils::execute(
+        r#"
+        print 0xFF;
+        print 0b1010;
+        print 1_000_000;
+        print 2.5e3;
+    "#,
+    );
+
+    assert_eq!(output[0], "255");
+    assert_eq!(output[1], "10");
+    assert_eq!(output[2], "1000000");
+    assert_eq!(output[3], "2500");
+}
+
+#[test]
+fn formatting() {
+    let output = utils::execute(
+        r#"
+        print 3.0;
+        print 0.1 + 0.2;
+        print 1 / 3;
+        print 10 ** 20;
+        print 10 ** 21;
+        print 0.000001;
+        print 0.0000001;
+        print 1e308 * 10;
+        print -1e308 * 10;
+    "#,
+    );
+
+    assert_eq!(output[0], "3");
+    assert_eq!(output[1], "0.30000000000000004");
+    assert_eq!(output[2], "0.3333333333333333");
+    assert_eq!(output[3], "100000000000000000000");
+    assert_eq!(output[4], "1e21");
+    assert_eq!(output[5], "0.000001");
+    assert_eq!(output[6], "1e-7");
+    assert_eq!(output[7], "Infinity");
+    assert_eq!(output[8], "-Infinity");
+}
+
+#[test]
+fn operator_methods() {
+    let output = utils::execute(
+        r#"
+        class Hours {
+            init(value) {
+                this.value = value;
+            }
+
+            __mod__(other) {
+                return Hours(this.value % other);
+            }
+        }
+
+        print (Hours(30) % 24).value;
+    "#,
+    );
+
+    assert_eq!(output[0], "6");
+}
@@ -0,0 +1,179 @@
+extern crate rlox;
+mod utils;
+use utils::execute;
+
+#[test]
+fn arithmetic_operators_call_methods() {
+    let output = execute(
+        r#"
+        class Vector {
+            init(x, y) {
+                this.x = x;
+                this.y = y;
+            }
+
+            __add__(other) {
+                return Vector(this.x + other.x, this.y + other.y);
+            }
+
+            __sub__(other) {
+                return Vector(this.x - other.x, this.y - other.y);
+            }
+
+            __mul__(scale) {
+                return Vector(this.x * scale, this.y * scale);
+            }
+
+            __div__(scale) {
+                return Vector(this.x / scale, this.y / scale);
+            }
+
+            toString() {
+                return "(" + this.x + ", " + this.y + ")";
+            }
+        }
+
+        var a = Vector(1, 2);
+        var b = Vector(3, 4);
+        print a + b;
+        print b - a;
+        print a * 3;
+        print b / 2;
+    "#,
+    );
+
+    assert_eq!(output, vec!["(4, 6)", "(2, 2)", "(3, 6)", "(1.5, 2)"]);
+}
+
+#[test]
+fn comparison_operators_call_methods() {
+    let output = execute(
+        r#"
+        class Money {
+            init(cents) {
+                this.cents = cents;
+            }
+
+            __lt__(other) { return this.cents < other.cents; }
+            __le__(other) { return this.cents <= other.cents; }
+            __gt__(other) { return this.cents > other.cents; }
+            __ge__(other) { return this.cents >= other.cents; }
+        }
+
+        var cheap = Money(100);
+        var expensive = Money(200);
+        print cheap < expensive;
+        print cheap <= expensive;
+        print cheap > expensive;
+        print cheap >= expensive;
+    "#,
+    );
+
+    assert_eq!(output, vec!["true", "true", "false", "false"]);
+}
+
+#[test]
+fn equality_calls_eq_method() {
+    let output = execute(
+        r#"
+        class Money {
+            init(cents) {
+                this.cents = cents;
+            }
+
+            __eq__(other) {
+                return this.cents == other.cents;
+            }
+        }
+
+        print Money(1) == Money(1);
+        print Money(1) != Money(1);
+        print Money(1) == Money(2);
+    "#,
+    );
+
+    assert_eq!(output, vec!["true", "false", "false"]);
+}
+
+#[test]
+fn equality_without_eq_method_compares_identity() {
+    let output = execute(
+        r#"
+        class Something {}
+        var a = Something();
+        print a == a;
+        print a == Something();
+        print a != Something();
+    "#,
+    );
+
+    assert_eq!(output, vec!["true", "false", "true"]);
+}
+
+#[test]
+fn operator_methods_are_inherited() {
+    let output = execute(
+        r#"
+        class Base {
+            __add__(other) {
+                return "added " + other;
+            }
+        }
+
+        class Derived < Base {}
+
+        print Derived() + 1;
+    "#,
+    );
+
+    assert_eq!(output[0], "added 1");
+}
+
+#[test]
+fn missing_operator_method() {
+    let output = execute(
+        r#"
+        class Something {}
+        print Something() - 1;
+    "#,
+    );
+
+    assert_eq!(
+        output[0],
+        "[line 3] Class `Something` has no `__sub__` method to support `-`."
+    );
+}
+
+#[test]
+fn plus_with_string_falls_back_to_conversion() {
+    let output = execute(
+        r#"
+        class Something {
+            toString() {
+                return "something";
+            }
+        }
+
+        print Something() + "!";
+    "#,
+    );
+
+    assert_eq!(output[0], "something!");
+}
+
+#[test]
+fn operator_method_with_wrong_arity() {
+    let output = execute(
+        r#"
+        class Something {
+            __add__() {
+                return 1;
+            }
+        }
+
+        print Something() + 1;
+    "#,
+    );
+
+    assert!(output[0].contains("expected 0 arguments but got 1"));
+}
@@ -0,0 +1,101 @@
+extern crate rlox;
+
+use std::fs;
+
+use rlox::RunOptions;
+
+fn same_result(code: &str) -> rlox::RunResult {
+    let expected = rlox::run_string(code.to_string());
+    let options = RunOptions {
+        optimize: true,
+        ..RunOptions::default()
+    };
+    let result = rlox::run_string_with_options(code.to_string(), options);
+
+    assert_eq!(result.output, expected.output);
+    assert_eq!(result.to_string(), expected.to_string());
+    assert_eq!(result.exit_code, expected.exit_code);
+
+    result
+}
+
+#[test]
+fn samples_run_the_same() {
+    for entry in fs::read_dir("samples").unwrap() {
+        let code = fs::read_to_string(entry.unwrap().path()).unwrap();
+        same_result(&code);
+    }
+}
+
+#[test]
+fn folded_expressions() {
+    let result = same_result(
+        r#"
+        var seconds = 0;
+        for (var i = 0; i < 3; i = i + 1) seconds = seconds + 60 * 60 * 24;
+        print seconds;
+        print "a" + "b" + 1;
+        print 1 + "a";
+        print (2 + 3) * 4 == 20;
+        print -(-3) + ~1 | 8;
+        print 2 ** 0.5;
+        print true and "yes";
+        print nil or "fallback";
+        print 0 and "zero";
+        print 1 > 2 ? "big" : "small";
+        print !!nil;
+        var value = "something";
+        print !!value;
+        if (!!value) print "truthy";
+        while (!!!value) print "unreachable";
+    "#,
+    );
+
+    assert_eq!(
+        result.output,
+        "259200\nab1\n1a\ntrue\n9\n1.4142135623730951\nyes\nfallback\nzero\nsmall\n\
+         false\ntrue\ntruthy\n"
+    );
+}
+
+#[test]
+fn dead_code() {
+    let result = same_result(
+        r#"
+        if (false) print "never"; else print "else";
+        if (nil) { print "never"; }
+        if (1) print "always";
+        while (false) print "never";
+        for (var i = 0; false; i = i + 1) print "never";
+
+        fun numbers() {
+            yield 1;
+            if (false) yield 2;
+        }
+
+        fun nothing() {
+            if (false) yield 1;
+        }
+
+        print nothing();
+        for (n in numbers()) print n;
+    "#,
+    );
+
+    assert_eq!(result.output, "else\nalways\n<generator nothing>\n1\n");
+}
+
+#[test]
+fn errors_stay_where_they_were() {
+    let result = same_result("print 1;\nprint 1 /\n  0;");
+    assert_eq!(result.to_string(), "[line 2] Cannot divide by zero");
+
+    let result = same_result("print 1 % 0;");
+    assert_eq!(result.error_reports()[0].variant, "DivideByZeroError");
+
+    same_result("print \"a\" - 1;");
+    same_result("print -\"a\";");
+    same_result("print 0 / 0 + 1;");
+    same_result("print 1.5 & 1;");
+    same_result("if (true) print undefined;");
+}
@@ -0,0 +1,134 @@
+extern crate rlox;
+
+use std::io::Cursor;
+
+fn repl(input: &str) -> (String, String, i32) {
+    let mut input = Cursor::new(input.as_bytes().to_vec());
+    let mut output: Vec<u8> = Vec::new();
+    let mut errors: Vec<u8> = Vec::new();
+    let exit_code = rlox::run_repl(&mut input, &mut output, &mut errors);
+
+    let output = String::from_utf8(output).unwrap();
+    // Drop the welcome message
+    let output = output.splitn(2, "\n\n").nth(1).unwrap().to_string();
+
+    (output, String::from_utf8(errors).unwrap(), exit_code)
+}
+
+#[test]
+fn shows_the_value_of_expressions() {
+    let (output, errors, exit_code) = repl(
+        r#"var x = 1;
+x + 2
+"two";
+x = 5;
+print x;
+nil
+[x, "x"]
+"#,
+    );
+
+    assert_eq!(output, ">> >> 3\n>> \"two\"\n>> >> 5\n>> >> [5, \"x\"]\n>> ");
+    assert_eq!(errors, "");
+    assert_eq!(exit_code, 0);
+}
+
+#[test]
+fn entries_can_span_several_lines() {
+    let (output, _, _) = repl(
+        r#"fun double(n) {
+  return n * 2;
+}
+double(
+  21
+)
+print "first
+second";
+"#,
+    );
+
+    assert_eq!(output, ">> .. .. >> .. .. 42\n>> .. first\nsecond\n>> ");
+}
+
+#[test]
+fn errors_dont_end_the_session() {
+    let (output, errors, exit_code) = repl("print undefined;\nprint (;\n\nprint 1;\n");
+
+    assert_eq!(output, ">> >> .. >> 1\n>> ");
+    assert_eq!(
+        errors,
+        "[line 1] Undefined variable `undefined`\n[line 1] UnexpectedTokenError: Unexpected token ;\n"
+    );
+    assert_eq!(exit_code, 0);
+}
+
+#[test]
+fn meta_commands() {
+    let (output, errors, _) = repl(
+        ".load samples/recursion.lox\nfibo(6)\n.reset\nfibo\n.load missing.lox\n.nope\n",
+    );
+
+    assert_eq!(output, ">> 8\n>> 13\n>> Session reset\n>> >> >> >> ");
+    assert_eq!(
+        errors.lines().collect::<Vec<_>>(),
+        vec![
+            "[line 1] Undefined variable `fibo`",
+            "Could not read missing.lox: No such file or directory (os error 2)",
+            "Unknown command `.nope`, type .help for a list of commands",
+        ]
+    );
+
+    let (output, _, _) = repl(".help\n");
+    assert!(output.contains(".load <file>         Run a file in the current session"));
+}
+
+#[test]
+fn exit_ends_the_session() {
+    let (output, _, exit_code) = repl("print 1;\nexit(3);\nprint 2;\n");
+
+    assert_eq!(output, ">> 1\n>> ");
+    assert_eq!(exit_code, 3);
+}
+
+#[test]
+fn inspection_commands() {
+    let (output, errors, _) = repl(
+        r#".vars
+class Shape { init(name) { this.name = name; } area { return 0; } class unit() {} }
+class Square < Shape { init(side, name = "square") { super.init(name); } scale(...by) {} }
+var sq = Square(2);
+.vars
+.type sq
+.type sq.name
+.type Square
+.type clock
+.methods Square
+.methods sq
+.type
+"#,
+    );
+
+    assert_eq!(
+        output,
+        r#">> No variables defined
+>> >> >> >> Shape = class <Shape>
+Square = class <Square>
+sq = instance of <Square>
+>> instance of Square
+>> string
+>> class
+>> native function
+>> Square
+  init(side, name = "square")
+  scale(...by)
+Shape
+  area
+  init(name)
+  static unit()
+>> >> >> "#
+    );
+    assert_eq!(
+        errors.lines().collect::<Vec<_>>(),
+        vec!["`sq` is not a class", "Usage: .type <expression>"]
+    );
+}
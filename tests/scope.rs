@@ -26,3 +26,48 @@ fn closure_environment_mutation() {
         "Assignments to enclosed variable shouldn't change function closure environment"
     );
 }
+
+#[test]
+fn locals_in_nested_scopes() {
+    let output = utils::execute(
+        r#"
+        var a = "global a";
+        fun outer(b) {
+            var c = "outer c";
+            {
+                var d = "block d";
+                fun inner() {
+                    c = c + "!";
+                    return a + ", " + b + ", " + c + ", " + d;
+                }
+                print inner();
+            }
+            print c;
+        }
+        outer("param b");
+    "#,
+    );
+
+    assert_eq!(output[0], "global a, param b, outer c!, block d");
+    assert_eq!(output[1], "outer c!");
+}
+
+#[test]
+fn locals_cant_be_declared_twice_in_the_same_scope() {
+    let result = rlox::run_string("{ var a = 1; var a = a + 1; print a; }".to_string());
+    assert_eq!(result.exit_code, 65);
+    assert_eq!(
+        result.to_string(),
+        "[line 1] UnexpectedTokenError: A variable named `a` is already declared in this scope."
+    );
+
+    let result = rlox::run_string("fun f(a) { var a = 2; } class C {} { class C {} }".to_string());
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors[0].variant(), "VariableAlreadyDeclared");
+
+    // Globals and variables of nested scopes can still be redeclared or shadowed
+    let output = utils::execute(
+        "var a = 1; var a = a + 1; { var a = 3; { var b = a; var a = b + 1; print a; } } print a;",
+    );
+    assert_eq!(output, vec!["4", "2"]);
+}
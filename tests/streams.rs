@@ -0,0 +1,76 @@
+extern crate rlox;
+mod utils;
+
+#[test]
+fn output_and_errors_are_separate() {
+    let output = utils::execute_with_input(
+        r#"
+        print "out";
+        eprint("err");
+        eprint([1, "two"]);
+
+        match (1) {
+            _ => print "matched";
+            1 => print "unreachable";
+        }
+    "#,
+        "",
+    );
+
+    assert_eq!(output.stdout, "out\nmatched\n");
+    assert_eq!(
+        output.stderr,
+        "[line 8] Warning: Unreachable match arm after `_`\nerr\n[1, \"two\"]\n"
+    );
+}
+
+#[test]
+fn runtime_errors_go_to_the_error_output() {
+    let output = utils::execute_with_input(
+        r#"
+        print "before";
+        nil + 1;
+        print "after";
+    "#,
+        "",
+    );
+
+    assert_eq!(output.stdout, "before\n");
+    assert_eq!(
+        output.stderr,
+        "[line 3] Both sides of an addition must be either strings or numbers\n"
+    );
+}
+
+#[test]
+fn reads_from_the_given_input() {
+    let output = utils::execute_with_input(
+        r#"
+        var name = input("Name: ");
+        print "Hello, " + name;
+
+        var line = readLine();
+        while (line != nil) {
+            print "> " + line;
+            line = readLine();
+        }
+    "#,
+        "Lox\nfirst\r\nsecond",
+    );
+
+    assert_eq!(output.stdout, "Name: Hello, Lox\n> first\n> second\n");
+    assert_eq!(output.stderr, "");
+}
+
+#[test]
+fn run_string_keeps_a_single_stream() {
+    let output = utils::execute(
+        r#"
+        print 1;
+        eprint(2);
+        print 3;
+    "#,
+    );
+
+    assert_eq!(output, vec!["1", "2", "3"]);
+}
@@ -0,0 +1,67 @@
+extern crate rlox;
+mod utils;
+use utils::execute;
+
+#[test]
+fn escape_sequences() {
+    let output = execute(r#"print "say \"hi\"\tnow";"#);
+
+    assert_eq!(output[0], "say \"hi\"\tnow");
+}
+
+#[test]
+fn newline_escape_splits_output() {
+    let output = execute(r#"print "first\nsecond";"#);
+
+    assert_eq!(output[0], "first");
+    assert_eq!(output[1], "second");
+}
+
+#[test]
+fn invalid_escape_sequence() {
+    let output = execute(r#"print "\q";"#);
+
+    assert_eq!(output[0], "[line 1] ScannerError: Invalid escape sequence: \\q");
+}
+
+#[test]
+fn interpolation() {
+    let output = execute(
+        r#"
+        var name = "Ann";
+        var age = 30;
+        print "Hello ${name}, you are ${age}";
+    "#,
+    );
+
+    assert_eq!(output[0], "Hello Ann, you are 30");
+}
+
+#[test]
+fn interpolation_of_expressions() {
+    let output = execute(
+        r#"
+        fun double(n) {
+            return n * 2;
+        }
+
+        print "${double(2) + 1} is odd: ${true}";
+    "#,
+    );
+
+    assert_eq!(output[0], "5 is odd: true");
+}
+
+#[test]
+fn nested_interpolation() {
+    let output = execute(r#"print "a ${ "b ${ "c" }" }";"#);
+
+    assert_eq!(output[0], "a b c");
+}
+
+#[test]
+fn escaped_interpolation() {
+    let output = execute(r#"print "\${not interpolated}";"#);
+
+    assert_eq!(output[0], "${not interpolated}");
+}
@@ -0,0 +1,98 @@
+extern crate rlox;
+mod utils;
+
+use std::rc::Rc;
+
+use rlox::ManualClock;
+
+#[test]
+fn clock_has_fractional_seconds() {
+    let clock = Rc::new(ManualClock::new(1500.0));
+    let output = utils::execute_with_clock(
+        r#"
+        print clock();
+        print millis();
+    "#,
+        clock.clone(),
+    );
+
+    assert_eq!(output, vec!["1.5", "1500"]);
+
+    clock.advance(0.75);
+    let output = utils::execute_with_clock("print millis();", clock);
+    assert_eq!(output, vec!["1500"]);
+}
+
+#[test]
+fn nanos_measures_elapsed_time() {
+    let output = utils::execute_with_clock(
+        r#"
+        var start = nanos();
+
+        setTimeout(fun () {
+            print nanos() - start;
+        }, 20);
+    "#,
+        Rc::new(ManualClock::new(0.0)),
+    );
+
+    assert_eq!(output, vec!["20000000"]);
+}
+
+#[test]
+fn system_clock() {
+    let output = utils::execute(
+        r#"
+        var start = nanos();
+        var seconds = clock();
+        print seconds > 1600000000;
+        print millis() >= seconds * 1000 - 1;
+        print nanos() >= start;
+    "#,
+    );
+
+    assert_eq!(output, vec!["true", "true", "true"]);
+}
+
+#[test]
+fn format_time() {
+    let output = utils::execute(
+        r#"
+        print formatTime(0);
+        print formatTime(951829509.25, "%d/%m/%Y %H:%M:%S.%L 100%%");
+        print formatTime(-1);
+        print formatTime(253402300799);
+    "#,
+    );
+
+    assert_eq!(
+        output,
+        vec![
+            "1970-01-01 00:00:00",
+            "29/02/2000 13:05:09.250 100%",
+            "1969-12-31 23:59:59",
+            "9999-12-31 23:59:59",
+        ]
+    );
+
+    let output = utils::execute_with_clock(
+        "print formatTime(clock(), \"%Y-%m-%d\");",
+        Rc::new(ManualClock::new(1_700_000_000_000.0)),
+    );
+    assert_eq!(output, vec!["2023-11-14"]);
+}
+
+#[test]
+fn format_time_errors() {
+    let output = utils::execute("formatTime(0, \"%Q\");");
+    assert_eq!(
+        output[0],
+        "Invalid argument for `formatTime`, unknown format `%Q`."
+    );
+
+    let output = utils::execute("formatTime(\"now\");");
+    assert_eq!(
+        output[0],
+        "Invalid argument for `formatTime`, expected a time in seconds."
+    );
+}
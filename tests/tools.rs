@@ -0,0 +1,124 @@
+extern crate rlox;
+
+#[test]
+fn tokens() {
+    let tokens = rlox::tokens("var x = \"a\";\nprint x;".to_string()).unwrap();
+
+    assert_eq!(
+        tokens,
+        "1 Var var\n1 Identifier x\n1 Equal =\n1 String \"a\"\n1 Semicolon ;\n\
+         2 Print print\n2 Identifier x\n2 Semicolon ;\n2 Eof \n"
+    );
+}
+
+#[test]
+fn ast() {
+    let ast = rlox::ast(
+        r#"
+        fun add(a, b = 1) { return a + b; }
+        class Point < Base { x { return 1; } }
+        for (n in [1, add(2)]) if (n > 1) print n; else print -n;
+    "#.to_string(),
+    ).unwrap();
+
+    assert_eq!(
+        ast,
+        "(fun add (a (= b 1)) (block (return (+ (var a) (var b)))))\n\
+         (class Point < (var Base) (get x () (block (return 1))))\n\
+         (for-in n (list 1 (call (var add) 2)) (if (> (var n) 1) (print (var n)) (print (- (var n)))))\n"
+    );
+}
+
+#[test]
+fn check() {
+    let warnings = rlox::check("match (1) { _ => print 1; 1 => print 2; }".to_string()).unwrap();
+    assert_eq!(warnings, vec!["[line 1] Warning: Unreachable match arm after `_`"]);
+
+    let errors = rlox::check("print (;".to_string()).unwrap_err();
+    assert_eq!(errors[0].exit_code(), 65);
+}
+
+#[test]
+fn format_code() {
+    let formatted = rlox::format_code(
+        r#"// Comments are kept
+var   list=[1,2,...rest];   // after code too
+fun add(a,b){
+return a+b;}
+
+
+class Counter<Base{
+init(){this.count=-1;}
+}
+for(var i=0;i<3;i++) print "${i+1}: ${list[i]}";
+promise
+.then(fun (value) { print !value; });
+"#.to_string(),
+    ).unwrap();
+
+    assert_eq!(
+        formatted,
+        r#"// Comments are kept
+var list = [1, 2, ...rest]; // after code too
+fun add(a, b) {
+  return a + b;
+}
+
+class Counter < Base {
+  init() { this.count = -1; }
+}
+for (var i = 0; i < 3; i++) print "${i + 1}: ${list[i]}";
+promise
+  .then(fun (value) { print !value; });
+"#
+    );
+
+    assert_eq!(rlox::format_code(formatted.clone()).unwrap(), formatted);
+}
+
+#[test]
+fn format_needs_valid_code() {
+    assert!(rlox::format_code("print (;".to_string()).is_err());
+}
+
+#[test]
+fn format_keeps_prefix_operators_apart() {
+    let code = "var x = 2;\nprint - -x;\nprint - -1;\nprint - --x;\nprint x - -1;\n".to_string();
+    let formatted = rlox::format_code(code.clone()).unwrap();
+
+    assert_eq!(formatted, code);
+    assert_eq!(
+        rlox::run_string(formatted).to_string(),
+        rlox::run_string(code).to_string()
+    );
+}
+
+#[test]
+fn format_wrapped_lines() {
+    let formatted = rlox::format_code(
+        r#"fun outer(a,
+b) {
+return a +
+b;
+}
+var x = outer(1,
+2) + outer([3,
+4], 5);
+"#.to_string(),
+    ).unwrap();
+
+    assert_eq!(
+        formatted,
+        r#"fun outer(a,
+  b) {
+  return a +
+    b;
+}
+var x = outer(1,
+  2) + outer([3,
+  4], 5);
+"#
+    );
+
+    assert_eq!(rlox::format_code(formatted.clone()).unwrap(), formatted);
+}
@@ -1,7 +1,54 @@
 extern crate rlox;
 
+use std::rc::Rc;
+
+use self::rlox::{Capabilities, Capability, ManualClock, Output, RunOptions};
+
 pub fn execute(code: &str) -> Vec<String> {
-    rlox::run_string(code.to_string())
+    lines(rlox::run_string(code.to_string()).to_string())
+}
+
+/// Runs the code with the given clock instead of the system time, so anything depending on
+/// time runs instantly and always the same way.
+#[allow(dead_code)]
+pub fn execute_with_clock(code: &str, clock: Rc<ManualClock>) -> Vec<String> {
+    let options = RunOptions {
+        clock,
+        ..RunOptions::default()
+    };
+    lines(rlox::run_string_with_options(code.to_string(), options).to_string())
+}
+
+/// Runs the code allowing it to use the given capabilities, with `args` as its command line
+/// arguments.
+#[allow(dead_code)]
+pub fn execute_with_capabilities(
+    code: &str,
+    capabilities: Capabilities,
+    args: Vec<&str>,
+) -> Vec<String> {
+    let options = RunOptions {
+        capabilities,
+        args: args.into_iter().map(|arg| arg.to_string()).collect(),
+        ..RunOptions::default()
+    };
+    lines(rlox::run_string_with_options(code.to_string(), options).to_string())
+}
+
+/// Runs the code with `input` as what it reads, which it's allowed to do, keeping its output
+/// and error output apart.
+#[allow(dead_code)]
+pub fn execute_with_input(code: &str, input: &str) -> Output {
+    let options = RunOptions {
+        capabilities: Capabilities::none().allow(Capability::Input),
+        input: input.to_string(),
+        ..RunOptions::default()
+    };
+    rlox::run_string_with_error_output(code.to_string(), options)
+}
+
+fn lines(output: String) -> Vec<String> {
+    output
         .split("\n")
         .filter(|s| !s.is_empty())
         .map(|s| s.trim().to_string())
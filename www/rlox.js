@@ -57,7 +57,10 @@ export default function getInterpreter() {
     .then(response => response.arrayBuffer())
     .then(buffer =>
       WebAssembly.instantiate(buffer, {
-        env: { get_current_js_time: () => Math.floor(Date.now() / 1000) }
+        env: {
+          get_current_js_time: () => Date.now(),
+          get_monotonic_js_time: () => performance.now()
+        }
       })
     )
     .then(results => new RloxInterpreter(results.instance.exports));